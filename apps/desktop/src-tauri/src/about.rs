@@ -0,0 +1,92 @@
+//! About panel for YULA Desktop
+//!
+//! macOS gets the native About panel filled with our metadata; other
+//! platforms receive a `show-about` event carrying the same metadata so the
+//! frontend can render its own.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Credits shown in the About panel
+const CREDITS: &str = env!("CARGO_PKG_DESCRIPTION");
+
+/// Metadata shown in the About panel, also the `show-about` payload
+#[derive(Debug, Clone, Serialize)]
+pub struct AboutInfo {
+    pub name: String,
+    pub version: String,
+    pub credits: String,
+    pub copyright: Option<String>,
+}
+
+fn about_info<R: Runtime>(app: &AppHandle<R>) -> AboutInfo {
+    let package = app.package_info();
+    AboutInfo {
+        name: package.name.clone(),
+        version: package.version.to_string(),
+        credits: CREDITS.to_string(),
+        copyright: app.config().bundle.copyright.clone(),
+    }
+}
+
+/// Show the About panel
+pub fn show<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let info = about_info(app);
+
+    #[cfg(target_os = "macos")]
+    {
+        app.run_on_main_thread(move || macos::show_panel(&info))
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        if let Some(window) = app.get_webview_window(crate::window::MAIN_WINDOW) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        crate::events::emit(app, crate::events::SHOW_ABOUT, info);
+        Ok(())
+    }
+}
+
+/// Show the About panel (native on macOS, rendered by the frontend elsewhere)
+#[tauri::command]
+pub async fn show_about_panel<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    show(&app)
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use objc2_foundation::{NSAttributedString, NSString};
+
+    use super::AboutInfo;
+
+    unsafe fn set(options: &AnyObject, key: &str, value: &AnyObject) {
+        let key = NSString::from_str(key);
+        let _: () = msg_send![options, setObject: value, forKey: &*key];
+    }
+
+    pub fn show_panel(info: &AboutInfo) {
+        unsafe {
+            let options: Retained<AnyObject> = msg_send![class!(NSMutableDictionary), new];
+            let credits = NSAttributedString::from_nsstring(&NSString::from_str(&info.credits));
+
+            set(&options, "ApplicationName", NSString::from_str(&info.name).as_ref());
+            set(&options, "ApplicationVersion", NSString::from_str(&info.version).as_ref());
+            set(&options, "Credits", credits.as_ref());
+            if let Some(copyright) = &info.copyright {
+                set(&options, "Copyright", NSString::from_str(copyright).as_ref());
+            }
+
+            let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            if let Some(app) = app.as_ref() {
+                let _: () = msg_send![app, activateIgnoringOtherApps: true];
+                let _: () = msg_send![app, orderFrontStandardAboutPanelWithOptions: &*options];
+            }
+        }
+    }
+}
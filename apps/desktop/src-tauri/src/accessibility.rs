@@ -0,0 +1,198 @@
+//! Accessibility preferences for YULA Desktop
+//!
+//! Exposes the OS "reduce motion" and high-contrast settings so the frontend
+//! can disable animations and switch stylesheets. The system values are
+//! polled, and `reduce-motion-changed` / `high-contrast-changed` are emitted
+//! whenever they flip.
+//!
+//! Precedence: an app-level override set with `set_reduce_motion_override`
+//! always wins; without one the OS setting applies, and where the OS setting
+//! can't be queried the default is `false`. High contrast has no override
+//! and is likewise `false` where undetectable.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// How often the OS setting is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Read the OS reduce-motion setting, `None` if it can't be queried
+fn system_reduce_motion() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        unsafe {
+            let workspace: *mut AnyObject =
+                objc2::msg_send![objc2::class!(NSWorkspace), sharedWorkspace];
+            let workspace = workspace.as_ref()?;
+            let reduce: bool = objc2::msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+            Some(reduce)
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::BOOL;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+        };
+
+        // "Show animations in Windows" off means the user wants less motion
+        let mut animations = BOOL::default();
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETCLIENTAREAANIMATION,
+                0,
+                Some(&mut animations as *mut BOOL as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .ok()?;
+        Some(!animations.as_bool())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let animations = crate::platform::output(
+            "gsettings",
+            &["get", "org.gnome.desktop.interface", "enable-animations"],
+        )?;
+        Some(animations == "false")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    None
+}
+
+/// Read the OS high-contrast setting, `None` if it can't be queried
+fn system_high_contrast() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        // "Increase contrast" in the accessibility display settings
+        unsafe {
+            let workspace: *mut AnyObject =
+                objc2::msg_send![objc2::class!(NSWorkspace), sharedWorkspace];
+            let workspace = workspace.as_ref()?;
+            let increase: bool =
+                objc2::msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+            Some(increase)
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SystemParametersInfoW, SPI_GETHIGHCONTRAST, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+        };
+
+        let mut high_contrast = HIGHCONTRASTW {
+            cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+            ..Default::default()
+        };
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETHIGHCONTRAST,
+                high_contrast.cbSize,
+                Some(&mut high_contrast as *mut HIGHCONTRASTW as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .ok()?;
+        Some(high_contrast.dwFlags.contains(HCF_HIGHCONTRASTON))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let enabled = crate::platform::output(
+            "gsettings",
+            &["get", "org.gnome.desktop.a11y.interface", "high-contrast"],
+        );
+        if enabled.as_deref() == Some("true") {
+            return Some(true);
+        }
+
+        let theme = crate::platform::output(
+            "gsettings",
+            &["get", "org.gnome.desktop.interface", "gtk-theme"],
+        )?;
+        Some(theme.contains("HighContrast"))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    None
+}
+
+/// Compute the effective reduce-motion value
+pub fn reduce_motion<R: Runtime>(app: &AppHandle<R>) -> bool {
+    crate::settings::get(app)
+        .reduce_motion_override
+        .or_else(system_reduce_motion)
+        .unwrap_or(false)
+}
+
+/// Store the effective values and emit change events for any that flipped
+fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let reduce = reduce_motion(app);
+    let state = app.state::<crate::AppState>();
+    if state.reduce_motion.swap(reduce, Ordering::SeqCst) != reduce {
+        log::info!("Reduce motion changed: {}", reduce);
+        crate::events::emit(app, crate::events::REDUCE_MOTION_CHANGED, reduce);
+    }
+
+    let high_contrast = system_high_contrast().unwrap_or(false);
+    if state.high_contrast.swap(high_contrast, Ordering::SeqCst) != high_contrast {
+        log::info!("High contrast changed: {}", high_contrast);
+        crate::events::emit(app, crate::events::HIGH_CONTRAST_CHANGED, high_contrast);
+    }
+}
+
+/// Start the background monitor that emits the change events
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    state
+        .reduce_motion
+        .store(reduce_motion(app), Ordering::SeqCst);
+    let high_contrast = system_high_contrast().unwrap_or(false);
+    state.high_contrast.store(high_contrast, Ordering::SeqCst);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            crate::tasks::tick(&app, "accessibility", POLL_INTERVAL).await;
+            let handle = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || refresh(&handle)).await;
+        }
+    });
+}
+
+/// Check whether animations should be reduced (override, then OS setting)
+#[tauri::command]
+pub fn get_reduce_motion<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<crate::AppState>()
+        .reduce_motion
+        .load(Ordering::SeqCst)
+}
+
+/// Check whether the OS high-contrast / forced-colors mode is on
+#[tauri::command]
+pub fn get_high_contrast<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<crate::AppState>()
+        .high_contrast
+        .load(Ordering::SeqCst)
+}
+
+/// Override the OS reduce-motion setting, or pass `null` to follow the OS
+#[tauri::command]
+pub async fn set_reduce_motion_override<R: Runtime>(
+    app: AppHandle<R>,
+    reduce_motion: Option<bool>,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.reduce_motion_override = reduce_motion)?;
+    refresh(&app);
+    Ok(())
+}
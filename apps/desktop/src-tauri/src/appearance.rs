@@ -0,0 +1,100 @@
+//! Appearance override for YULA Desktop
+//!
+//! Forces light or dark native chrome regardless of the OS theme, so menus,
+//! dialogs and the tray menu match a theme forced in the app. The override
+//! applies to every window and, on macOS, to `NSApp.appearance`. In
+//! `System` mode the OS theme is followed and its changes are forwarded as
+//! `appearance-changed`.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, Theme, Window};
+
+/// App-level appearance preference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AppearanceMode {
+    /// Follow the OS theme
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl AppearanceMode {
+    fn theme(self) -> Option<Theme> {
+        match self {
+            Self::System => None,
+            Self::Light => Some(Theme::Light),
+            Self::Dark => Some(Theme::Dark),
+        }
+    }
+}
+
+/// Payload of the `appearance-changed` event
+#[derive(Debug, Clone, Serialize)]
+struct AppearancePayload {
+    mode: AppearanceMode,
+    /// Theme in effect after the change
+    theme: Theme,
+}
+
+fn emit_changed<R: Runtime>(app: &AppHandle<R>, mode: AppearanceMode, theme: Theme) {
+    crate::events::emit(
+        app,
+        crate::events::APPEARANCE_CHANGED,
+        AppearancePayload { mode, theme },
+    );
+}
+
+/// Theme in effect, following the main window when the OS decides
+fn effective_theme<R: Runtime>(app: &AppHandle<R>, mode: AppearanceMode) -> Theme {
+    mode.theme().unwrap_or_else(|| {
+        crate::window::main_window(app)
+            .ok()
+            .and_then(|window| window.theme().ok())
+            .unwrap_or(Theme::Light)
+    })
+}
+
+/// Apply the persisted appearance at launch
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let mode = crate::settings::get(app).appearance;
+    if mode != AppearanceMode::System {
+        log::info!("Appearance override: {:?}", mode);
+        app.set_theme(mode.theme());
+    }
+}
+
+/// Forward OS theme changes while following the system appearance
+pub fn on_theme_changed<R: Runtime>(window: &Window<R>, theme: Theme) {
+    // Every window reports the change; the main window speaks for all
+    if window.label() != crate::window::MAIN_WINDOW {
+        return;
+    }
+
+    let app = window.app_handle();
+    let mode = crate::settings::get(app).appearance;
+    if mode == AppearanceMode::System {
+        log::info!("System theme changed: {:?}", theme);
+        emit_changed(app, mode, theme);
+    }
+}
+
+/// Force light or dark native chrome, or follow the OS, and persist it
+#[tauri::command]
+pub async fn set_appearance<R: Runtime>(
+    app: AppHandle<R>,
+    mode: AppearanceMode,
+) -> Result<(), String> {
+    app.set_theme(mode.theme());
+    crate::settings::update(&app, |settings| settings.appearance = mode)?;
+
+    log::info!("Appearance set to {:?}", mode);
+    emit_changed(&app, mode, effective_theme(&app, mode));
+    Ok(())
+}
+
+/// Get the persisted appearance preference
+#[tauri::command]
+pub fn get_appearance<R: Runtime>(app: AppHandle<R>) -> AppearanceMode {
+    crate::settings::get(&app).appearance
+}
@@ -0,0 +1,123 @@
+//! CPU architecture detection for YULA Desktop
+//!
+//! Compares the architecture the binary was built for with the machine's
+//! native one to spot emulation: Rosetta on Apple Silicon, x64 emulation or
+//! WOW64 on Windows, and qemu-style emulation on Linux where `uname` reports
+//! the host. An emulated build gets a one-time notification suggesting the
+//! native download.
+
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+/// Architecture of the running process and the machine
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuArchitectureDetails {
+    /// Architecture of the hardware, e.g. `aarch64`
+    pub native_arch: String,
+    /// Architecture this binary was built for
+    pub process_arch: String,
+    pub is_emulated: bool,
+}
+
+/// Map platform spellings onto Rust's architecture names
+fn normalize(arch: &str) -> String {
+    match arch.trim().to_lowercase().as_str() {
+        "arm64" | "aarch64" => "aarch64".to_string(),
+        "amd64" | "x64" | "x86_64" => "x86_64".to_string(),
+        "i386" | "i686" | "x86" => "x86".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Detect the machine's native architecture, `None` if unknown
+fn native_arch() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        // Rosetta reports x86_64 for most queries, but not this one
+        let translated = crate::platform::output("sysctl", &["-n", "sysctl.proc_translated"]);
+        let arm64 = crate::platform::output("sysctl", &["-n", "hw.optional.arm64"]);
+        if translated.as_deref() == Some("1") || arm64.as_deref() == Some("1") {
+            Some("aarch64".to_string())
+        } else {
+            Some("x86_64".to_string())
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::SystemInformation::{
+            IMAGE_FILE_MACHINE, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64,
+            IMAGE_FILE_MACHINE_I386,
+        };
+        use windows::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+        let mut process = IMAGE_FILE_MACHINE::default();
+        let mut native = IMAGE_FILE_MACHINE::default();
+        unsafe { IsWow64Process2(GetCurrentProcess(), &mut process, Some(&mut native)) }.ok()?;
+        match native {
+            IMAGE_FILE_MACHINE_ARM64 => Some("aarch64".to_string()),
+            IMAGE_FILE_MACHINE_AMD64 => Some("x86_64".to_string()),
+            IMAGE_FILE_MACHINE_I386 => Some("x86".to_string()),
+            _ => None,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        crate::platform::output("uname", &["-m"]).map(|arch| normalize(&arch))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    None
+}
+
+fn details() -> CpuArchitectureDetails {
+    let process_arch = normalize(std::env::consts::ARCH);
+    let native_arch = native_arch().unwrap_or_else(|| process_arch.clone());
+    CpuArchitectureDetails {
+        is_emulated: native_arch != process_arch,
+        native_arch,
+        process_arch,
+    }
+}
+
+/// Suggest the native build once if this one runs under emulation
+pub fn warn_if_emulated<R: Runtime>(app: &AppHandle<R>) {
+    let details = details();
+    if !details.is_emulated || crate::settings::get(app).emulation_warning_shown {
+        return;
+    }
+
+    log::warn!(
+        "Running a {} build on {} hardware",
+        details.process_arch,
+        details.native_arch
+    );
+    let body = format!(
+        "This is the {} build. Download the {} version for better performance and battery life.",
+        details.process_arch, details.native_arch
+    );
+    if let Err(e) = crate::notifications::notify(app, "Native version available", &body, None) {
+        log::warn!("Failed to show emulation notice: {}", e);
+        return;
+    }
+    if let Err(e) = crate::settings::update(app, |settings| settings.emulation_warning_shown = true)
+    {
+        log::warn!("Failed to save emulation notice state: {}", e);
+    }
+}
+
+/// Get the process and native CPU architectures and whether we're emulated
+#[tauri::command]
+pub async fn get_cpu_architecture_details() -> CpuArchitectureDetails {
+    tauri::async_runtime::spawn_blocking(details)
+        .await
+        .unwrap_or_else(|_| {
+            let arch = normalize(std::env::consts::ARCH);
+            CpuArchitectureDetails {
+                native_arch: arch.clone(),
+                process_arch: arch,
+                is_emulated: false,
+            }
+        })
+}
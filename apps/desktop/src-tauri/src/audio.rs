@@ -0,0 +1,190 @@
+//! Microphone input levels for YULA Desktop
+//!
+//! Opens the default input device with `cpal` and emits `audio-level` about
+//! 20 times a second with the RMS and peak of the samples since the last
+//! event, so the UI can show a live meter and confirm the microphone works
+//! before recording. Audio stays in this process; only levels are emitted.
+//! Streams aren't `Send` on every platform, so each one lives on a thread
+//! of its own until it's stopped or the app quits.
+
+use serde::Serialize;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::CommandError;
+
+/// Time between `audio-level` events
+const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for the device to open
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload of the `audio-level` event, both between 0 and 1
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct AudioLevel {
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// Samples seen since the last event
+#[derive(Debug, Default)]
+struct Accumulator {
+    sum_squares: f64,
+    peak: f32,
+    count: u64,
+}
+
+impl Accumulator {
+    fn add(&mut self, sample: f32) {
+        self.sum_squares += (sample as f64) * (sample as f64);
+        self.peak = self.peak.max(sample.abs());
+        self.count += 1;
+    }
+
+    fn take(&mut self) -> AudioLevel {
+        let level = AudioLevel {
+            rms: (self.sum_squares / self.count.max(1) as f64).sqrt() as f32,
+            peak: self.peak.min(1.0),
+        };
+        *self = Self::default();
+        level
+    }
+}
+
+/// The running level monitor
+#[derive(Debug)]
+pub struct AudioLevelMonitor {
+    stop: mpsc::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    levels: Arc<Mutex<Accumulator>>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    use cpal::traits::DeviceTrait;
+
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let mut levels = levels.lock().unwrap();
+            for &sample in data {
+                levels.add(sample.to_sample::<f32>());
+            }
+        },
+        |e| log::warn!("Audio input error: {}", e),
+        None,
+    )
+}
+
+/// Open the default input device and start capturing from it
+fn open(levels: Arc<Mutex<Accumulator>>) -> Result<cpal::Stream, String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use cpal::SampleFormat;
+
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or("No microphone is connected")?;
+    let supported = device.default_input_config().map_err(|e| e.to_string())?;
+    let config = supported.config();
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, levels),
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, levels),
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, levels),
+        SampleFormat::I32 => build_stream::<i32>(&device, &config, levels),
+        format => return Err(format!("Unsupported sample format: {}", format)),
+    }
+    .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Monitoring audio levels on {} ({} Hz)",
+        device.name().unwrap_or_default(),
+        config.sample_rate.0
+    );
+    Ok(stream)
+}
+
+/// Stop monitoring if it's running, closing the device
+pub fn stop<R: Runtime>(app: &AppHandle<R>) {
+    let monitor = app
+        .try_state::<crate::AppState>()
+        .and_then(|state| state.audio_levels.lock().unwrap().take());
+    if let Some(monitor) = monitor {
+        let _ = monitor.stop.send(());
+        let _ = monitor.thread.join();
+        log::info!("Audio level monitoring stopped");
+    }
+}
+
+/// Emit `audio-level` about 20 times a second from the default microphone
+///
+/// Fails with `PermissionDenied` if microphone access was refused. Does
+/// nothing if monitoring is already running.
+#[tauri::command]
+pub async fn start_audio_level_monitoring<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<(), CommandError> {
+    if crate::permissions::microphone_denied() {
+        return Err(CommandError::PermissionDenied(
+            "Microphone access was denied".to_string(),
+        ));
+    }
+    let state = app.state::<crate::AppState>();
+    if state.audio_levels.lock().unwrap().is_some() {
+        return Ok(());
+    }
+
+    let (opened_tx, opened_rx) = mpsc::channel();
+    let (stop, stop_rx) = mpsc::channel::<()>();
+    let handle = app.clone();
+    let thread = std::thread::spawn(move || {
+        let levels = Arc::new(Mutex::new(Accumulator::default()));
+        let stream = match open(levels.clone()) {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = opened_tx.send(Err(e));
+                return;
+            }
+        };
+        let _ = opened_tx.send(Ok(()));
+
+        while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(LEVEL_INTERVAL) {
+            let level = levels.lock().unwrap().take();
+            crate::events::emit(&handle, crate::events::AUDIO_LEVEL, level);
+        }
+        // Closes the device
+        drop(stream);
+    });
+
+    let opened =
+        tauri::async_runtime::spawn_blocking(move || opened_rx.recv_timeout(OPEN_TIMEOUT))
+            .await
+            .map_err(|e| e.to_string())?;
+    match opened {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => return Err(CommandError::Failed(e)),
+        Err(_) => {
+            let _ = stop.send(());
+            return Err(CommandError::Failed(
+                "The microphone didn't open in time".to_string(),
+            ));
+        }
+    }
+
+    *state.audio_levels.lock().unwrap() = Some(AudioLevelMonitor { stop, thread });
+    Ok(())
+}
+
+/// Stop emitting `audio-level` and close the microphone
+#[tauri::command]
+pub fn stop_audio_level_monitoring<R: Runtime>(app: AppHandle<R>) {
+    stop(&app);
+}
@@ -0,0 +1,223 @@
+//! Idle auto-lock for YULA Desktop
+//!
+//! On shared machines the app can lock itself after the system has had no
+//! keyboard or mouse input for a while, or after the machine slept. Locking
+//! emits `auto-lock`, marks the user as signed out and clears links that
+//! may carry tokens, and optionally hides the windows; the frontend then
+//! asks for re-authentication. The lock fires once per idle period and is
+//! armed again by the next input.
+//!
+//! Idle time comes from the OS: the HID idle counter on macOS,
+//! `GetLastInputInfo` on Windows, and Mutter's idle monitor or
+//! `xprintidle` on Linux. Sleep shows up as the wall clock running ahead of
+//! the monotonic clock, which stops while the machine is suspended.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// How often idle time is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Gap between the wall and monotonic clocks that means the machine slept
+const SLEEP_GAP: Duration = Duration::from_secs(60);
+
+/// Longest configurable idle period, one day
+const MAX_IDLE_MINUTES: u32 = 24 * 60;
+
+/// Persisted auto-lock configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoLockSettings {
+    pub enabled: bool,
+    /// Minutes without input before locking
+    pub idle_minutes: u32,
+    /// Hide every window when locking
+    pub hide_windows: bool,
+}
+
+impl Default for AutoLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 15,
+            hide_windows: false,
+        }
+    }
+}
+
+/// Why the app locked
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum AutoLockReason {
+    Idle,
+    Sleep,
+}
+
+/// Payload of the `auto-lock` event
+#[derive(Debug, Clone, Serialize)]
+struct AutoLockPayload {
+    reason: AutoLockReason,
+}
+
+/// Seconds since the last keyboard or mouse input, `None` if unknown
+fn system_idle_secs() -> Option<u64> {
+    #[cfg(target_os = "macos")]
+    {
+        #[link(name = "CoreGraphics", kind = "framework")]
+        extern "C" {
+            fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+        }
+
+        // Combined session state, any input event type
+        let secs = unsafe { CGEventSourceSecondsSinceLastEventType(0, u32::MAX) };
+        (secs >= 0.0).then_some(secs as u64)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::SystemInformation::GetTickCount;
+        use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return None;
+        }
+        // Both are 32-bit millisecond tick counts that wrap together
+        let idle_ms = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+        Some(u64::from(idle_ms) / 1000)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // GNOME on Wayland and X11, e.g. `(uint64 12345,)`
+        let mutter = crate::platform::output(
+            "gdbus",
+            &[
+                "call",
+                "--session",
+                "--dest",
+                "org.gnome.Mutter.IdleMonitor",
+                "--object-path",
+                "/org/gnome/Mutter/IdleMonitor/Core",
+                "--method",
+                "org.gnome.Mutter.IdleMonitor.GetIdletime",
+            ],
+        );
+        let idle_ms = mutter
+            .as_deref()
+            .and_then(|reply| reply.split_whitespace().nth(1))
+            .and_then(|ms| ms.trim_end_matches([',', ')']).parse::<u64>().ok())
+            .or_else(|| crate::platform::output("xprintidle", &[])?.parse().ok())?;
+        Some(idle_ms / 1000)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    None
+}
+
+/// Lock the app: sign out, clear sensitive state and tell the frontend
+fn lock<R: Runtime>(app: &AppHandle<R>, reason: AutoLockReason) {
+    log::info!("Auto-lock: {:?}", reason);
+    let state = app.state::<crate::AppState>();
+    state.is_authenticated.store(false, Ordering::SeqCst);
+    crate::deep_link::clear_deep_link_history(app.clone());
+
+    // A kiosk must stay on screen; the frontend shows its lock screen instead
+    if crate::settings::get(app).auto_lock.hide_windows && !crate::kiosk::is_active(app) {
+        for window in app.webview_windows().values() {
+            if let Err(e) = crate::window::hide(&window.as_ref().window()) {
+                log::warn!("Failed to hide window {} on lock: {}", window.label(), e);
+            }
+        }
+    }
+
+    crate::events::emit(app, crate::events::AUTO_LOCK, AutoLockPayload { reason });
+}
+
+/// Start the background monitor that locks on idle and after sleep
+///
+/// Unlike the other monitors this one ignores throttling and background
+/// suspension; a lock that waits for the window to come back is no lock.
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Cleared by a lock, set again once the user is back
+        let mut armed = true;
+        let mut last = (Instant::now(), SystemTime::now());
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let now = (Instant::now(), SystemTime::now());
+            let wall = now.1.duration_since(last.1).unwrap_or_default();
+            let slept = wall.saturating_sub(now.0 - last.0) > SLEEP_GAP;
+            last = now;
+
+            let settings = crate::settings::get(&app).auto_lock;
+            if !settings.enabled {
+                continue;
+            }
+
+            let idle_secs = tauri::async_runtime::spawn_blocking(system_idle_secs)
+                .await
+                .ok()
+                .flatten();
+            let idle = idle_secs.is_some_and(|secs| secs >= u64::from(settings.idle_minutes) * 60);
+
+            if slept {
+                lock(&app, AutoLockReason::Sleep);
+                armed = false;
+            } else if idle {
+                if armed {
+                    lock(&app, AutoLockReason::Idle);
+                    armed = false;
+                }
+            } else if idle_secs.is_some_and(|secs| secs < POLL_INTERVAL.as_secs()) {
+                armed = true;
+            }
+        }
+    });
+}
+
+/// Check an idle period before it's stored
+pub fn validate_idle_minutes(idle_minutes: u32) -> Result<(), String> {
+    if !(1..=MAX_IDLE_MINUTES).contains(&idle_minutes) {
+        return Err(format!(
+            "Idle period must be between 1 and {} minutes",
+            MAX_IDLE_MINUTES
+        ));
+    }
+    Ok(())
+}
+
+/// Enable or disable locking after `idle_minutes` without input or after sleep
+///
+/// `hide_windows` keeps its previous value when omitted.
+#[tauri::command]
+pub async fn set_auto_lock<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+    idle_minutes: u32,
+    hide_windows: Option<bool>,
+) -> Result<(), String> {
+    validate_idle_minutes(idle_minutes)?;
+    crate::settings::update(&app, |settings| {
+        settings.auto_lock.enabled = enabled;
+        settings.auto_lock.idle_minutes = idle_minutes;
+        if let Some(hide_windows) = hide_windows {
+            settings.auto_lock.hide_windows = hide_windows;
+        }
+    })?;
+
+    log::info!("Auto-lock {} after {} min", enabled, idle_minutes);
+    Ok(())
+}
+
+/// Get the auto-lock configuration
+#[tauri::command]
+pub fn get_auto_lock_settings<R: Runtime>(app: AppHandle<R>) -> AutoLockSettings {
+    crate::settings::get(&app).auto_lock
+}
@@ -0,0 +1,155 @@
+//! Autostart entry checks for YULA Desktop
+//!
+//! The autostart plugin registers the executable's path at the time it was
+//! enabled: a launch agent on macOS, a `Run` registry value on Windows and
+//! an XDG autostart entry on Linux. Moving the app or updating it to a new
+//! location leaves the entry pointing at a file that's gone, and login
+//! launches stop without any error. `verify_autostart` reads the entry back
+//! and compares it with this executable, and `repair_autostart` registers it
+//! again. Release builds check at launch and emit `autostart-broken` once
+//! the frontend is ready when the entry is stale.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+
+/// Payload of the `autostart-broken` event
+#[derive(Debug, Clone, Serialize)]
+struct BrokenPayload {
+    /// Program the login item would launch, if the entry could be read
+    registered_path: Option<String>,
+}
+
+/// The path the plugin registers for this executable
+fn launch_path() -> Option<PathBuf> {
+    // AppImages run from a mount point that changes every launch
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(appimage) = std::env::var_os("APPIMAGE") {
+            return Some(PathBuf::from(appimage));
+        }
+    }
+
+    std::env::current_exe().ok()
+}
+
+/// The program the autostart entry launches, if there is one
+#[cfg(target_os = "macos")]
+fn registered_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from)?;
+    let plist = home
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", app.package_info().name));
+    let plist = std::fs::read_to_string(plist).ok()?;
+
+    // The first of `ProgramArguments` is the program
+    let (_, arguments) = plist.split_once("<key>ProgramArguments</key>")?;
+    let (_, program) = arguments.split_once("<string>")?;
+    let (program, _) = program.split_once("</string>")?;
+    let program = program
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some(PathBuf::from(program))
+}
+
+/// The program the autostart entry launches, if there is one
+#[cfg(target_os = "windows")]
+fn registered_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    let name = &app.package_info().name;
+    let output = crate::platform::output("reg", &["query", key, "/v", name])?;
+    // `    YULA    REG_SZ    "C:\...\YULA.exe" --minimized`
+    let command = output
+        .lines()
+        .find_map(|line| line.split_once("REG_SZ").map(|(_, command)| command))?;
+    crate::platform::program_of(command).map(PathBuf::from)
+}
+
+/// The program the autostart entry launches, if there is one
+#[cfg(target_os = "linux")]
+fn registered_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    let entry = config_home
+        .join("autostart")
+        .join(format!("{}.desktop", app.package_info().name));
+    let entry = std::fs::read_to_string(entry).ok()?;
+
+    let exec = entry.lines().find_map(|line| line.strip_prefix("Exec="))?;
+    crate::platform::program_of(exec).map(PathBuf::from)
+}
+
+fn is_enabled<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    use tauri_plugin_autostart::ManagerExt;
+
+    crate::plugins::require(app, crate::plugins::AUTOSTART)?;
+    app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
+
+/// Whether the autostart entry launches this executable
+fn is_current<R: Runtime>(app: &AppHandle<R>) -> bool {
+    match (registered_path(app), launch_path()) {
+        (Some(registered), Some(current)) => crate::platform::same_path(&registered, &current),
+        _ => false,
+    }
+}
+
+/// Warn at launch when the autostart entry is stale; skipped in debug
+/// builds, which run from a different path than the installed app
+pub fn check_launch<R: Runtime>(app: &AppHandle<R>) {
+    if cfg!(debug_assertions) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if !is_enabled(&app).unwrap_or(false) || is_current(&app) {
+            return;
+        }
+
+        let registered_path = registered_path(&app).map(|path| path.display().to_string());
+        log::warn!(
+            "Autostart entry doesn't launch this install: {}",
+            registered_path.as_deref().unwrap_or("unreadable")
+        );
+        // Held until the page can listen
+        crate::launch::emit_when_ready(
+            &app,
+            crate::events::AUTOSTART_BROKEN,
+            BrokenPayload { registered_path },
+        );
+    });
+}
+
+/// Check whether autostart is enabled and launches this executable
+///
+/// Returns `false` when autostart is disabled.
+#[tauri::command]
+pub async fn verify_autostart<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        is_enabled(&app).map(|enabled| enabled && is_current(&app))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Register autostart again at this executable's path, if it's enabled
+///
+/// Returns whether the entry had to be rewritten.
+#[tauri::command]
+pub async fn repair_autostart<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    use tauri_plugin_autostart::ManagerExt;
+
+    if !is_enabled(&app)? || is_current(&app) {
+        return Ok(false);
+    }
+
+    // Enabling overwrites the entry with the current path
+    app.autolaunch().enable().map_err(|e| e.to_string())?;
+    log::info!("Autostart entry repaired");
+    Ok(true)
+}
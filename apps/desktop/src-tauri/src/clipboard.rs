@@ -0,0 +1,298 @@
+//! Clipboard change monitoring for YULA Desktop
+//!
+//! Emits `clipboard-changed` with the new text whenever the system clipboard
+//! changes, so the frontend's clipboard history doesn't have to poll over
+//! IPC. Windows delivers native change notifications to the main window
+//! (`AddClipboardFormatListener`), so nothing is polled there. Elsewhere the
+//! clipboard is polled adaptively: every second while the main window is
+//! focused on AC power, every five seconds when it isn't focused or the
+//! machine is on battery or in low-power mode. macOS and Windows expose a
+//! change counter, so the contents are only read after a change; on Linux
+//! the text itself is compared.
+//!
+//! Watching is off until the frontend opts in with
+//! `set_clipboard_poll_strategy`, and the monitor only runs while at least
+//! one caller of `start_clipboard_monitoring` hasn't stopped again.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Poll interval while the user is likely copying into the app
+const FAST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Poll interval in the background or away from AC power
+const SLOW_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How the clipboard is watched for changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ClipboardPollStrategy {
+    /// Native notifications where available, otherwise adaptive polling
+    Auto,
+    /// Poll every second regardless of focus and power
+    Fast,
+    /// Poll every five seconds
+    Slow,
+    /// Don't watch the clipboard
+    #[default]
+    Off,
+}
+
+/// Who is listening for `clipboard-changed` and whether the monitor runs
+#[derive(Debug, Default)]
+pub struct ClipboardMonitor {
+    listeners: u32,
+    running: bool,
+    /// Whether native notifications were set up, once tried
+    native: Option<bool>,
+}
+
+/// Cheap value that changes whenever the clipboard does
+#[derive(Debug, PartialEq)]
+enum Marker {
+    Count(i64),
+    Text(Option<String>),
+}
+
+/// The OS clipboard change counter, `None` where there's none
+fn change_count() -> Option<i64> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        unsafe {
+            let pasteboard: *mut AnyObject =
+                objc2::msg_send![objc2::class!(NSPasteboard), generalPasteboard];
+            let count: isize = objc2::msg_send![pasteboard.as_ref()?, changeCount];
+            Some(count as i64)
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+
+        // Zero when the counter isn't accessible to this session
+        let count = unsafe { GetClipboardSequenceNumber() };
+        (count != 0).then_some(i64::from(count))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    None
+}
+
+fn read_text<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    app.clipboard().read_text().ok()
+}
+
+fn marker<R: Runtime>(app: &AppHandle<R>) -> Marker {
+    change_count()
+        .map(Marker::Count)
+        .unwrap_or_else(|| Marker::Text(read_text(app)))
+}
+
+/// Wake the monitor whenever the main window receives `WM_CLIPBOARDUPDATE`
+#[cfg(target_os = "windows")]
+fn listen_natively<R: Runtime>(app: &AppHandle<R>) -> bool {
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::DataExchange::AddClipboardFormatListener;
+    use windows::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+    use windows::Win32::UI::WindowsAndMessaging::WM_CLIPBOARDUPDATE;
+
+    /// Identifies our subclass among others on the window
+    const SUBCLASS_ID: usize = 0x5955_4c41;
+
+    unsafe extern "system" fn subclass_proc<R: Runtime>(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        app: usize,
+    ) -> LRESULT {
+        if msg == WM_CLIPBOARDUPDATE {
+            let app = &*(app as *const AppHandle<R>);
+            app.state::<crate::AppState>().clipboard_changed.notify_one();
+        }
+        DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+
+    let Some(hwnd) = crate::window::main_window(app)
+        .ok()
+        .and_then(|window| window.hwnd().ok())
+    else {
+        return false;
+    };
+    let hwnd = hwnd.0 as isize;
+    // Leaked on purpose: the subclass stays installed for the window's life
+    let handle = Box::into_raw(Box::new(app.clone())) as usize;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let queued = app.run_on_main_thread(move || unsafe {
+        let hwnd = HWND(hwnd as *mut _);
+        let listening = SetWindowSubclass(hwnd, Some(subclass_proc::<R>), SUBCLASS_ID, handle)
+            .as_bool()
+            && AddClipboardFormatListener(hwnd).is_ok();
+        let _ = tx.send(listening);
+    });
+    queued.is_ok() && rx.recv_timeout(Duration::from_secs(1)).unwrap_or(false)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn listen_natively<R: Runtime>(_app: &AppHandle<R>) -> bool {
+    false
+}
+
+/// How long to wait before the next check, `None` to wait for a notification
+fn wait_interval<R: Runtime>(
+    app: &AppHandle<R>,
+    strategy: ClipboardPollStrategy,
+    native: bool,
+) -> Option<Duration> {
+    match strategy {
+        ClipboardPollStrategy::Auto if native => None,
+        ClipboardPollStrategy::Auto => {
+            let focused = crate::window::main_window(app)
+                .is_ok_and(|window| window.is_focused().unwrap_or(false));
+            let on_ac = !crate::power::is_on_battery(app) && !crate::power::is_low_power(app);
+            Some(if focused && on_ac {
+                FAST_INTERVAL
+            } else {
+                SLOW_INTERVAL
+            })
+        }
+        ClipboardPollStrategy::Fast => Some(FAST_INTERVAL),
+        ClipboardPollStrategy::Slow | ClipboardPollStrategy::Off => Some(SLOW_INTERVAL),
+    }
+}
+
+/// Start the monitor that emits `clipboard-changed`, unless it runs
+/// already, nobody listens or the strategy is `Off`
+///
+/// It follows its own intervals rather than the throttle factor, but
+/// stops reading the clipboard while background tasks are suspended.
+fn ensure_running<R: Runtime>(app: &AppHandle<R>) {
+    if crate::plugins::require(app, crate::plugins::CLIPBOARD).is_err() {
+        return;
+    }
+    let state = app.state::<crate::AppState>();
+    let mut monitor = state.clipboard_monitor.lock().unwrap();
+    let off = crate::settings::get(app).clipboard_poll_strategy == ClipboardPollStrategy::Off;
+    if monitor.running || monitor.listeners == 0 || off {
+        return;
+    }
+    // The window subclass stays installed, so it's only set up once
+    let native = *monitor.native.get_or_insert_with(|| listen_natively(app));
+    monitor.running = true;
+    drop(monitor);
+    log::info!("Clipboard monitor started (native notifications: {})", native);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let handle = app.clone();
+        let mut last = tauri::async_runtime::spawn_blocking(move || marker(&handle))
+            .await
+            .ok();
+
+        loop {
+            let strategy = crate::settings::get(&app).clipboard_poll_strategy;
+            let state = app.state::<crate::AppState>();
+            // Notifications and strategy changes cut the wait short
+            match wait_interval(&app, strategy, native) {
+                Some(interval) => {
+                    let notified = state.clipboard_changed.notified();
+                    let _ = tokio::time::timeout(interval, notified).await;
+                }
+                None => state.clipboard_changed.notified().await,
+            }
+
+            let strategy = crate::settings::get(&app).clipboard_poll_strategy;
+            {
+                // Checked under the lock so a new listener can't miss the exit
+                let mut monitor = state.clipboard_monitor.lock().unwrap();
+                if strategy == ClipboardPollStrategy::Off || monitor.listeners == 0 {
+                    monitor.running = false;
+                    log::info!("Clipboard monitor stopped");
+                    return;
+                }
+            }
+            if state.background_suspended.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            let handle = app.clone();
+            let Ok(current) = tauri::async_runtime::spawn_blocking(move || marker(&handle)).await
+            else {
+                continue;
+            };
+            if last.as_ref() == Some(&current) {
+                continue;
+            }
+
+            // The counter only says something changed; read what it was
+            let text = match &current {
+                Marker::Text(text) => text.clone(),
+                Marker::Count(_) => {
+                    let handle = app.clone();
+                    tauri::async_runtime::spawn_blocking(move || read_text(&handle))
+                        .await
+                        .ok()
+                        .flatten()
+                }
+            };
+            // Without a baseline the first read only becomes one
+            if last.replace(current).is_some() {
+                crate::events::emit(&app, crate::events::CLIPBOARD_CHANGED, text);
+            }
+        }
+    });
+}
+
+/// Start emitting `clipboard-changed`, once per listener
+///
+/// Nothing is watched while the strategy is `Off`, the default, until
+/// `set_clipboard_poll_strategy` chooses another.
+#[tauri::command]
+pub async fn start_clipboard_monitoring<R: Runtime>(app: AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    state.clipboard_monitor.lock().unwrap().listeners += 1;
+    ensure_running(&app);
+}
+
+/// Stop emitting `clipboard-changed` for one listener; the monitor stops
+/// once the last is gone
+#[tauri::command]
+pub fn stop_clipboard_monitoring<R: Runtime>(app: AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    let mut monitor = state.clipboard_monitor.lock().unwrap();
+    monitor.listeners = monitor.listeners.saturating_sub(1);
+    if monitor.listeners == 0 {
+        state.clipboard_changed.notify_one();
+    }
+}
+
+/// Choose how the clipboard is watched for `clipboard-changed`
+///
+/// `Auto` adapts to focus and power and uses native notifications where it
+/// can; the others poll at a fixed rate or, with `Off`, stop watching.
+#[tauri::command]
+pub async fn set_clipboard_poll_strategy<R: Runtime>(
+    app: AppHandle<R>,
+    strategy: ClipboardPollStrategy,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.clipboard_poll_strategy = strategy)?;
+
+    log::info!("Clipboard poll strategy: {:?}", strategy);
+    app.state::<crate::AppState>().clipboard_changed.notify_one();
+    ensure_running(&app);
+    Ok(())
+}
+
+/// Get how the clipboard is watched
+#[tauri::command]
+pub fn get_clipboard_poll_strategy<R: Runtime>(app: AppHandle<R>) -> ClipboardPollStrategy {
+    crate::settings::get(&app).clipboard_poll_strategy
+}
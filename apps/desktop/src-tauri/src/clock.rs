@@ -0,0 +1,110 @@
+//! Clock skew detection for YULA Desktop
+//!
+//! Sign-in tokens and TLS certificates are rejected when the system clock is
+//! far off, which surfaces as confusing login failures. The local clock is
+//! compared with the HTTP `Date` header of a well-known server, corrected
+//! for the request's round trip. `Date` has one-second resolution, which is
+//! plenty for the one-minute threshold. The check runs once at startup and
+//! emits `clock-skew-detected` with a notification when the skew matters.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+
+/// Servers whose `Date` header is used as the reference time, in order
+const TIME_SOURCES: &[&str] = &["https://releases.yula.ai", "https://www.cloudflare.com"];
+
+/// Timeout for each time request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Skew beyond which sign-in and TLS are likely to fail
+const SIGNIFICANT_SKEW_SECS: i64 = 60;
+
+/// Difference between a reference clock and the local one
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockSkew {
+    /// Reference time minus local time; positive when the local clock is behind
+    pub offset_secs: i64,
+    pub is_significant: bool,
+}
+
+/// Measure the offset against the first time source that answers
+async fn measure() -> Result<i64, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut last_error = String::from("No time source configured");
+    for source in TIME_SOURCES {
+        let started = Instant::now();
+        let sent_at = chrono::Utc::now();
+        let response = match client.head(*source).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                last_error = format!("{}: {}", source, e);
+                continue;
+            }
+        };
+
+        let date = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok());
+        let Some(date) = date else {
+            last_error = format!("{} sent no valid Date header", source);
+            continue;
+        };
+
+        // The server stamped the response about halfway through the round trip
+        let local = sent_at.timestamp_millis() + started.elapsed().as_millis() as i64 / 2;
+        return Ok((date.timestamp_millis() - local) / 1000);
+    }
+
+    Err(format!("Failed to reach a time source: {}", last_error))
+}
+
+async fn check<R: Runtime>(app: &AppHandle<R>) -> Result<ClockSkew, String> {
+    let offset_secs = measure().await?;
+    let skew = ClockSkew {
+        offset_secs,
+        is_significant: offset_secs.abs() > SIGNIFICANT_SKEW_SECS,
+    };
+
+    if skew.is_significant {
+        log::warn!("System clock is off by {} s", offset_secs);
+        crate::events::emit(app, crate::events::CLOCK_SKEW_DETECTED, skew.clone());
+    }
+    Ok(skew)
+}
+
+/// Check the clock once in the background after launch, notifying on skew
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match check(&app).await {
+            Ok(skew) if skew.is_significant => {
+                let body = format!(
+                    "Your clock is off by {} minutes, so signing in may fail. \
+                     Turn on automatic date and time in your system settings.",
+                    (skew.offset_secs.abs() + 30) / 60
+                );
+                if let Err(e) = crate::notifications::notify(&app, "Check your clock", &body, None)
+                {
+                    log::warn!("Failed to show clock skew notice: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Clock skew check skipped: {}", e),
+        }
+    });
+}
+
+/// Compare the system clock with a time server
+///
+/// Emits `clock-skew-detected` when the skew exceeds a minute.
+#[tauri::command]
+pub async fn check_clock_skew<R: Runtime>(app: AppHandle<R>) -> Result<ClockSkew, String> {
+    check(&app).await
+}
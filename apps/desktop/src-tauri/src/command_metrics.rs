@@ -0,0 +1,160 @@
+//! Local command latency metrics for YULA Desktop
+//!
+//! Helps find which native command makes the app feel slow without
+//! attaching a profiler. The native invoke handler returns as soon as an
+//! async command is spawned and never sees its result, so the frontend's
+//! `invoke` wrapper times each call from request to reply and reports the
+//! samples in batches through `record_command_samples`. Each command keeps
+//! its call and failure counts and its most recent durations, from which
+//! `get_command_metrics` derives p50 and p95 latencies. The metrics are
+//! therefore frontend-reported: they include IPC and serialization, and
+//! calls made outside that wrapper aren't counted. Everything stays in
+//! memory on this device and is gone on quit or `reset_command_metrics`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Durations kept per command for the percentiles
+const MAX_SAMPLES: usize = 512;
+
+/// Distinct command names tracked, so stray names can't grow the map forever
+const MAX_COMMANDS: usize = 1024;
+
+/// One timed command call, as reported by the frontend
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandSample {
+    pub command: String,
+    pub duration_ms: f64,
+    pub ok: bool,
+}
+
+#[derive(Debug, Default)]
+struct CommandHistory {
+    calls: u64,
+    failures: u64,
+    /// Most recent durations in ms, oldest first
+    durations: VecDeque<f64>,
+}
+
+/// Recorded calls per command name
+#[derive(Debug, Default)]
+pub struct CommandMetrics {
+    commands: HashMap<String, CommandHistory>,
+}
+
+impl CommandMetrics {
+    fn record(&mut self, sample: CommandSample) {
+        if !sample.duration_ms.is_finite() || sample.duration_ms < 0.0 {
+            return;
+        }
+        if !self.commands.contains_key(&sample.command) && self.commands.len() >= MAX_COMMANDS {
+            return;
+        }
+
+        let history = self.commands.entry(sample.command).or_default();
+        history.calls += 1;
+        if !sample.ok {
+            history.failures += 1;
+        }
+        if history.durations.len() >= MAX_SAMPLES {
+            history.durations.pop_front();
+        }
+        history.durations.push_back(sample.duration_ms);
+    }
+}
+
+/// Call counts and latencies of one command
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub calls: u64,
+    pub failures: u64,
+    /// Over the most recent calls
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+}
+
+/// Nearest-rank percentile of sorted durations
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Add command timings measured by the frontend
+#[tauri::command]
+pub fn record_command_samples<R: Runtime>(app: AppHandle<R>, samples: Vec<CommandSample>) {
+    let state = app.state::<crate::AppState>();
+    let mut metrics = state.command_metrics.lock().unwrap();
+    for sample in samples {
+        metrics.record(sample);
+    }
+}
+
+/// Get call counts and p50/p95 latencies per command, slowest p95 first
+///
+/// Latencies are as reported by the frontend, from request to reply.
+#[tauri::command]
+pub fn get_command_metrics<R: Runtime>(app: AppHandle<R>) -> Vec<CommandStats> {
+    let state = app.state::<crate::AppState>();
+    let metrics = state.command_metrics.lock().unwrap();
+    let mut stats: Vec<CommandStats> = metrics
+        .commands
+        .iter()
+        .map(|(command, history)| {
+            let mut sorted: Vec<f64> = history.durations.iter().copied().collect();
+            sorted.sort_by(f64::total_cmp);
+            CommandStats {
+                command: command.clone(),
+                calls: history.calls,
+                failures: history.failures,
+                p50_ms: percentile(&sorted, 0.5),
+                p95_ms: percentile(&sorted, 0.95),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+    stats
+}
+
+/// Forget every recorded command call
+#[tauri::command]
+pub fn reset_command_metrics<R: Runtime>(app: AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    state.command_metrics.lock().unwrap().commands.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_nothing_is_zero() {
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn percentile_of_one_sample_is_that_sample() {
+        for p in [0.0, 0.5, 0.95, 0.99, 1.0] {
+            assert_eq!(percentile(&[7.0], p), 7.0);
+        }
+    }
+
+    #[test]
+    fn percentile_uses_the_nearest_rank() {
+        let sorted: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 0.5), 50.0);
+        assert_eq!(percentile(&sorted, 0.95), 95.0);
+        assert_eq!(percentile(&sorted, 0.99), 99.0);
+        assert_eq!(percentile(&sorted, 1.0), 100.0);
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+
+        // Ranks round up between samples
+        let sorted = [1.0, 2.0, 3.0];
+        assert_eq!(percentile(&sorted, 0.5), 2.0);
+        assert_eq!(percentile(&sorted, 0.95), 3.0);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! These commands are exposed to the frontend via `invoke()`.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Runtime};
 
 /// System information response
@@ -15,31 +15,82 @@ pub struct SystemInfo {
 }
 
 /// Update information
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct UpdateInfo {
     pub available: bool,
     pub version: Option<String>,
     pub notes: Option<String>,
+    /// Size of the update package, when the server reports it
+    pub download_size_bytes: Option<u64>,
+}
+
+/// macOS activation policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ActivationPolicy {
+    /// Regular app with a dock icon
+    #[default]
+    Regular,
+    /// Menu bar only, no dock icon
+    Accessory,
+    /// No dock icon and no UI activation
+    Prohibited,
+}
+
+#[cfg(target_os = "macos")]
+impl From<ActivationPolicy> for tauri::ActivationPolicy {
+    fn from(policy: ActivationPolicy) -> Self {
+        match policy {
+            ActivationPolicy::Regular => tauri::ActivationPolicy::Regular,
+            ActivationPolicy::Accessory => tauri::ActivationPolicy::Accessory,
+            ActivationPolicy::Prohibited => tauri::ActivationPolicy::Prohibited,
+        }
+    }
+}
+
+/// App-managed directories
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum AppDir {
+    Config,
+    Data,
+    Cache,
+    Log,
+}
+
+fn resolve_app_dir<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: AppDir,
+) -> Result<std::path::PathBuf, String> {
+    // Logs are shared; the other directories belong to the active profile
+    match kind {
+        AppDir::Config => crate::profiles::config_dir(app),
+        AppDir::Data => crate::profiles::data_dir(app),
+        AppDir::Cache => crate::profiles::cache_dir(app),
+        AppDir::Log => app.path().app_log_dir().map_err(|e| e.to_string()),
+    }
 }
 
 /// Show a native system notification
+///
+/// Returns `RateLimited` instead of showing it when too many notifications
+/// were requested within the last minute, or `Digested` if digest mode is
+/// on; `group`, such as a chat id, is what the digest counts it under.
 #[tauri::command]
 pub async fn show_notification<R: Runtime>(
     app: AppHandle<R>,
     title: String,
     body: String,
     icon: Option<String>,
-) -> Result<(), String> {
-    use tauri_plugin_notification::NotificationExt;
-
-    let mut notification = app.notification().builder();
-    notification = notification.title(&title).body(&body);
-
-    if let Some(icon_path) = icon {
-        notification = notification.icon(&icon_path);
-    }
-
-    notification.show().map_err(|e| e.to_string())
+    group: Option<String>,
+) -> Result<crate::notifications::NotificationResult, String> {
+    crate::plugins::require(&app, crate::plugins::NOTIFICATION)?;
+
+    crate::notifications::notify_in_group(
+        &app,
+        group.as_deref(),
+        &title,
+        &body,
+        icon.as_deref(),
+    )
 }
 
 /// Get system information
@@ -90,20 +141,50 @@ pub async fn clear_badge<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
 /// Check for application updates
 #[tauri::command]
 pub async fn check_for_updates<R: Runtime>(app: AppHandle<R>) -> Result<UpdateInfo, String> {
+    use crate::updater::UpdaterState;
+
+    crate::plugins::require(&app, crate::plugins::UPDATER)?;
+
+    // A check during an install leaves the install's state alone
+    let tracked = !crate::updater::state(&app).is_installing();
+    if tracked {
+        crate::updater::set_state(&app, UpdaterState::Checking);
+    }
+    let result = check(&app).await;
+    match &result {
+        // Found updates move to `Available` when announced
+        Ok(info) if info.available => {}
+        Ok(_) if tracked => crate::updater::set_state(&app, UpdaterState::Idle),
+        Err(e) if tracked => {
+            crate::updater::set_state(&app, UpdaterState::Failed { error: e.clone() })
+        }
+        _ => {}
+    }
+    result
+}
+
+async fn check<R: Runtime>(app: &AppHandle<R>) -> Result<UpdateInfo, String> {
     use tauri_plugin_updater::UpdaterExt;
 
     match app.updater() {
         Ok(updater) => {
             match updater.check().await {
-                Ok(Some(update)) => Ok(UpdateInfo {
-                    available: true,
-                    version: Some(update.version.clone()),
-                    notes: update.body.clone(),
-                }),
+                Ok(Some(update)) => {
+                    let info = UpdateInfo {
+                        available: true,
+                        version: Some(update.version.clone()),
+                        notes: update.body.clone(),
+                        download_size_bytes: crate::network::content_length(&update.download_url)
+                            .await,
+                    };
+                    crate::updater::announce(app, &info);
+                    Ok(info)
+                }
                 Ok(None) => Ok(UpdateInfo {
                     available: false,
                     version: None,
                     notes: None,
+                    download_size_bytes: None,
                 }),
                 Err(e) => Err(e.to_string()),
             }
@@ -115,25 +196,43 @@ pub async fn check_for_updates<R: Runtime>(app: AppHandle<R>) -> Result<UpdateIn
 /// Install available update
 #[tauri::command]
 pub async fn install_update<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    use crate::updater::UpdaterState;
+
+    crate::plugins::require(&app, crate::plugins::UPDATER)?;
+    if crate::updater::state(&app).is_installing() {
+        return Err("An update is already being installed".to_string());
+    }
+
+    let result = install(&app).await;
+    let cancelled = app
+        .state::<crate::AppState>()
+        .update_download
+        .was_cancelled();
+    crate::updater::set_state(
+        &app,
+        match &result {
+            Err(e) if !cancelled => UpdaterState::Failed { error: e.clone() },
+            _ => UpdaterState::Idle,
+        },
+    );
+    result
+}
+
+async fn install<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     use tauri_plugin_updater::UpdaterExt;
 
+    crate::signature::check_before_update(app).await?;
+
     let updater = app.updater().map_err(|e| e.to_string())?;
 
     if let Some(update) = updater.check().await.map_err(|e| e.to_string())? {
+        // Keep the current version around in case the update is broken
+        if let Err(e) = crate::rollback::record(app, &update.version).await {
+            log::warn!("Update will not be reversible: {}", e);
+        }
+
         // Download and install
-        let mut downloaded = 0;
-        update
-            .download_and_install(
-                |chunk_length, content_length| {
-                    downloaded += chunk_length;
-                    log::info!("Downloaded {} of {:?}", downloaded, content_length);
-                },
-                || {
-                    log::info!("Download finished, installing...");
-                },
-            )
-            .await
-            .map_err(|e| e.to_string())?;
+        crate::updater::download_and_install(app, &update).await?;
     }
 
     Ok(())
@@ -147,6 +246,8 @@ pub async fn set_autostart<R: Runtime>(
 ) -> Result<(), String> {
     use tauri_plugin_autostart::ManagerExt;
 
+    crate::plugins::require(&app, crate::plugins::AUTOSTART)?;
+
     let autostart = app.autolaunch();
 
     if enabled {
@@ -161,6 +262,8 @@ pub async fn set_autostart<R: Runtime>(
 pub async fn get_autostart_enabled<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
     use tauri_plugin_autostart::ManagerExt;
 
+    crate::plugins::require(&app, crate::plugins::AUTOSTART)?;
+
     app.autolaunch()
         .is_enabled()
         .map_err(|e| e.to_string())
@@ -174,6 +277,9 @@ pub async fn open_external_link<R: Runtime>(
 ) -> Result<(), String> {
     use tauri_plugin_shell::ShellExt;
 
+    crate::plugins::require(&app, crate::plugins::SHELL)?;
+
+    #[allow(deprecated)]
     app.shell()
         .open(&url, None)
         .map_err(|e| e.to_string())
@@ -187,6 +293,8 @@ pub async fn copy_to_clipboard<R: Runtime>(
 ) -> Result<(), String> {
     use tauri_plugin_clipboard_manager::ClipboardExt;
 
+    crate::plugins::require(&app, crate::plugins::CLIPBOARD)?;
+
     app.clipboard()
         .write_text(&text)
         .map_err(|e| e.to_string())
@@ -199,6 +307,8 @@ pub async fn read_from_clipboard<R: Runtime>(
 ) -> Result<String, String> {
     use tauri_plugin_clipboard_manager::ClipboardExt;
 
+    crate::plugins::require(&app, crate::plugins::CLIPBOARD)?;
+
     app.clipboard()
         .read_text()
         .map_err(|e| e.to_string())
@@ -207,8 +317,11 @@ pub async fn read_from_clipboard<R: Runtime>(
 /// Minimize the window to system tray
 #[tauri::command]
 pub async fn minimize_to_tray<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    crate::kiosk::ensure_inactive(&app, "Minimizing to the tray")?;
     if let Some(window) = app.get_webview_window("main") {
-        window.hide().map_err(|e| e.to_string())
+        crate::window::hide(&window.as_ref().window())?;
+        crate::throttle::set_throttled(&app, true);
+        Ok(())
     } else {
         Err("Main window not found".to_string())
     }
@@ -220,3 +333,96 @@ pub async fn quit_app<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     app.exit(0);
     Ok(())
 }
+
+/// Restart the application
+#[tauri::command]
+pub async fn restart_app<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    crate::restart(&app, &app.env());
+}
+
+/// Set the macOS activation policy and persist it
+///
+/// In `Accessory` mode the dock icon disappears and the app lives only in the
+/// menu bar; the tray remains the way back to the window.
+#[tauri::command]
+pub async fn set_activation_policy<R: Runtime>(
+    app: AppHandle<R>,
+    policy: ActivationPolicy,
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        apply_activation_policy(&app, policy)?;
+        crate::settings::update(&app, |settings| settings.activation_policy = policy)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, policy);
+        Err("Activation policy is only supported on macOS".to_string())
+    }
+}
+
+/// Get the persisted macOS activation policy
+#[tauri::command]
+pub fn get_activation_policy<R: Runtime>(app: AppHandle<R>) -> ActivationPolicy {
+    crate::settings::get(&app).activation_policy
+}
+
+/// Apply an activation policy, keeping a visible main window in front
+#[cfg(target_os = "macos")]
+pub fn apply_activation_policy<R: Runtime>(
+    app: &AppHandle<R>,
+    policy: ActivationPolicy,
+) -> Result<(), String> {
+    app.set_activation_policy(policy.into())
+        .map_err(|e| e.to_string())?;
+
+    // Changing the policy deactivates the app, which would leave a visible
+    // window stranded behind others with no dock icon to bring it back
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the path of an app-managed directory
+#[tauri::command]
+pub fn get_app_directory<R: Runtime>(app: AppHandle<R>, kind: AppDir) -> Result<String, String> {
+    resolve_app_dir(&app, kind).map(|path| path.to_string_lossy().to_string())
+}
+
+/// Reveal an app-managed directory in the file manager
+#[tauri::command]
+pub async fn open_app_directory<R: Runtime>(
+    app: AppHandle<R>,
+    kind: AppDir,
+) -> Result<(), String> {
+    // The directory may not exist yet if nothing has been written to it
+    let path = resolve_app_dir(&app, kind)?;
+    std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+
+    open_in_file_manager(&path)
+}
+
+/// Open a path with the platform file manager
+///
+/// The shell plugin's `open` scope only admits URLs, so local paths are
+/// handed to the platform opener directly.
+pub fn open_in_file_manager(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let opener = "open";
+    #[cfg(target_os = "windows")]
+    let opener = "explorer";
+    #[cfg(target_os = "linux")]
+    let opener = "xdg-open";
+
+    crate::platform::command(opener)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
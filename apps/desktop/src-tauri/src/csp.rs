@@ -0,0 +1,165 @@
+//! Runtime Content-Security-Policy for YULA Desktop
+//!
+//! The CSP in `tauri.conf.json` is fixed at build time. To point the app at
+//! different API hosts without rebuilding, every window that loads the
+//! bundled frontend intercepts the responses Tauri serves on its own
+//! protocol and adds the `connect-src` sources stored in settings to the
+//! policy Tauri attached, hashes of inline scripts included. Pages keep the
+//! bundled origin, and with it their local storage, IndexedDB and cookies.
+//! In development the frontend comes from the dev server, so the composed
+//! policy is reported but not enforced.
+
+use tauri::http::{header, HeaderValue};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// `connect-src` sources only allowed in debug builds
+const DEV_CONNECT_SOURCES: &[&str] = &["http://localhost:*", "ws://localhost:*"];
+
+/// Check `connect-src` sources before they're stored
+pub fn validate_sources(sources: &[String]) -> Result<(), String> {
+    match sources.iter().find(|s| !valid_source(s)) {
+        Some(bad) => Err(format!("Invalid CSP source: {:?}", bad)),
+        None => Ok(()),
+    }
+}
+
+/// A source containing `;` or whitespace could smuggle in directives
+fn valid_source(source: &str) -> bool {
+    !source.is_empty() && !source.contains(';') && !source.contains(char::is_whitespace)
+}
+
+/// User `connect-src` sources, plus the dev server's in debug builds
+///
+/// The settings file can be edited by hand, so invalid sources are dropped
+/// here too.
+fn connect_sources<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let mut sources = crate::settings::get(app).csp_connect_sources;
+    sources.retain(|source| {
+        let valid = valid_source(source);
+        if !valid {
+            log::warn!("Ignoring invalid CSP source: {:?}", source);
+        }
+        valid
+    });
+    if cfg!(debug_assertions) {
+        sources.extend(DEV_CONNECT_SOURCES.iter().map(|s| s.to_string()));
+    }
+    sources
+}
+
+/// Compose the CSP from the configured policy and user `connect-src` sources
+pub fn effective_csp<R: Runtime>(app: &AppHandle<R>) -> String {
+    let base = app
+        .config()
+        .app
+        .security
+        .csp
+        .as_ref()
+        .map(|csp| csp.to_string())
+        .unwrap_or_default();
+    compose(&base, &connect_sources(app))
+}
+
+/// Add `connect-src` sources to a policy, creating the directive if missing
+fn compose(base: &str, extra: &[String]) -> String {
+    let mut directives: Vec<String> = base
+        .split(';')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    match directives
+        .iter_mut()
+        .find(|d| d.split_whitespace().next() == Some("connect-src"))
+    {
+        Some(connect_src) => {
+            for source in extra {
+                if !connect_src.split_whitespace().any(|s| s == source) {
+                    connect_src.push(' ');
+                    connect_src.push_str(source);
+                }
+            }
+        }
+        None if !extra.is_empty() => {
+            directives.push(format!("connect-src 'self' {}", extra.join(" ")));
+        }
+        None => {}
+    }
+
+    directives.join("; ")
+}
+
+/// Add the user `connect-src` sources to the CSP of the bundled pages a
+/// window loads
+pub fn attach<'a, R: Runtime, M: Manager<R>>(
+    app: &AppHandle<R>,
+    builder: WebviewWindowBuilder<'a, R, M>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let app = app.clone();
+    builder.on_web_resource_request(move |_, response| {
+        let Some(csp) = response
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .and_then(|csp| csp.to_str().ok())
+        else {
+            return;
+        };
+        match HeaderValue::from_str(&compose(csp, &connect_sources(&app))) {
+            Ok(csp) => {
+                response.headers_mut().insert(header::CONTENT_SECURITY_POLICY, csp);
+            }
+            Err(e) => log::warn!("Failed to compose the CSP, serving the configured one: {}", e),
+        }
+    })
+}
+
+/// Get the CSP currently applied to loaded pages
+#[tauri::command]
+pub fn get_effective_csp<R: Runtime>(app: AppHandle<R>) -> String {
+    effective_csp(&app)
+}
+
+/// Set additional `connect-src` sources (e.g. API hosts) and persist them
+///
+/// Applies to pages loaded after the change; reload the window to pick it up.
+#[tauri::command]
+pub async fn set_csp_connect_sources<R: Runtime>(
+    app: AppHandle<R>,
+    sources: Vec<String>,
+) -> Result<(), String> {
+    validate_sources(&sources)?;
+    crate::settings::update(&app, |settings| settings.csp_connect_sources = sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(sources: &[&str]) -> Vec<String> {
+        sources.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn extends_the_existing_connect_src() {
+        let base = "default-src 'self'; connect-src 'self' https://api.yula.dev;";
+        let csp = compose(base, &sources(&["https://api.yula.dev", "wss://rt.yula.dev"]));
+        assert_eq!(
+            csp,
+            "default-src 'self'; connect-src 'self' https://api.yula.dev wss://rt.yula.dev"
+        );
+    }
+
+    #[test]
+    fn adds_connect_src_when_missing() {
+        let csp = compose("default-src 'self'", &sources(&["https://api.yula.dev"]));
+        assert_eq!(csp, "default-src 'self'; connect-src 'self' https://api.yula.dev");
+    }
+
+    #[test]
+    fn keeps_the_policy_without_extra_sources() {
+        let csp = compose(" default-src 'self' ;; img-src *", &[]);
+        assert_eq!(csp, "default-src 'self'; img-src *");
+        assert_eq!(compose("", &[]), "");
+    }
+}
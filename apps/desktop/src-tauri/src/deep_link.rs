@@ -0,0 +1,368 @@
+//! Deep link handling for YULA Desktop
+//!
+//! URLs arrive either through the deep-link plugin or, on Windows and Linux,
+//! as arguments to a second instance forwarded by the single-instance plugin.
+//! Both paths go through `handle`, which records the URL in a capped history
+//! before emitting `deep-link` to the window its path is routed to, parsed
+//! into the route and its query parameters. Routes map a path prefix
+//! (`council` matches `yula://council/...`) to a window label; the longest
+//! matching prefix wins and unmatched links go to the main window. Links
+//! that don't parse, or whose first segment is neither a page this version
+//! knows nor a configured route, emit `deep-link-error` instead so the
+//! frontend can say the link isn't supported. `yula://auth` links are
+//! checked by `handoff` and never recorded. Forwarded file paths are handed
+//! to `file_associations`. Routes to secondary windows follow the window
+//! policy, and every link is dropped in kiosk mode.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// URL scheme registered in `tauri.conf.json`
+pub const SCHEME: &str = "yula";
+
+/// Maximum number of URLs kept in the history
+const MAX_HISTORY: usize = 50;
+
+/// First path segments of the frontend pages a link can open
+const KNOWN_ROUTES: &[&str] = &[
+    "analytics",
+    "billing",
+    "chat",
+    "compare",
+    "council",
+    "import",
+    "memory",
+    "settings",
+    "skills",
+    "timeline",
+];
+
+/// Payload of the `deep-link` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLink {
+    /// The link as received
+    pub url: String,
+    /// `host/path` without outer slashes, e.g. `council/abc`
+    pub route: String,
+    /// Query parameters; the last value wins for repeated keys
+    pub params: BTreeMap<String, String>,
+}
+
+/// Payload of the `deep-link-error` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLinkError {
+    pub url: String,
+    pub reason: String,
+}
+
+/// A received deep link
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLinkEntry {
+    pub url: String,
+    pub received_at: i64,
+}
+
+/// Recently received deep links, oldest first
+#[derive(Debug, Default)]
+pub struct DeepLinkHistory {
+    entries: VecDeque<DeepLinkEntry>,
+}
+
+impl DeepLinkHistory {
+    fn record(&mut self, url: &str) {
+        // The OS may deliver the same link twice (e.g. plugin and argv)
+        if self.entries.back().is_some_and(|last| last.url == url) {
+            return;
+        }
+
+        if self.entries.len() >= MAX_HISTORY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(DeepLinkEntry {
+            url: url.to_string(),
+            received_at: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+}
+
+/// Register the deep-link plugin handler
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        let urls = event.urls();
+        log::info!("Deep link received: {:?}", urls);
+        for url in urls {
+            self::handle(&handle, url.as_str());
+        }
+    });
+}
+
+/// Handle arguments forwarded from a second instance
+pub fn on_second_instance<R: Runtime>(app: &AppHandle<R>, args: Vec<String>, cwd: String) {
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    let urls: Vec<&String> = args
+        .iter()
+        .filter(|arg| tauri::Url::parse(arg).is_ok_and(|url| url.scheme() == SCHEME))
+        .collect();
+    let files = crate::file_associations::file_args(&args, std::path::Path::new(&cwd));
+
+    // Launching the app again is an explicit request to bring it forward
+    if urls.is_empty() && files.is_empty() {
+        if let Err(e) = crate::window::bring_to_front(app) {
+            log::warn!("Failed to raise main window: {}", e);
+        }
+        return;
+    }
+
+    if !urls.is_empty() {
+        log::info!("Deep link forwarded from second instance: {:?}", urls);
+    }
+    for url in urls {
+        handle(app, url);
+    }
+    for path in files {
+        crate::file_associations::open(app, &path);
+    }
+}
+
+/// Normalize a route prefix or link path to `host/path` without outer slashes
+fn normalize(path: &str) -> String {
+    path.trim_matches('/').to_string()
+}
+
+/// Normalize a route's prefix, rejecting empty prefixes and labels
+pub fn normalize_route(path_prefix: &str, window_label: &str) -> Result<String, String> {
+    let prefix = normalize(path_prefix);
+    if prefix.is_empty() {
+        return Err("Route prefix must not be empty".to_string());
+    }
+    if window_label.is_empty() {
+        return Err("Window label must not be empty".to_string());
+    }
+    Ok(prefix)
+}
+
+/// Whether `path` is `prefix` or below it
+fn matches_prefix(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Parse a link into its route, rejecting links no page or configured route
+/// handles
+fn parse(url: Option<&tauri::Url>, routes: &BTreeMap<String, String>) -> Result<DeepLink, String> {
+    let url = url.ok_or("The link is malformed")?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Only {}:// links are supported", SCHEME));
+    }
+
+    let route = normalize(&format!("{}{}", url.host_str().unwrap_or(""), url.path()));
+    let page = route.split('/').next().unwrap_or_default();
+    let known = KNOWN_ROUTES.contains(&page)
+        || routes.keys().any(|prefix| matches_prefix(&route, prefix));
+    if !known {
+        return Err(if page.is_empty() {
+            "The link doesn't name a page".to_string()
+        } else {
+            format!("This version doesn't support {} links", page)
+        });
+    }
+
+    Ok(DeepLink {
+        url: url.to_string(),
+        route,
+        params: url.query_pairs().into_owned().collect(),
+    })
+}
+
+/// Pick the window a route is sent to
+fn route<R: Runtime>(app: &AppHandle<R>, route: &str) -> String {
+    crate::settings::get(app)
+        .deep_link_routes
+        .into_iter()
+        .filter(|(prefix, _)| matches_prefix(route, prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, label)| label)
+        .unwrap_or_else(|| crate::window::MAIN_WINDOW.to_string())
+}
+
+/// Record a deep link and forward it to the window it's routed to
+pub fn handle<R: Runtime>(app: &AppHandle<R>, url: &str) {
+    // A link mustn't navigate or open windows on a locked-down kiosk
+    if crate::kiosk::is_active(app) {
+        log::warn!("Deep link ignored in kiosk mode: {}", url);
+        return;
+    }
+
+    let parsed = tauri::Url::parse(url).ok();
+    // Handoff links carry a credential, so they stay out of the history
+    if parsed.as_ref().is_some_and(|parsed| crate::handoff::handle(app, parsed)) {
+        return;
+    }
+
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        state.deep_link_history.lock().unwrap().record(url);
+    }
+
+    if parsed.as_ref().is_some_and(|parsed| crate::intents::handle(app, parsed)) {
+        return;
+    }
+
+    let routes = crate::settings::get(app).deep_link_routes;
+    let link = match parse(parsed.as_ref(), &routes) {
+        Ok(link) => link,
+        Err(reason) => {
+            log::warn!("Unsupported deep link {}: {}", url, reason);
+            if let Err(e) = crate::window::bring_to_front(app) {
+                log::warn!("Failed to raise main window: {}", e);
+            }
+            let error = DeepLinkError {
+                url: url.to_string(),
+                reason,
+            };
+            crate::events::emit(app, crate::events::DEEP_LINK_ERROR, error);
+            return;
+        }
+    };
+
+    let label = route(app, &link.route);
+    if let Some(window) = app.get_webview_window(&label) {
+        crate::events::emit_to(app, &label, crate::events::DEEP_LINK, link);
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+
+    if label == crate::window::MAIN_WINDOW {
+        log::warn!("Main window not available for deep link {}", url);
+        return;
+    }
+
+    if let Err(e) = crate::window::check_policy(app, &label) {
+        log::warn!("Deep link {} not opened: {}", url, e);
+        return;
+    }
+
+    // A freshly created window can't receive the event until it has loaded
+    let pending = Arc::new(AtomicBool::new(true));
+    let builder = WebviewWindowBuilder::new(app, &label, crate::window::frontend_url(&label));
+    let builder = crate::csp::attach(app, builder);
+    let result = crate::profiles::isolate_webview(app, builder)
+        .title("YULA")
+        .inner_size(1280.0, 800.0)
+        .on_page_load(move |window, payload| {
+            if payload.event() == PageLoadEvent::Finished && pending.swap(false, Ordering::SeqCst)
+            {
+                crate::events::emit_to(
+                    window.app_handle(),
+                    window.label(),
+                    crate::events::DEEP_LINK,
+                    link.clone(),
+                );
+            }
+        })
+        .build();
+    if let Err(e) = result {
+        log::warn!("Failed to open window {} for deep link: {}", label, e);
+    }
+}
+
+/// Route deep links whose path starts with `path_prefix` to a window
+///
+/// The window is created at the frontend route named after its label if it
+/// isn't open when a matching link arrives.
+#[tauri::command]
+pub async fn set_deep_link_route<R: Runtime>(
+    app: AppHandle<R>,
+    path_prefix: String,
+    window_label: String,
+) -> Result<(), String> {
+    let prefix = normalize_route(&path_prefix, &window_label)?;
+    crate::settings::update(&app, |settings| {
+        settings.deep_link_routes.insert(prefix, window_label);
+    })
+}
+
+/// Remove the route for `path_prefix`, sending its links back to main
+#[tauri::command]
+pub async fn remove_deep_link_route<R: Runtime>(
+    app: AppHandle<R>,
+    path_prefix: String,
+) -> Result<(), String> {
+    let prefix = normalize(&path_prefix);
+    crate::settings::update(&app, |settings| {
+        settings.deep_link_routes.remove(&prefix);
+    })
+}
+
+/// Get recently received deep links, oldest first
+#[tauri::command]
+pub fn get_deep_link_history<R: Runtime>(app: AppHandle<R>) -> Vec<DeepLinkEntry> {
+    app.state::<crate::AppState>()
+        .deep_link_history
+        .lock()
+        .unwrap()
+        .entries
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Clear the deep link history
+#[tauri::command]
+pub fn clear_deep_link_history<R: Runtime>(app: AppHandle<R>) {
+    app.state::<crate::AppState>()
+        .deep_link_history
+        .lock()
+        .unwrap()
+        .entries
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(url: &str, routes: &BTreeMap<String, String>) -> Result<DeepLink, String> {
+        parse(tauri::Url::parse(url).ok().as_ref(), routes)
+    }
+
+    #[test]
+    fn matches_prefix_on_segment_boundaries() {
+        assert!(matches_prefix("council", "council"));
+        assert!(matches_prefix("council/abc", "council"));
+        assert!(!matches_prefix("councils", "council"));
+        assert!(!matches_prefix("chat", "council"));
+    }
+
+    #[test]
+    fn parses_known_routes_with_params() {
+        let link = parse_str("yula://council/abc/?tab=votes&tab=notes", &BTreeMap::new()).unwrap();
+        assert_eq!(link.url, "yula://council/abc/?tab=votes&tab=notes");
+        assert_eq!(link.route, "council/abc");
+        assert_eq!(link.params.get("tab").map(String::as_str), Some("notes"));
+    }
+
+    #[test]
+    fn accepts_configured_routes() {
+        let routes = BTreeMap::from([("reports/weekly".to_string(), "reports".to_string())]);
+        assert!(parse_str("yula://reports/weekly/3", &routes).is_ok());
+        assert!(parse_str("yula://reports/daily", &routes).is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_links() {
+        let routes = BTreeMap::new();
+        assert!(parse(None, &routes).is_err());
+        assert!(parse_str("https://council/abc", &routes).is_err());
+        assert!(parse_str("yula://", &routes).is_err());
+        assert!(parse_str("yula://unknown/page", &routes).is_err());
+    }
+}
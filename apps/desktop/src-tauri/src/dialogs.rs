@@ -0,0 +1,120 @@
+//! Native prompt dialogs for YULA Desktop
+//!
+//! The dialog plugin covers message and confirmation dialogs but has no text
+//! input, so `prompt_dialog` uses the platform's own input box: AppleScript
+//! `display dialog` on macOS, the VisualBasic `InputBox` through PowerShell
+//! on Windows, and zenity or kdialog on Linux. Both commands are async and
+//! never block the webview.
+
+use tauri::{AppHandle, Runtime};
+
+/// Show a text prompt, returning `None` if it was cancelled
+fn prompt(title: &str, message: &str, default_value: &str) -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        // Pass strings as arguments so they never need escaping
+        let output = crate::platform::command("osascript")
+            .args([
+                "-e",
+                "on run argv",
+                "-e",
+                "set {dialogTitle, dialogMessage, defaultAnswer} to argv",
+                "-e",
+                "set answer to display dialog dialogMessage default answer defaultAnswer \
+                 with title dialogTitle",
+                "-e",
+                "return text returned of answer",
+                "-e",
+                "end run",
+                title,
+                message,
+                default_value,
+            ])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        const SCRIPT: &str = concat!(
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8; ",
+            "Add-Type -AssemblyName Microsoft.VisualBasic; ",
+            "$answer = [Microsoft.VisualBasic.Interaction]::InputBox(",
+            "$env:YULA_PROMPT_MESSAGE, $env:YULA_PROMPT_TITLE, $env:YULA_PROMPT_DEFAULT); ",
+            "if ($answer -eq '') { exit 1 }; [Console]::Out.Write($answer)"
+        );
+
+        // InputBox returns an empty string on cancel, so empty input is a cancel too
+        let output = crate::platform::command("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+            .env("YULA_PROMPT_TITLE", title)
+            .env("YULA_PROMPT_MESSAGE", message)
+            .env("YULA_PROMPT_DEFAULT", default_value)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let zenity = crate::platform::command("zenity")
+            .args(["--entry", "--title", title, "--text", message])
+            .args(["--entry-text", default_value])
+            .output();
+        let output = match zenity {
+            Ok(output) => output,
+            Err(_) => crate::platform::command("kdialog")
+                .args(["--title", title, "--inputbox", message, default_value])
+                .output()
+                .ok()?,
+        };
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string())
+    }
+}
+
+/// Ask the user for a line of text, returning `None` if cancelled
+#[tauri::command]
+pub async fn prompt_dialog(
+    title: String,
+    message: String,
+    default_value: Option<String>,
+) -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        prompt(&title, &message, default_value.as_deref().unwrap_or(""))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Ask the user to confirm, returning `false` for cancel/no
+#[tauri::command]
+pub async fn confirm_dialog<R: Runtime>(
+    app: AppHandle<R>,
+    title: String,
+    message: String,
+) -> Result<bool, String> {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+    crate::plugins::require(&app, crate::plugins::DIALOG)?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(message)
+        .title(title)
+        .buttons(MessageDialogButtons::OkCancel)
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+
+    Ok(rx.await.unwrap_or(false))
+}
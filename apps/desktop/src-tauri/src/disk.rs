@@ -0,0 +1,54 @@
+//! Disk space for YULA Desktop
+//!
+//! Reports the size and free space of the volume holding a path, so large
+//! downloads and imports can be checked up front instead of failing halfway.
+
+use serde::Serialize;
+use std::path::Path;
+use sysinfo::Disks;
+use tauri::{AppHandle, Runtime};
+
+/// Space on the volume holding a path
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    /// Where the volume is mounted
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Space on the volume holding `path`
+pub fn usage(path: &Path) -> Result<DiskUsage, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    // Volumes are nested, so the deepest mount point containing the path wins
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DiskUsage {
+            mount_point: disk.mount_point().to_string_lossy().into_owned(),
+            total_bytes: disk.total_space(),
+            available_bytes: disk.available_space(),
+        })
+        .ok_or_else(|| format!("No volume found for {}", path.display()))
+}
+
+/// Get the size and free space of the volume holding a path
+///
+/// Defaults to the volume holding the profile's data directory.
+#[tauri::command]
+pub async fn get_disk_usage<R: Runtime>(
+    app: AppHandle<R>,
+    path: Option<String>,
+) -> Result<DiskUsage, String> {
+    let path = match path {
+        Some(path) => path.into(),
+        None => crate::profiles::data_dir(&app)?,
+    };
+    usage(&path)
+}
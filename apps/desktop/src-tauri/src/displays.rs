@@ -0,0 +1,457 @@
+//! Display arrangement monitoring for YULA Desktop
+//!
+//! Docking, undocking or rearranging monitors can strand windows on a
+//! display that no longer exists. The OS reports reconfiguration through
+//! `CGDisplayRegisterReconfigurationCallback` on macOS, `WM_DISPLAYCHANGE`
+//! to the main window on Windows and GDK's `monitors-changed` on Linux.
+//! Those wake a task that waits for the changes to settle, emits
+//! `displays-changed` with the new layout when it differs, and moves any
+//! window that ended up off-screen back onto a visible monitor.
+//!
+//! Each display's color space and HDR support come from `NSScreen` on macOS
+//! and the DXGI output description on Windows; Linux reports neither. The
+//! OS doesn't announce profile changes everywhere, so they're polled, and
+//! `color-profile-changed` is emitted for a display whose profile changed.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Monitor, Runtime};
+
+use crate::error::CommandError;
+
+/// How long reconfiguration must be quiet before the layout is read
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// How often color profiles are checked for changes
+const COLOR_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A rectangle in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DisplayRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// One monitor in the display arrangement
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayInfo {
+    pub name: Option<String>,
+    pub bounds: DisplayRect,
+    /// Bounds minus the menu bar, dock or taskbar
+    pub work_area: DisplayRect,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+fn describe(monitor: &Monitor, primary: Option<&Monitor>) -> DisplayInfo {
+    let (position, size, area) = (monitor.position(), monitor.size(), monitor.work_area());
+    DisplayInfo {
+        name: monitor.name().cloned(),
+        bounds: DisplayRect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+        work_area: DisplayRect {
+            x: area.position.x,
+            y: area.position.y,
+            width: area.size.width,
+            height: area.size.height,
+        },
+        scale_factor: monitor.scale_factor(),
+        is_primary: primary.is_some_and(|primary| {
+            primary.name() == monitor.name() && primary.position() == monitor.position()
+        }),
+    }
+}
+
+/// Scale of one monitor
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorScaling {
+    pub name: Option<String>,
+    pub scale_factor: f64,
+    /// Not a whole multiple, like 150%
+    pub fractional: bool,
+    /// Size of the bundled tray icon used on this monitor, 1 to 3
+    pub icon_scale: u32,
+}
+
+/// Scale of every connected monitor, primary first
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveScaling {
+    pub monitors: Vec<MonitorScaling>,
+    /// Monitors with different scale factors are connected
+    pub mixed: bool,
+}
+
+/// Color capabilities of one monitor
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayColorInfo {
+    /// Index in `get_display_arrangement`
+    pub display_index: u32,
+    pub name: Option<String>,
+    /// Color space or ICC profile name, e.g. "Display P3", where the OS says
+    pub color_space: Option<String>,
+    /// Whether HDR content can be shown, where the OS says; on Windows this
+    /// means HDR is turned on for the display
+    pub hdr_capable: Option<bool>,
+}
+
+/// Color space and HDR support of a display, where known
+type ColorDetails = (Option<String>, Option<bool>);
+
+/// Every connected monitor, primary first
+pub fn arrangement<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<DisplayInfo>, String> {
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?;
+    let mut displays: Vec<DisplayInfo> = app
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|monitor| describe(monitor, primary.as_ref()))
+        .collect();
+    displays.sort_by_key(|display| !display.is_primary);
+    Ok(displays)
+}
+
+/// Wake the monitor task; called from the OS reconfiguration hooks
+fn notify<R: Runtime>(app: &AppHandle<R>) {
+    app.state::<crate::AppState>().displays_changed.notify_one();
+}
+
+#[cfg(target_os = "macos")]
+fn listen<R: Runtime>(app: &AppHandle<R>) -> bool {
+    use std::ffi::c_void;
+
+    type ReconfigurationCallback = unsafe extern "C" fn(u32, u32, *mut c_void);
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGDisplayRegisterReconfigurationCallback(
+            callback: ReconfigurationCallback,
+            user_info: *mut c_void,
+        ) -> i32;
+    }
+
+    /// `kCGDisplayBeginConfigurationFlag`; the change itself follows
+    const BEGIN_CONFIGURATION: u32 = 1;
+
+    unsafe extern "C" fn reconfigured<R: Runtime>(_display: u32, flags: u32, app: *mut c_void) {
+        if flags & BEGIN_CONFIGURATION == 0 {
+            notify(&*(app as *const AppHandle<R>));
+        }
+    }
+
+    // Leaked on purpose: the callback stays registered for the process
+    let handle = Box::into_raw(Box::new(app.clone())) as *mut c_void;
+    unsafe { CGDisplayRegisterReconfigurationCallback(reconfigured::<R>, handle) == 0 }
+}
+
+#[cfg(target_os = "windows")]
+fn listen<R: Runtime>(app: &AppHandle<R>) -> bool {
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+    use windows::Win32::UI::WindowsAndMessaging::WM_DISPLAYCHANGE;
+
+    /// Identifies our subclass among others on the window
+    const SUBCLASS_ID: usize = 0x5955_4c44;
+
+    unsafe extern "system" fn subclass_proc<R: Runtime>(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        app: usize,
+    ) -> LRESULT {
+        if msg == WM_DISPLAYCHANGE {
+            notify(&*(app as *const AppHandle<R>));
+        }
+        DefSubclassProc(hwnd, msg, wparam, lparam)
+    }
+
+    let Some(hwnd) = crate::window::main_window(app)
+        .ok()
+        .and_then(|window| window.hwnd().ok())
+    else {
+        return false;
+    };
+    let hwnd = hwnd.0 as isize;
+    // Leaked on purpose: the subclass stays installed for the window's life
+    let handle = Box::into_raw(Box::new(app.clone())) as usize;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let queued = app.run_on_main_thread(move || unsafe {
+        let hwnd = HWND(hwnd as *mut _);
+        let installed =
+            SetWindowSubclass(hwnd, Some(subclass_proc::<R>), SUBCLASS_ID, handle).as_bool();
+        let _ = tx.send(installed);
+    });
+    queued.is_ok() && rx.recv_timeout(Duration::from_secs(1)).unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn listen<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let handle = app.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let queued = app.run_on_main_thread(move || {
+        let screen = gtk::gdk::Screen::default();
+        if let Some(screen) = &screen {
+            screen.connect_monitors_changed(move |_| notify(&handle));
+        }
+        let _ = tx.send(screen.is_some());
+    });
+    queued.is_ok() && rx.recv_timeout(Duration::from_secs(1)).unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn listen<R: Runtime>(_app: &AppHandle<R>) -> bool {
+    false
+}
+
+/// Color details of each display, in order
+#[cfg(target_os = "macos")]
+fn color_details<R: Runtime>(app: &AppHandle<R>, displays: &[DisplayInfo]) -> Vec<ColorDetails> {
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSString;
+
+    // Both NSScreen and NSColorSpace have one
+    unsafe fn localized_name(object: &AnyObject) -> Option<String> {
+        let name: *mut NSString = msg_send![object, localizedName];
+        name.as_ref().map(|name| name.to_string())
+    }
+
+    // AppKit wants screens read on the main thread
+    let (tx, rx) = std::sync::mpsc::channel();
+    let queued = app.run_on_main_thread(move || {
+        let mut screens = Vec::new();
+        unsafe {
+            let all: *mut AnyObject = msg_send![class!(NSScreen), screens];
+            if let Some(all) = all.as_ref() {
+                let count: usize = msg_send![all, count];
+                for index in 0..count {
+                    let screen: *mut AnyObject = msg_send![all, objectAtIndex: index];
+                    let Some(screen) = screen.as_ref() else {
+                        continue;
+                    };
+                    let color_space: *mut AnyObject = msg_send![screen, colorSpace];
+                    let profile = color_space.as_ref().and_then(|space| localized_name(space));
+                    // Headroom above 1.0 is extended dynamic range
+                    let headroom: f64 = msg_send![
+                        screen,
+                        maximumPotentialExtendedDynamicRangeColorComponentValue
+                    ];
+                    screens.push((localized_name(screen), (profile, Some(headroom > 1.0))));
+                }
+            }
+        }
+        let _ = tx.send(screens);
+    });
+    let screens = if queued.is_ok() {
+        rx.recv_timeout(Duration::from_secs(1)).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    displays
+        .iter()
+        .map(|display| {
+            screens
+                .iter()
+                .find(|(name, _)| display.name.is_some() && *name == display.name)
+                .map(|(_, details)| details.clone())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Color details of each display, in order
+#[cfg(target_os = "windows")]
+fn color_details<R: Runtime>(_app: &AppHandle<R>, displays: &[DisplayInfo]) -> Vec<ColorDetails> {
+    use windows::core::Interface;
+    use windows::Win32::Graphics::Dxgi::Common::{
+        DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020, DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709,
+    };
+    use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, IDXGIOutput6};
+
+    // A new factory each time; an old one doesn't see HDR being toggled
+    let mut outputs = Vec::new();
+    unsafe {
+        if let Ok(factory) = CreateDXGIFactory1::<IDXGIFactory1>() {
+            for adapter in (0..).map_while(|index| factory.EnumAdapters1(index).ok()) {
+                for output in (0..).map_while(|index| adapter.EnumOutputs(index).ok()) {
+                    if let Ok(desc) = output.cast::<IDXGIOutput6>().and_then(|o| o.GetDesc1()) {
+                        outputs.push(desc);
+                    }
+                }
+            }
+        }
+    }
+
+    displays
+        .iter()
+        .map(|display| {
+            let Some(desc) = outputs.iter().find(|desc| {
+                desc.DesktopCoordinates.left == display.bounds.x
+                    && desc.DesktopCoordinates.top == display.bounds.y
+            }) else {
+                return (None, None);
+            };
+            let hdr = desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
+            let color_space = if hdr {
+                Some("HDR10 (BT.2100 PQ)".to_string())
+            } else if desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G22_NONE_P709 {
+                Some("sRGB".to_string())
+            } else {
+                None
+            };
+            (color_space, Some(hdr))
+        })
+        .collect()
+}
+
+/// Color details of each display, in order
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn color_details<R: Runtime>(_app: &AppHandle<R>, displays: &[DisplayInfo]) -> Vec<ColorDetails> {
+    vec![(None, None); displays.len()]
+}
+
+/// Color info of every connected monitor, in `arrangement` order
+fn color_info<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<DisplayColorInfo>, String> {
+    let displays = arrangement(app)?;
+    let details = color_details(app, &displays);
+    Ok(displays
+        .into_iter()
+        .zip(details)
+        .enumerate()
+        .map(|(index, (display, (color_space, hdr_capable)))| DisplayColorInfo {
+            display_index: index as u32,
+            name: display.name,
+            color_space,
+            hdr_capable,
+        })
+        .collect())
+}
+
+/// Move every window that is off all monitors back onto one
+fn rescue_windows<R: Runtime>(app: &AppHandle<R>) {
+    for window in app.webview_windows().values() {
+        if let Err(e) = crate::window::ensure_on_screen(window) {
+            log::warn!("Failed to check window {} placement: {}", window.label(), e);
+        }
+    }
+}
+
+/// Start watching for display reconfiguration, once the main window exists
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    if !listen(app) {
+        log::warn!("Display changes can't be observed on this system");
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last = arrangement(&app).ok();
+        loop {
+            let state = app.state::<crate::AppState>();
+            state.displays_changed.notified().await;
+            // Reconfiguration arrives as a burst; wait until it's over
+            while tokio::time::timeout(SETTLE_DELAY, state.displays_changed.notified())
+                .await
+                .is_ok()
+            {}
+
+            let Ok(displays) = arrangement(&app) else {
+                continue;
+            };
+            if last.as_ref() == Some(&displays) {
+                continue;
+            }
+            log::info!("Display arrangement changed: {} display(s)", displays.len());
+            crate::events::emit(&app, crate::events::DISPLAYS_CHANGED, displays.clone());
+            last = Some(displays);
+            rescue_windows(&app);
+        }
+    });
+}
+
+/// Poll color profiles and emit `color-profile-changed` for displays whose
+/// profile changed
+pub fn start_color_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last: Vec<DisplayColorInfo> = Vec::new();
+        loop {
+            let handle = app.clone();
+            let current = tauri::async_runtime::spawn_blocking(move || color_info(&handle))
+                .await
+                .ok()
+                .and_then(Result::ok);
+            if let Some(current) = current {
+                for info in &current {
+                    // Displays that came or went are reported by `displays-changed`
+                    let changed = last.iter().any(|old| {
+                        old.name == info.name
+                            && (old.color_space != info.color_space
+                                || old.hdr_capable != info.hdr_capable)
+                    });
+                    if changed {
+                        log::info!("Color profile changed on display {}", info.display_index);
+                        let event = crate::events::COLOR_PROFILE_CHANGED;
+                        crate::events::emit(&app, event, info.clone());
+                    }
+                }
+                last = current;
+            }
+            crate::tasks::tick(&app, "color-profiles", COLOR_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Get every connected monitor's bounds, work area and scale, primary first
+#[tauri::command]
+pub fn get_display_arrangement<R: Runtime>(app: AppHandle<R>) -> Result<Vec<DisplayInfo>, String> {
+    arrangement(&app)
+}
+
+/// Summarize the scale factor of every monitor and how the tray adapts to it
+///
+/// GTK only reports whole scale factors, so on Linux a fractional scale set
+/// in the compositor shows as the next whole factor.
+#[tauri::command]
+pub fn get_effective_scaling<R: Runtime>(app: AppHandle<R>) -> Result<EffectiveScaling, String> {
+    let monitors: Vec<MonitorScaling> = arrangement(&app)?
+        .into_iter()
+        .map(|display| MonitorScaling {
+            name: display.name,
+            scale_factor: display.scale_factor,
+            fractional: display.scale_factor.fract() != 0.0,
+            icon_scale: crate::tray::icon_scale(display.scale_factor),
+        })
+        .collect();
+    let mixed = monitors
+        .windows(2)
+        .any(|pair| pair[0].scale_factor != pair[1].scale_factor);
+    Ok(EffectiveScaling { monitors, mixed })
+}
+
+/// Get a display's color space and HDR support, by its index in
+/// `get_display_arrangement`
+///
+/// Fields the OS doesn't report are `null`; Linux reports neither.
+#[tauri::command]
+pub async fn get_display_color_info<R: Runtime>(
+    app: AppHandle<R>,
+    display_index: u32,
+) -> Result<DisplayColorInfo, CommandError> {
+    let displays = tauri::async_runtime::spawn_blocking(move || color_info(&app))
+        .await
+        .map_err(|e| e.to_string())??;
+    displays
+        .into_iter()
+        .nth(display_index as usize)
+        .ok_or_else(|| CommandError::NotFound(format!("No display at index {}", display_index)))
+}
@@ -0,0 +1,72 @@
+//! Dock and taskbar icon for YULA Desktop
+//!
+//! Swaps the whole app icon at runtime, e.g. for a red-dot variant while
+//! there are unread messages. On macOS this replaces the dock tile image via
+//! `NSApplication`; elsewhere the main window icon is replaced, which is
+//! what the Windows taskbar (`WM_SETICON`) and most Linux docks show.
+
+use tauri::{AppHandle, Runtime};
+
+/// Replace the dock / taskbar icon with a base64-encoded PNG
+#[tauri::command]
+pub async fn set_dock_icon<R: Runtime>(
+    app: AppHandle<R>,
+    base64_png: String,
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let (bytes, _) = crate::tray::decode_png(&base64_png)?;
+        app.run_on_main_thread(move || macos::set_icon(Some(&bytes)))
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let (_, icon) = crate::tray::decode_png(&base64_png)?;
+        crate::window::main_window(&app)?
+            .set_icon(icon)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Restore the bundled dock / taskbar icon
+#[tauri::command]
+pub async fn reset_dock_icon<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        app.run_on_main_thread(|| macos::set_icon(None))
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let icon = app.default_window_icon().ok_or("No default icon")?.clone();
+        crate::window::main_window(&app)?
+            .set_icon(icon)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::rc::{Allocated, Retained};
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSData;
+
+    /// Set the dock tile image, or restore the bundle icon with `None`
+    pub fn set_icon(png: Option<&[u8]>) {
+        unsafe {
+            let image: Option<Retained<AnyObject>> = png.and_then(|bytes| {
+                let data = NSData::with_bytes(bytes);
+                let image: Allocated<AnyObject> = msg_send![class!(NSImage), alloc];
+                msg_send![image, initWithData: &*data]
+            });
+
+            let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            if let Some(app) = app.as_ref() {
+                let _: () = msg_send![app, setApplicationIconImage: image.as_deref()];
+            }
+        }
+    }
+}
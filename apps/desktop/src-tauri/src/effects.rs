@@ -0,0 +1,283 @@
+//! Window translucency for YULA Desktop
+//!
+//! Background blur maps to vibrancy materials on macOS and Mica/Acrylic on
+//! Windows 11; a material the current platform doesn't have is ignored.
+//! Opacity isn't exposed by Tauri, so it's set on the native window.
+//! Both are persisted and re-applied when the main window is created.
+//!
+//! Blur only shows through a transparent window, and transparency can only
+//! be chosen when the window is created, so the main window is transparent
+//! only when it starts with effects on. Turning them on for the first time
+//! takes a restart. On macOS a transparent webview needs Tauri's
+//! `macos-private-api`.
+//!
+//! Windows without decorations get square corners and no shadow by default;
+//! `set_window_decorations_style` rounds them by masking the content layer
+//! on macOS and through the DWM corner preference on Windows 11, whose
+//! rounding comes in two fixed sizes. Shadows are toggled through Tauri.
+//! Linux compositors decide both, so the call does nothing there.
+
+use serde::{Deserialize, Serialize};
+use tauri::window::{Effect, EffectsBuilder};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+/// Background blur material
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlurMaterial {
+    /// macOS sidebar vibrancy
+    Sidebar,
+    /// macOS popover vibrancy
+    Popover,
+    /// macOS HUD window vibrancy
+    HudWindow,
+    /// macOS material behind the window
+    UnderWindowBackground,
+    /// Windows 11 Mica
+    Mica,
+    /// Windows 10/11 Acrylic
+    Acrylic,
+    /// Windows 7/10/11 blur
+    Blur,
+}
+
+impl From<BlurMaterial> for Effect {
+    fn from(material: BlurMaterial) -> Self {
+        match material {
+            BlurMaterial::Sidebar => Effect::Sidebar,
+            BlurMaterial::Popover => Effect::Popover,
+            BlurMaterial::HudWindow => Effect::HudWindow,
+            BlurMaterial::UnderWindowBackground => Effect::UnderWindowBackground,
+            BlurMaterial::Mica => Effect::Mica,
+            BlurMaterial::Acrylic => Effect::Acrylic,
+            BlurMaterial::Blur => Effect::Blur,
+        }
+    }
+}
+
+/// Background effects applied to the main window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowEffects {
+    /// Materials to try, in order; each platform uses the ones it supports
+    pub materials: Vec<BlurMaterial>,
+    /// Corner radius of the effect view (macOS only)
+    pub radius: Option<f64>,
+}
+
+/// Corner and shadow style for a window without decorations
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DecorationsStyle {
+    /// Corner radius in logical pixels; 0 for square corners
+    pub corner_radius: f64,
+    pub shadow: bool,
+}
+
+/// Re-apply persisted effects and opacity to a newly created window
+pub fn restore<R: Runtime>(app: &AppHandle<R>, window: &WebviewWindow<R>) {
+    let settings = crate::settings::get(app);
+
+    if let Some(effects) = settings.window_effects {
+        if let Err(e) = apply_effects(window, &effects) {
+            log::warn!("Failed to restore window effects: {}", e);
+        }
+    }
+
+    if settings.window_opacity < 1.0 {
+        if let Err(e) = apply_opacity(window, settings.window_opacity) {
+            log::warn!("Failed to restore window opacity: {}", e);
+        }
+    }
+}
+
+fn apply_effects<R: Runtime>(
+    window: &WebviewWindow<R>,
+    effects: &WindowEffects,
+) -> Result<(), String> {
+    let mut builder =
+        EffectsBuilder::new().effects(effects.materials.iter().copied().map(Effect::from));
+    if let Some(radius) = effects.radius {
+        builder = builder.radius(radius);
+    }
+
+    window.set_effects(builder.build()).map_err(|e| e.to_string())
+}
+
+/// Check a window opacity before it's stored
+pub fn validate_opacity(opacity: f64) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&opacity) {
+        return Err(format!("Opacity must be between 0 and 1, got {}", opacity));
+    }
+    Ok(())
+}
+
+fn apply_opacity<R: Runtime>(window: &WebviewWindow<R>, opacity: f64) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        // Raw pointers aren't Send, so hand the address to the main thread
+        let ns_window = window.ns_window().map_err(|e| e.to_string())? as usize;
+        window
+            .run_on_main_thread(move || unsafe {
+                let ns_window = &*(ns_window as *const AnyObject);
+                let _: () = objc2::msg_send![ns_window, setAlphaValue: opacity];
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::COLORREF;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+            LWA_ALPHA, WS_EX_LAYERED,
+        };
+
+        let hwnd = window.hwnd().map_err(|e| e.to_string())?;
+        let alpha = (opacity * 255.0).round() as u8;
+        unsafe {
+            let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as isize);
+            SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use gtk::prelude::WidgetExt;
+
+        window
+            .gtk_window()
+            .map(|gtk_window| gtk_window.set_opacity(opacity))
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn apply_corner_radius<R: Runtime>(window: &WebviewWindow<R>, radius: f64) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        let ns_window = window.ns_window().map_err(|e| e.to_string())? as usize;
+        window
+            .run_on_main_thread(move || unsafe {
+                let ns_window = &*(ns_window as *const AnyObject);
+                let view: *mut AnyObject = objc2::msg_send![ns_window, contentView];
+                let Some(view) = view.as_ref() else {
+                    return;
+                };
+                // Corners outside the mask must show through the window
+                let clear: *mut AnyObject = objc2::msg_send![objc2::class!(NSColor), clearColor];
+                let _: () = objc2::msg_send![ns_window, setOpaque: false];
+                let _: () = objc2::msg_send![ns_window, setBackgroundColor: clear];
+                let _: () = objc2::msg_send![view, setWantsLayer: true];
+                let layer: *mut AnyObject = objc2::msg_send![view, layer];
+                if let Some(layer) = layer.as_ref() {
+                    let _: () = objc2::msg_send![layer, setCornerRadius: radius];
+                    let _: () = objc2::msg_send![layer, setMasksToBounds: radius > 0.0];
+                }
+                // The shadow follows the old outline until it's recomputed
+                let _: () = objc2::msg_send![ns_window, invalidateShadow];
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Graphics::Dwm::{
+            DwmSetWindowAttribute, DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DONOTROUND,
+            DWMWCP_ROUND, DWMWCP_ROUNDSMALL,
+        };
+
+        let preference = if radius <= 0.0 {
+            DWMWCP_DONOTROUND
+        } else if radius <= 4.0 {
+            DWMWCP_ROUNDSMALL
+        } else {
+            DWMWCP_ROUND
+        };
+        let hwnd = window.hwnd().map_err(|e| e.to_string())?;
+        let result = unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_WINDOW_CORNER_PREFERENCE,
+                &preference as *const _ as *const _,
+                std::mem::size_of_val(&preference) as u32,
+            )
+        };
+        // Windows 10 doesn't know the attribute and keeps square corners
+        if let Err(e) = result {
+            log::debug!("Corner preference not applied: {}", e);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (window, radius);
+        Ok(())
+    }
+}
+
+/// Apply background blur effects to the main window and persist them
+///
+/// Returns whether a restart is required for them to show, which is the
+/// case when the window was created opaque.
+#[tauri::command]
+pub async fn set_window_effects<R: Runtime>(
+    app: AppHandle<R>,
+    effects: WindowEffects,
+) -> Result<bool, String> {
+    apply_effects(&crate::window::main_window(&app)?, &effects)?;
+    crate::settings::update(&app, |settings| settings.window_effects = Some(effects))?;
+
+    let transparent = app
+        .state::<crate::AppState>()
+        .main_transparent
+        .load(std::sync::atomic::Ordering::Relaxed);
+    Ok(cfg!(any(target_os = "macos", target_os = "windows")) && !transparent)
+}
+
+/// Remove background effects from the main window
+#[tauri::command]
+pub async fn clear_window_effects<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    crate::window::main_window(&app)?
+        .set_effects(None)
+        .map_err(|e| e.to_string())?;
+    crate::settings::update(&app, |settings| settings.window_effects = None)
+}
+
+/// Set the main window opacity (0.0 - 1.0) and persist it
+#[tauri::command]
+pub async fn set_window_opacity<R: Runtime>(
+    app: AppHandle<R>,
+    opacity: f64,
+) -> Result<(), String> {
+    validate_opacity(opacity)?;
+    apply_opacity(&crate::window::main_window(&app)?, opacity)?;
+    crate::settings::update(&app, |settings| settings.window_opacity = opacity)
+}
+
+/// Round the corners of and add a shadow to a window without decorations
+///
+/// Does nothing on Linux, where the compositor draws both.
+#[tauri::command]
+pub async fn set_window_decorations_style<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    style: DecorationsStyle,
+) -> Result<(), String> {
+    if !(0.0..=64.0).contains(&style.corner_radius) {
+        return Err(format!(
+            "Corner radius must be between 0 and 64, got {}",
+            style.corner_radius
+        ));
+    }
+
+    let window = crate::window::get_window(&app, &label)?;
+    if cfg!(target_os = "linux") {
+        return Ok(());
+    }
+    window.set_shadow(style.shadow).map_err(|e| e.to_string())?;
+    apply_corner_radius(&window, style.corner_radius)
+}
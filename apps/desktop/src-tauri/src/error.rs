@@ -0,0 +1,59 @@
+//! Typed errors for YULA Desktop commands
+//!
+//! Most commands return plain `String` errors. Commands whose failures the
+//! frontend needs to branch on return `CommandError` instead, which is
+//! serialized as `{ kind, message }`.
+
+use serde::Serialize;
+
+/// Error returned by commands that expose machine-readable failure kinds
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum CommandError {
+    /// The accelerator string could not be parsed
+    InvalidAccelerator(String),
+    /// The accelerator is already bound, by us or by another application
+    ShortcutAlreadyRegistered(String),
+    /// An imported settings file has fields that failed validation
+    InvalidSettings(Vec<String>),
+    /// The current platform lacks the required API
+    NotSupported(String),
+    /// The user hasn't granted a required OS permission
+    PermissionDenied(String),
+    /// A file or other resource doesn't exist
+    NotFound(String),
+    /// No window has the given label
+    WindowNotFound(String),
+    /// The action is forbidden by a configured policy
+    PolicyViolation(String),
+    /// Any other failure
+    Failed(String),
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAccelerator(msg) => write!(f, "Invalid accelerator: {}", msg),
+            Self::ShortcutAlreadyRegistered(msg) => {
+                write!(f, "Shortcut already registered: {}", msg)
+            }
+            Self::InvalidSettings(fields) => {
+                write!(f, "Invalid settings fields: {}", fields.join(", "))
+            }
+            Self::NotSupported(msg) => write!(f, "Not supported: {}", msg),
+            Self::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            Self::NotFound(msg) => write!(f, "Not found: {}", msg),
+            Self::WindowNotFound(label) => write!(f, "Window not found: {}", label),
+            Self::PolicyViolation(msg) => write!(f, "Policy violation: {}", msg),
+            Self::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<String> for CommandError {
+    fn from(msg: String) -> Self {
+        Self::Failed(msg)
+    }
+}
@@ -0,0 +1,290 @@
+//! Backend event bus for YULA Desktop
+//!
+//! All backend events go through `emit` so they can optionally be recorded
+//! in a ring buffer. A reloading frontend can replay what it missed with
+//! `replay_events`. Recording is off by default in release builds and can be
+//! toggled from the advanced settings.
+//!
+//! Event names are declared once in the manifest below, which emitters
+//! reference and `get_event_manifest` exposes to the frontend.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime};
+
+/// Maximum number of events kept in the ring buffer
+const MAX_RECORDED: usize = 200;
+
+/// An event recorded by the event bus
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordedEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub target: Option<String>,
+    pub timestamp: i64,
+}
+
+/// Ring buffer of recently emitted events
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<RecordedEvent>,
+}
+
+impl EventLog {
+    fn push(&mut self, event: RecordedEvent) {
+        if self.events.len() >= MAX_RECORDED {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// An event the backend can emit
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EventInfo {
+    pub name: &'static str,
+    /// TypeScript type of the payload, as declared in the frontend's `tauri.ts`
+    pub payload: &'static str,
+    pub description: &'static str,
+}
+
+/// Declare the event name constants and the manifest listing them, so the
+/// two can't drift apart
+macro_rules! events {
+    ($($constant:ident = $name:literal, $payload:literal, $description:literal;)*) => {
+        $(
+            #[doc = $description]
+            pub const $constant: &str = $name;
+        )*
+
+        /// Every event the backend emits
+        pub const MANIFEST: &[EventInfo] = &[$(EventInfo {
+            name: $name,
+            payload: $payload,
+            description: $description,
+        }),*];
+    };
+}
+
+events! {
+    APPEARANCE_CHANGED = "appearance-changed", "AppearanceChange",
+        "The appearance override or the followed OS theme changed";
+    AUDIO_LEVEL = "audio-level", "AudioLevel",
+        "Microphone RMS and peak level, about 20 times a second while monitoring";
+    AUTH_HANDOFF = "auth-handoff", "{ token: string }",
+        "A valid `yula://auth` handoff link arrived; exchange the token for a session";
+    AUTOSTART_BROKEN = "autostart-broken", "{ registered_path: string | null }",
+        "The launch-at-login entry doesn't point at this install; offer to repair it";
+    AUTO_LOCK = "auto-lock", "AutoLockEvent",
+        "The app locked itself after idling or sleep; ask to sign in again";
+    CLIPBOARD_CHANGED = "clipboard-changed", "string | null",
+        "The clipboard changed; carries its text, null for other content";
+    CLOCK_SKEW_DETECTED = "clock-skew-detected", "ClockSkew",
+        "The system clock differs from a time server by more than a minute";
+    COLOR_PROFILE_CHANGED = "color-profile-changed", "DisplayColorInfo",
+        "A display's color space or HDR support changed";
+    DEEP_LINK = "deep-link", "DeepLink",
+        "A `yula://` link was opened; sent to the window its route maps to";
+    DEEP_LINK_ERROR = "deep-link-error", "DeepLinkError",
+        "A `yula://` link was malformed or names a page this version doesn't have";
+    DISPLAYS_CHANGED = "displays-changed", "DisplayInfo[]",
+        "Monitors were connected, removed or rearranged";
+    FILE_OPENED = "file-opened", "{ path: string }", "A file was opened with the app";
+    FS_CHANGE = "fs-change", "FsChangeEvent", "A watched directory changed";
+    GLOBAL_SHORTCUT = "global-shortcut", "{ action_id: string }",
+        "A registered global shortcut was pressed";
+    HASH_PROGRESS = "hash-progress", "HashProgress", "Progress of hashing a large file";
+    HIGH_CONTRAST_CHANGED = "high-contrast-changed", "boolean",
+        "The OS high-contrast setting changed";
+    INTEGRATION_ACTION = "integration-action", "IntegrationAction",
+        "An action arrived from the local integration server or OS automation";
+    KEYBOARD_LAYOUT_CHANGED = "keyboard-layout-changed", "string",
+        "The active keyboard layout changed";
+    LOCAL_SHORTCUT = "local-shortcut", "{ action_id: string }",
+        "A focus-only shortcut was pressed while the main window had focus";
+    LOW_POWER_MODE_CHANGED = "low-power-mode-changed", "boolean",
+        "The OS low-power or battery-saver mode was turned on or off";
+    MEDIA_KEY = "media-key", "{ action: MediaKeyAction }", "A hardware media key was pressed";
+    MINI_MODE_CHANGED = "mini-mode-changed", "boolean", "The main window entered or left mini mode";
+    NOTIFICATION_ACTIVATED = "notification-activated", "{ id: number }",
+        "A notification was clicked; the main window was raised";
+    NOTIFICATION_DIGEST_CLICK = "notification-digest-click", "NotificationDigest",
+        "The notification digest was clicked; open the chats it covered";
+    NOTIFICATION_DISMISSED = "notification-dismissed", "{ id: number }",
+        "A notification was cleared without acting on it (not in unbundled macOS builds)";
+    OFFER_ROLLBACK = "offer-rollback", "RollbackInfo",
+        "The updated version keeps crashing and can be rolled back";
+    OPERATION_PROGRESS = "operation-progress", "OperationProgress",
+        "A multi-step operation started, moved to another step or progressed, or finished";
+    PRESENCE_FAILED = "presence-failed", "string", "A presence heartbeat ping failed";
+    PRESENCE_SENT = "presence-sent", "number",
+        "A presence heartbeat ping succeeded; carries the Unix time in ms";
+    PROTOCOL_HANDLER_CONFLICT = "protocol-handler-conflict", "{ owner: string }",
+        "`yula://` links open another install of the app";
+    QUICK_CAPTURE_REGION = "quick-capture-region", "CaptureRegion",
+        "A screen region was selected in quick capture";
+    REDUCE_MOTION_CHANGED = "reduce-motion-changed", "boolean",
+        "The effective reduce-motion preference changed";
+    SCALE_FACTOR_CHANGED = "scale-factor-changed", "ScaleFactorChange",
+        "A window moved to a display with a different scale factor";
+    SCREEN_CAPTURE_CHANGED = "screen-capture-changed", "boolean",
+        "Screen sharing or recording started or stopped";
+    SELF_TEST_FAILED = "self-test-failed", "SelfTestCheck[]",
+        "Self-test checks that passed before now fail";
+    SERIAL_DEVICES_CHANGED = "serial-devices-changed", "SerialPortInfo[]",
+        "Serial devices were connected or disconnected";
+    SETTINGS_IMPORTED = "settings-imported", "string[]",
+        "Settings fields were imported from a file";
+    SETTINGS_RESET = "settings-reset", "SettingsResetEvent",
+        "The settings file was unreadable and reset to defaults";
+    SHOW_ABOUT = "show-about", "AboutInfo",
+        "The frontend should render the About panel (non-macOS)";
+    SIGNATURE_INVALID = "signature-invalid", "SignatureStatus",
+        "The app's code signature is missing or doesn't verify";
+    SYSTEM_LOG_LINE = "system-log-line", "SystemLogLine", "A line from an OS log tail";
+    THROTTLE_MODE = "throttle-mode", "boolean", "Background throttling started or stopped";
+    TRAY_ACTION = "tray-action", "TrayAction", "A tray menu item was chosen";
+    UPDATE_AVAILABLE = "update-available", "UpdateInfo", "A newer version is available";
+    UPDATE_DOWNLOAD_PROGRESS = "update-download-progress", "DownloadProgress",
+        "Bytes of the update downloaded so far";
+    UPDATE_STAGED = "update-staged", "{ version: string }",
+        "An update was installed and takes effect on restart; background work is paused";
+    UPDATED_FROM = "updated-from", "UpdatedFrom",
+        "This launch is the first since an update; sent once the frontend is ready";
+    UPDATER_STATE_CHANGED = "updater-state-changed", "UpdaterState",
+        "The updater moved between idle, checking, downloading, installing and so on";
+    WEBVIEW_MEMORY_HIGH = "webview-memory-high", "WebviewMemoryHigh",
+        "Webview memory crossed the configured limit";
+    WINDOW_OCCLUSION_CHANGED = "window-occlusion-changed", "{ label: string; occluded: boolean }",
+        "A window became covered or visible again";
+}
+
+/// Emit an event to every listener
+pub fn emit<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    debug_assert!(is_declared(event), "Event missing from the manifest: {}", event);
+    record(app, event, &payload, None);
+    if let Err(e) = app.emit(event, payload) {
+        log::warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+/// Emit an event to a single window
+pub fn emit_to<R: Runtime, S: Serialize + Clone>(
+    app: &AppHandle<R>,
+    label: &str,
+    event: &str,
+    payload: S,
+) {
+    debug_assert!(is_declared(event), "Event missing from the manifest: {}", event);
+    record(app, event, &payload, Some(label));
+    if let Err(e) = app.emit_to(EventTarget::webview_window(label), event, payload) {
+        log::warn!("Failed to emit {} to {}: {}", event, label, e);
+    }
+}
+
+fn is_declared(event: &str) -> bool {
+    MANIFEST.iter().any(|info| info.name == event)
+}
+
+fn record<R: Runtime, S: Serialize>(
+    app: &AppHandle<R>,
+    event: &str,
+    payload: &S,
+    target: Option<&str>,
+) {
+    let Some(state) = app.try_state::<crate::AppState>() else {
+        return;
+    };
+    if !state.record_events.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+    state.event_log.lock().unwrap().push(RecordedEvent {
+        event: event.to_string(),
+        payload,
+        target: target.map(str::to_string),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    });
+}
+
+/// Enable recording by default in debug builds or when the user opted in
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = cfg!(debug_assertions) || crate::settings::get(app).record_events;
+    app.state::<crate::AppState>()
+        .record_events
+        .store(enabled, Ordering::Relaxed);
+}
+
+/// Enable or disable event recording and persist the choice
+#[tauri::command]
+pub async fn set_event_recording<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    state.record_events.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        state.event_log.lock().unwrap().events.clear();
+    }
+
+    crate::settings::update(&app, |settings| settings.record_events = enabled)
+}
+
+/// Get the recorded events, oldest first
+#[tauri::command]
+pub fn get_recent_events<R: Runtime>(app: AppHandle<R>) -> Vec<RecordedEvent> {
+    app.state::<crate::AppState>()
+        .event_log
+        .lock()
+        .unwrap()
+        .events
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Re-emit recorded events newer than `since` (Unix millis)
+///
+/// Returns the number of events replayed. Replayed events aren't recorded
+/// again.
+#[tauri::command]
+pub fn replay_events<R: Runtime>(app: AppHandle<R>, since: i64) -> Result<usize, String> {
+    let state = app.state::<crate::AppState>();
+    if !state.record_events.load(Ordering::Relaxed) {
+        return Err("Event recording is disabled".to_string());
+    }
+
+    let events: Vec<RecordedEvent> = state
+        .event_log
+        .lock()
+        .unwrap()
+        .events
+        .iter()
+        .filter(|recorded| recorded.timestamp > since)
+        .cloned()
+        .collect();
+
+    for recorded in &events {
+        let result = match &recorded.target {
+            Some(label) => app.emit_to(
+                EventTarget::webview_window(label),
+                &recorded.event,
+                recorded.payload.clone(),
+            ),
+            None => app.emit(&recorded.event, recorded.payload.clone()),
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to replay {}: {}", recorded.event, e);
+        }
+    }
+
+    Ok(events.len())
+}
+
+/// List every event the backend can emit, with its payload type
+#[tauri::command]
+pub fn get_event_manifest() -> Vec<EventInfo> {
+    MANIFEST.to_vec()
+}
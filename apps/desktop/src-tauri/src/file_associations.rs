@@ -0,0 +1,332 @@
+//! File-type associations for YULA Desktop
+//!
+//! Registers the app as the handler for file extensions such as `.yula`
+//! exports, per user: ProgID keys under `HKCU\Software\Classes` on Windows,
+//! a shared-mime-info package plus a `.desktop` entry on Linux. macOS reads
+//! document types from `CFBundleDocumentTypes` in the bundle's Info.plist, so
+//! only extensions declared in `bundle.fileAssociations` can be handled there.
+//!
+//! Files opened through an association arrive as command line arguments,
+//! either at launch or forwarded from a second instance, or on macOS through
+//! the app delegate's open-files callback, and are emitted as `file-opened`,
+//! one event per file. Files opened before `frontend_ready` are held and
+//! emitted once the frontend is listening, so a cold launch doesn't drop them.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::CommandError;
+
+/// Payload of the `file-opened` event
+#[derive(Debug, Clone, Serialize)]
+struct FileOpenedPayload {
+    path: String,
+}
+
+/// Files among command line arguments, resolved against `cwd`
+pub fn file_args(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+    args.iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn emit<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+    crate::events::emit(
+        app,
+        crate::events::FILE_OPENED,
+        FileOpenedPayload {
+            path: path.to_string_lossy().to_string(),
+        },
+    );
+}
+
+/// Forward a file opened through an association to the main window
+pub fn open<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+    log::info!("File opened: {}", path.display());
+
+    let state = app.state::<crate::AppState>();
+    if !state.frontend_ready.load(Ordering::SeqCst) {
+        state.pending_files.lock().unwrap().push(path.to_path_buf());
+        return;
+    }
+
+    if let Some(window) = app.get_webview_window(crate::window::MAIN_WINDOW) {
+        emit(app, path);
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Emit the files opened before the frontend was ready
+pub fn flush_pending<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    let pending = std::mem::take(&mut *state.pending_files.lock().unwrap());
+    if pending.is_empty() {
+        return;
+    }
+
+    log::info!("Forwarding {} file(s) opened at launch", pending.len());
+    for path in &pending {
+        emit(app, path);
+    }
+    if let Ok(window) = crate::window::main_window(app) {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Open the files among URLs delivered by the macOS app delegate
+///
+/// `yula://` links arrive the same way but are handled by the deep-link
+/// plugin.
+#[cfg(target_os = "macos")]
+pub fn on_opened<R: Runtime>(app: &AppHandle<R>, urls: &[tauri::Url]) {
+    for url in urls.iter().filter(|url| url.scheme() == "file") {
+        match url.to_file_path() {
+            Ok(path) if path.is_file() => open(app, &path),
+            _ => log::warn!("Ignoring opened URL: {}", url),
+        }
+    }
+}
+
+/// Emit `file-opened` for files passed on the launch command line
+pub fn handle_launch_args<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(cwd) = std::env::current_dir() else {
+        return;
+    };
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    for path in file_args(&args, &cwd) {
+        open(app, &path);
+    }
+}
+
+/// Normalize an extension to lowercase without a leading dot
+fn normalize(extension: &str) -> Result<String, CommandError> {
+    let extension = extension.trim().trim_start_matches('.').to_lowercase();
+    if extension.is_empty() || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(CommandError::Failed(format!(
+            "Invalid file extension: {:?}",
+            extension
+        )));
+    }
+    Ok(extension)
+}
+
+/// Whether the bundle declares the extension in `bundle.fileAssociations`
+#[cfg(target_os = "macos")]
+fn declared_in_bundle<R: Runtime>(app: &AppHandle<R>, extension: &str) -> bool {
+    app.config()
+        .bundle
+        .file_associations
+        .iter()
+        .flatten()
+        .flat_map(|association| association.ext.iter())
+        .any(|ext| ext.0.eq_ignore_ascii_case(extension))
+}
+
+/// Register the app as the handler for a file extension
+#[tauri::command]
+pub async fn register_file_association<R: Runtime>(
+    app: AppHandle<R>,
+    extension: String,
+) -> Result<(), CommandError> {
+    let extension = normalize(&extension)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        if declared_in_bundle(&app, &extension) {
+            Ok(())
+        } else {
+            Err(CommandError::NotSupported(format!(
+                ".{} is not declared in the app bundle",
+                extension
+            )))
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        win::register(&app, &extension).map_err(CommandError::Failed)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::register(&app, &extension).map_err(CommandError::Failed)
+    }
+}
+
+/// Check whether the app is the registered handler for a file extension
+#[tauri::command]
+pub async fn is_file_association_registered<R: Runtime>(
+    app: AppHandle<R>,
+    extension: String,
+) -> Result<bool, CommandError> {
+    let extension = normalize(&extension)?;
+
+    #[cfg(target_os = "macos")]
+    let registered = declared_in_bundle(&app, &extension);
+    #[cfg(target_os = "windows")]
+    let registered = win::is_registered(&app, &extension);
+    #[cfg(target_os = "linux")]
+    let registered = linux::is_registered(&app, &extension);
+
+    Ok(registered)
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    use tauri::{AppHandle, Runtime};
+
+    fn prog_id<R: Runtime>(app: &AppHandle<R>, extension: &str) -> String {
+        format!("{}.{}", app.config().identifier, extension)
+    }
+
+    fn reg_add(key: &str, value: &str) -> Result<(), String> {
+        let status = crate::platform::command("reg")
+            .args(["add", key, "/ve", "/d", value, "/f"])
+            .status()
+            .map_err(|e| e.to_string())?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("Failed to write registry key {}", key))
+        }
+    }
+
+    pub fn register<R: Runtime>(app: &AppHandle<R>, extension: &str) -> Result<(), String> {
+        use windows::Win32::UI::Shell::{SHChangeNotify, SHCNE_ASSOCCHANGED, SHCNF_IDLIST};
+
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let prog_id = prog_id(app, extension);
+        let classes = r"HKCU\Software\Classes";
+
+        reg_add(&format!(r"{}\.{}", classes, extension), &prog_id)?;
+        reg_add(
+            &format!(r"{}\{}\shell\open\command", classes, prog_id),
+            &format!("\"{}\" \"%1\"", exe.display()),
+        )?;
+
+        // Let Explorer pick up the new handler without a sign-out
+        unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST, None, None) };
+        Ok(())
+    }
+
+    pub fn is_registered<R: Runtime>(app: &AppHandle<R>, extension: &str) -> bool {
+        let key = format!(r"HKCU\Software\Classes\.{}", extension);
+        crate::platform::output("reg", &["query", &key, "/ve"])
+            .is_some_and(|output| output.contains(&prog_id(app, extension)))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::path::PathBuf;
+    use tauri::{AppHandle, Manager, Runtime};
+
+    fn mime_type(extension: &str) -> String {
+        format!("application/x-{}", extension)
+    }
+
+    fn desktop_file<R: Runtime>(app: &AppHandle<R>) -> String {
+        format!("{}.desktop", app.config().identifier)
+    }
+
+    fn data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+        app.path().data_dir().map_err(|e| e.to_string())
+    }
+
+    fn run(program: &str, args: &[&str]) -> Result<(), String> {
+        let status = crate::platform::command(program)
+            .args(args)
+            .status()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("{} exited with {}", program, status))
+        }
+    }
+
+    pub fn register<R: Runtime>(app: &AppHandle<R>, extension: &str) -> Result<(), String> {
+        let data_dir = data_dir(app)?;
+        let mime = mime_type(extension);
+        let product_name = app
+            .config()
+            .product_name
+            .clone()
+            .unwrap_or_else(|| "YULA".to_string());
+
+        // Teach shared-mime-info about the extension
+        let mime_dir = data_dir.join("mime");
+        let packages_dir = mime_dir.join("packages");
+        std::fs::create_dir_all(&packages_dir).map_err(|e| e.to_string())?;
+        let package = format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n",
+                "  <mime-type type=\"{mime}\">\n",
+                "    <comment>{name} file</comment>\n",
+                "    <glob pattern=\"*.{ext}\"/>\n",
+                "  </mime-type>\n",
+                "</mime-info>\n"
+            ),
+            mime = mime,
+            name = product_name,
+            ext = extension
+        );
+        let package_path =
+            packages_dir.join(format!("{}-{}.xml", app.config().identifier, extension));
+        std::fs::write(&package_path, package).map_err(|e| e.to_string())?;
+        run("update-mime-database", &[&mime_dir.to_string_lossy()])?;
+
+        // (Re)write our desktop entry, keeping previously registered types
+        let applications_dir = data_dir.join("applications");
+        std::fs::create_dir_all(&applications_dir).map_err(|e| e.to_string())?;
+        let entry_path = applications_dir.join(desktop_file(app));
+        let mut mime_types: Vec<String> = std::fs::read_to_string(&entry_path)
+            .ok()
+            .and_then(|entry| {
+                entry
+                    .lines()
+                    .find_map(|line| line.strip_prefix("MimeType="))
+                    .map(|types| types.split(';').map(str::to_string).collect())
+            })
+            .unwrap_or_default();
+        mime_types.retain(|t| !t.is_empty());
+        if !mime_types.contains(&mime) {
+            mime_types.push(mime.clone());
+        }
+
+        // AppImages are run through a mounted path that changes every launch
+        let exe = std::env::var_os("APPIMAGE")
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_exe().ok())
+            .ok_or("Failed to resolve executable path")?;
+        let entry = format!(
+            concat!(
+                "[Desktop Entry]\n",
+                "Type=Application\n",
+                "Name={name}\n",
+                "Exec=\"{exe}\" %f\n",
+                "MimeType={types};\n",
+                "NoDisplay=true\n"
+            ),
+            name = product_name,
+            exe = exe.display(),
+            types = mime_types.join(";")
+        );
+        std::fs::write(&entry_path, entry).map_err(|e| e.to_string())?;
+        run("update-desktop-database", &[&applications_dir.to_string_lossy()])?;
+
+        run("xdg-mime", &["default", &desktop_file(app), &mime])
+    }
+
+    pub fn is_registered<R: Runtime>(app: &AppHandle<R>, extension: &str) -> bool {
+        crate::platform::output("xdg-mime", &["query", "default", &mime_type(extension)])
+            .is_some_and(|handler| handler == desktop_file(app))
+    }
+}
@@ -0,0 +1,359 @@
+//! Scoped file access for YULA Desktop
+//!
+//! Large files (e.g. transcript imports) are read through streams so they
+//! never have to be loaded into memory or sent over IPC in one piece. Open
+//! streams live in `AppState`; ones left idle are closed by a background
+//! reaper so a reloading frontend can't leak handles.
+//!
+//! Files can also be hashed for integrity checks, streamed the same way.
+//!
+//! Paths must be within the fs plugin scope (which includes files picked
+//! through the dialog plugin) or one of the app's own directories.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::CommandError;
+
+/// Largest chunk returned by a single read
+const MAX_CHUNK_BYTES: usize = 8 * 1024 * 1024;
+
+/// Streams idle for longer than this are closed
+const STREAM_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How often idle streams are looked for
+const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Read size used while hashing
+const HASH_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Files smaller than this are hashed without progress events
+const HASH_PROGRESS_THRESHOLD: u64 = 32 * 1024 * 1024;
+
+/// Identifier of an open file stream
+pub type StreamId = u32;
+
+#[derive(Debug)]
+struct OpenStream {
+    file: File,
+    last_used: Instant,
+}
+
+/// File streams opened by the frontend
+#[derive(Debug, Default)]
+pub struct FileStreams {
+    next_id: StreamId,
+    open: HashMap<StreamId, OpenStream>,
+}
+
+/// Supported digest algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+/// Payload of the `hash-progress` event
+#[derive(Debug, Clone, Serialize)]
+struct HashProgress {
+    path: String,
+    bytes_read: u64,
+    total_bytes: u64,
+}
+
+/// Streaming hasher over the supported algorithms
+enum Hasher {
+    Sha256(sha2::Sha256),
+    Sha512(sha2::Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        use sha2::Digest;
+
+        match algorithm {
+            HashAlgorithm::Sha256 => Self::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Sha512 => Self::Sha512(sha2::Sha512::new()),
+            HashAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        use sha2::Digest;
+
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Sha512(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    /// Finish and return the lowercase hex digest
+    fn finalize(self) -> String {
+        use sha2::Digest;
+
+        let digest: Vec<u8> = match self {
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+            Self::Sha512(hasher) => hasher.finalize().to_vec(),
+            Self::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+        };
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Resolve symlinks in a path, returning whether it exists
+///
+/// A missing path resolves through its deepest existing ancestor, so its
+/// scope can still be checked; one that climbs out with `..` past a missing
+/// component doesn't resolve.
+fn resolve(path: &Path) -> std::io::Result<(PathBuf, bool)> {
+    use std::path::Component;
+
+    let path = std::path::absolute(path)?;
+    let mut missing = Vec::new();
+    for ancestor in path.ancestors() {
+        match ancestor.canonicalize() {
+            Ok(resolved) => {
+                let exists = missing.is_empty();
+                let resolved = missing
+                    .into_iter()
+                    .rev()
+                    .fold(resolved, |path, name| path.join(name));
+                return Ok((resolved, exists));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                match ancestor.components().next_back() {
+                    Some(Component::Normal(name)) => missing.push(name.to_owned()),
+                    _ => return Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(std::io::ErrorKind::NotFound.into())
+}
+
+/// Resolve a path and check that the frontend may read it
+///
+/// Returns `NotFound` for a missing path only when it would be in scope, so
+/// files outside it can't be probed for existence.
+pub fn check_scope<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<PathBuf, CommandError> {
+    use tauri_plugin_fs::FsExt;
+
+    let requested = path;
+    let (path, exists) =
+        resolve(Path::new(path)).map_err(|e| CommandError::Failed(format!("{}: {}", path, e)))?;
+
+    let in_app_dir = [
+        app.path().app_data_dir(),
+        app.path().app_config_dir(),
+        app.path().app_cache_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|dir| dir.canonicalize().is_ok_and(|dir| path.starts_with(dir)));
+
+    if !in_app_dir && !app.fs_scope().is_allowed(&path) {
+        return Err(CommandError::Failed(format!(
+            "Path is outside the allowed scope: {}",
+            path.display()
+        )));
+    }
+    if !exists {
+        return Err(CommandError::NotFound(requested.to_string()));
+    }
+    Ok(path)
+}
+
+/// Start the background task that closes idle streams
+pub fn start_reaper<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            crate::tasks::tick(&app, "file-stream-reaper", REAP_INTERVAL).await;
+
+            let state = app.state::<crate::AppState>();
+            let mut streams = state.file_streams.lock().unwrap();
+            let before = streams.open.len();
+            streams
+                .open
+                .retain(|_, stream| stream.last_used.elapsed() < STREAM_TIMEOUT);
+            let closed = before - streams.open.len();
+            if closed > 0 {
+                log::info!("Closed {} idle file stream(s)", closed);
+            }
+        }
+    });
+}
+
+/// Open a file for chunked reading
+#[tauri::command]
+pub async fn open_file_stream<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<StreamId, String> {
+    let path = check_scope(&app, &path).map_err(|e| e.to_string())?;
+    let file = File::open(&path).map_err(|e| e.to_string())?;
+
+    let state = app.state::<crate::AppState>();
+    let mut streams = state.file_streams.lock().unwrap();
+    streams.next_id = streams.next_id.wrapping_add(1).max(1);
+    let id = streams.next_id;
+    streams.open.insert(
+        id,
+        OpenStream {
+            file,
+            last_used: Instant::now(),
+        },
+    );
+
+    Ok(id)
+}
+
+/// Read up to `size` bytes, base64-encoded; `None` at end of file
+#[tauri::command]
+pub async fn read_file_chunk<R: Runtime>(
+    app: AppHandle<R>,
+    id: StreamId,
+    size: usize,
+) -> Result<Option<String>, String> {
+    use base64::Engine;
+
+    let size = size.clamp(1, MAX_CHUNK_BYTES);
+    let chunk = tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<crate::AppState>();
+        let mut streams = state.file_streams.lock().unwrap();
+        let stream = streams
+            .open
+            .get_mut(&id)
+            .ok_or_else(|| format!("File stream not found: {}", id))?;
+        stream.last_used = Instant::now();
+
+        let mut buffer = Vec::with_capacity(size);
+        (&mut stream.file)
+            .take(size as u64)
+            .read_to_end(&mut buffer)
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>(buffer)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    if chunk.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(base64::engine::general_purpose::STANDARD.encode(chunk)))
+}
+
+/// Close a file stream
+#[tauri::command]
+pub fn close_file_stream<R: Runtime>(app: AppHandle<R>, id: StreamId) {
+    app.state::<crate::AppState>()
+        .file_streams
+        .lock()
+        .unwrap()
+        .open
+        .remove(&id);
+}
+
+/// Compute the hex digest of a file, streaming it from disk
+///
+/// Emits `hash-progress` for files larger than 32 MiB.
+#[tauri::command]
+pub async fn hash_file<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    algorithm: HashAlgorithm,
+) -> Result<String, CommandError> {
+    let resolved = check_scope(&app, &path)?;
+    if !resolved.is_file() {
+        return Err(CommandError::NotFound(path));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut file = File::open(&resolved).map_err(|e| e.to_string())?;
+        let total_bytes = file.metadata().map_err(|e| e.to_string())?.len();
+        let report_every = (total_bytes / 100).max(HASH_BUFFER_BYTES as u64);
+
+        let mut hasher = Hasher::new(algorithm);
+        let mut buffer = vec![0u8; HASH_BUFFER_BYTES];
+        let mut bytes_read = 0u64;
+        let mut last_report = 0u64;
+        loop {
+            let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            bytes_read += read as u64;
+
+            if total_bytes >= HASH_PROGRESS_THRESHOLD && bytes_read - last_report >= report_every {
+                last_report = bytes_read;
+                crate::events::emit(
+                    &app,
+                    crate::events::HASH_PROGRESS,
+                    HashProgress {
+                        path: path.clone(),
+                        bytes_read,
+                        total_bytes,
+                    },
+                );
+            }
+        }
+
+        Ok::<_, String>(hasher.finalize())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(CommandError::Failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yula-files-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn resolves_existing_files() {
+        let dir = temp_dir("existing");
+        std::fs::write(dir.join("notes.txt"), b"hello").unwrap();
+
+        let (resolved, exists) = resolve(&dir.join("notes.txt")).unwrap();
+        assert_eq!(resolved, dir.join("notes.txt"));
+        assert!(exists);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn resolves_missing_paths_through_their_parent() {
+        let dir = temp_dir("missing");
+
+        let (resolved, exists) = resolve(&dir.join("imports/notes.txt")).unwrap();
+        assert_eq!(resolved, dir.join("imports").join("notes.txt"));
+        assert!(!exists);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn doesnt_resolve_climbing_out_of_a_missing_directory() {
+        let dir = temp_dir("climbing");
+
+        let error = resolve(&dir.join("imports/../../etc")).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! Deep-link session handoff for YULA Desktop
+//!
+//! Signing in on the web and handing the session to the desktop app ends in
+//! a `yula://auth?token=...` link. The desktop mints the token first with
+//! `generate_handoff_token`; the frontend passes it to the server along with
+//! the login attempt, and the web app sends the user back with the same
+//! token once signed in. A token is a random nonce and an expiry, signed
+//! with a key that never leaves this process, so links can't be forged, and
+//! each one is accepted once within `TOKEN_TTL_MS`, so they can't be
+//! replayed. Accepted links emit `auth-handoff`; the rest are logged and
+//! dropped, and none of them enter the deep-link history.
+
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Deep-link host reserved for handoff, as in `yula://auth?token=...`
+const HANDOFF_HOST: &str = "auth";
+
+/// How long a minted token can be redeemed, in milliseconds
+const TOKEN_TTL_MS: i64 = 5 * 60 * 1000;
+
+const NONCE_LEN: usize = 16;
+
+/// Encoding of both token halves; safe in a URL query without escaping
+const BASE64: base64::engine::GeneralPurpose = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+/// Payload of the `auth-handoff` event
+#[derive(Debug, Clone, Serialize)]
+struct HandoffPayload {
+    token: String,
+}
+
+/// Signing key and the tokens minted with it that are still unredeemed
+#[derive(Debug, Default)]
+pub struct HandoffTokens {
+    key: Option<[u8; 32]>,
+    /// Expiry of each outstanding nonce, Unix millis
+    pending: HashMap<[u8; NONCE_LEN], i64>,
+}
+
+impl HandoffTokens {
+    fn mint(&mut self, now: i64) -> String {
+        self.pending.retain(|_, expires| *expires > now);
+
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let expires = now + TOKEN_TTL_MS;
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&expires.to_be_bytes());
+
+        let key = *self.key.get_or_insert_with(rand::random);
+        let signature = blake3::keyed_hash(&key, &payload);
+        self.pending.insert(nonce, expires);
+        format!("{}.{}", BASE64.encode(&payload), BASE64.encode(signature.as_bytes()))
+    }
+
+    /// Accept a token once, if it's ours and hasn't expired
+    fn redeem(&mut self, token: &str, now: i64) -> Result<(), &'static str> {
+        let (payload, signature) = token.split_once('.').ok_or("malformed token")?;
+        let payload = BASE64.decode(payload).map_err(|_| "malformed token")?;
+        let signature: [u8; 32] = BASE64
+            .decode(signature)
+            .ok()
+            .and_then(|signature| signature.try_into().ok())
+            .ok_or("malformed token")?;
+        if payload.len() != NONCE_LEN + 8 {
+            return Err("malformed token");
+        }
+
+        let key = self.key.ok_or("no token was issued")?;
+        // `Hash` equality runs in constant time
+        if blake3::keyed_hash(&key, &payload) != blake3::Hash::from(signature) {
+            return Err("invalid signature");
+        }
+
+        let (nonce, expires) = payload.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("length checked above");
+        let expires = i64::from_be_bytes(expires.try_into().expect("length checked above"));
+        let outstanding = self.pending.remove(&nonce).is_some();
+        if expires <= now {
+            Err("token expired")
+        } else if !outstanding {
+            Err("token already used")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Accept a handoff link, returning `false` for other links
+pub fn handle<R: Runtime>(app: &AppHandle<R>, url: &tauri::Url) -> bool {
+    if url.host_str() != Some(HANDOFF_HOST) {
+        return false;
+    }
+
+    let Some(token) = url
+        .query_pairs()
+        .find(|(key, _)| key == "token")
+        .map(|(_, token)| token.into_owned())
+    else {
+        log::warn!("Rejected auth handoff link: no token");
+        return true;
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let redeemed = app
+        .state::<crate::AppState>()
+        .handoff_tokens
+        .lock()
+        .unwrap()
+        .redeem(&token, now);
+    if let Err(reason) = redeemed {
+        log::warn!("Rejected auth handoff link: {}", reason);
+        return true;
+    }
+
+    log::info!("Auth handoff accepted");
+    if let Err(e) = crate::window::bring_to_front(app) {
+        log::warn!("Failed to raise main window: {}", e);
+    }
+    crate::events::emit(app, crate::events::AUTH_HANDOFF, HandoffPayload { token });
+    true
+}
+
+/// Mint a single-use token for handing a web session to this app
+///
+/// The web app must return it as `yula://auth?token=...` within five
+/// minutes; tokens don't survive a restart.
+#[tauri::command]
+pub fn generate_handoff_token<R: Runtime>(app: AppHandle<R>) -> String {
+    let now = chrono::Utc::now().timestamp_millis();
+    app.state::<crate::AppState>()
+        .handoff_tokens
+        .lock()
+        .unwrap()
+        .mint(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000_000;
+
+    #[test]
+    fn redeems_a_minted_token_once() {
+        let mut tokens = HandoffTokens::default();
+        let token = tokens.mint(NOW);
+        assert_eq!(tokens.redeem(&token, NOW + 1000), Ok(()));
+        assert_eq!(tokens.redeem(&token, NOW + 2000), Err("token already used"));
+    }
+
+    #[test]
+    fn rejects_expired_tokens() {
+        let mut tokens = HandoffTokens::default();
+        let token = tokens.mint(NOW);
+        assert_eq!(tokens.redeem(&token, NOW + TOKEN_TTL_MS), Err("token expired"));
+    }
+
+    #[test]
+    fn minting_drops_expired_tokens() {
+        let mut tokens = HandoffTokens::default();
+        tokens.mint(NOW);
+        tokens.mint(NOW + TOKEN_TTL_MS);
+        assert_eq!(tokens.pending.len(), 1);
+    }
+
+    #[test]
+    fn rejects_tokens_from_another_key() {
+        let mut tokens = HandoffTokens::default();
+        let foreign = HandoffTokens::default().mint(NOW);
+        assert_eq!(tokens.redeem(&foreign, NOW), Err("no token was issued"));
+
+        tokens.mint(NOW);
+        assert_eq!(tokens.redeem(&foreign, NOW), Err("invalid signature"));
+    }
+
+    #[test]
+    fn rejects_tampered_tokens() {
+        let mut tokens = HandoffTokens::default();
+        let token = tokens.mint(NOW);
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        let mut forged = BASE64.decode(payload).unwrap();
+        forged[NONCE_LEN..].copy_from_slice(&i64::MAX.to_be_bytes());
+        let forged = format!("{}.{}", BASE64.encode(forged), signature);
+        assert_eq!(tokens.redeem(&forged, NOW), Err("invalid signature"));
+
+        assert_eq!(tokens.redeem("not-a-token", NOW), Err("malformed token"));
+        assert_eq!(tokens.redeem(&format!("{}.", payload), NOW), Err("malformed token"));
+    }
+}
@@ -0,0 +1,151 @@
+//! OS automation actions for YULA Desktop
+//!
+//! "New Chat" and "Ask YULA" are exposed as `yula://action/<id>` deep links,
+//! which the OS automation layers can open: the Shortcuts app's "Open URL"
+//! action on macOS, jump-list tasks on Windows (registered here), and
+//! `xdg-open` on Linux. Native App Intents would need Swift metadata compiled
+//! into the bundle, so on macOS nothing is registered at runtime. When an
+//! action link arrives the main window is raised and `integration-action`
+//! is emitted, the same event the local integration server sends.
+
+use tauri::{AppHandle, Runtime};
+
+use crate::error::CommandError;
+use crate::local_server::IntegrationAction;
+
+/// Deep-link host reserved for actions, as in `yula://action/new-chat`
+const ACTION_HOST: &str = "action";
+
+/// An action exposed to OS automation
+#[cfg(target_os = "windows")]
+struct AppIntent {
+    id: &'static str,
+    title: &'static str,
+}
+
+#[cfg(target_os = "windows")]
+const INTENTS: &[AppIntent] = &[
+    AppIntent {
+        id: "new-chat",
+        title: "New Chat",
+    },
+    AppIntent {
+        id: "ask",
+        title: "Ask YULA",
+    },
+];
+
+/// Deep link that invokes an action
+#[cfg(target_os = "windows")]
+fn intent_url(id: &str) -> String {
+    format!("{}://{}/{}", crate::deep_link::SCHEME, ACTION_HOST, id)
+}
+
+/// Run the action a deep link names, returning `false` for other links
+///
+/// `yula://action/ask?text=...` starts a chat with the given prompt.
+pub fn handle<R: Runtime>(app: &AppHandle<R>, url: &tauri::Url) -> bool {
+    if url.host_str() != Some(ACTION_HOST) {
+        return false;
+    }
+
+    let id = url.path().trim_matches('/');
+    let action = match id {
+        "new-chat" => IntegrationAction::NewChat { text: None },
+        "ask" => IntegrationAction::NewChat {
+            text: url
+                .query_pairs()
+                .find(|(key, _)| key == "text")
+                .map(|(_, text)| text.into_owned()),
+        },
+        _ => {
+            log::warn!("Unknown action link: {}", url);
+            return true;
+        }
+    };
+
+    log::info!("App intent invoked: {}", id);
+    if let Err(e) = crate::window::bring_to_front(app) {
+        log::warn!("Failed to raise main window: {}", e);
+    }
+    crate::events::emit(app, crate::events::INTEGRATION_ACTION, action);
+    true
+}
+
+/// Register the app's actions with the OS automation layer
+pub fn register<R: Runtime>(app: &AppHandle<R>) -> Result<(), CommandError> {
+    let _ = app;
+
+    #[cfg(target_os = "windows")]
+    {
+        jump_list::register(INTENTS).map_err(CommandError::Failed)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(CommandError::NotSupported(format!(
+            "Actions can't be registered at runtime; open {}://{}/<action> links instead",
+            crate::deep_link::SCHEME,
+            ACTION_HOST
+        )))
+    }
+}
+
+/// Register the app's actions (jump-list tasks on Windows)
+#[tauri::command]
+pub async fn register_app_intents<R: Runtime>(app: AppHandle<R>) -> Result<(), CommandError> {
+    register(&app)
+}
+
+#[cfg(target_os = "windows")]
+mod jump_list {
+    use windows::core::{Interface, HSTRING};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
+        ShellLink,
+    };
+
+    use super::AppIntent;
+
+    /// Replace the taskbar jump list's tasks with one per action
+    pub fn register(intents: &[AppIntent]) -> Result<(), String> {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let exe = HSTRING::from(exe.as_os_str());
+        unsafe { build(&exe, intents) }.map_err(|e| e.to_string())
+    }
+
+    unsafe fn build(exe: &HSTRING, intents: &[AppIntent]) -> windows::core::Result<()> {
+        // Already initialized on the main thread; a mismatch is harmless
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut max_slots = 0u32;
+        let _removed: IObjectArray = list.BeginList(&mut max_slots)?;
+
+        let tasks: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for intent in intents {
+            // Launching with the link forwards it through single-instance
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+            link.SetPath(exe)?;
+            link.SetArguments(&HSTRING::from(super::intent_url(intent.id)))?;
+            link.SetIconLocation(exe, 0)?;
+
+            let properties: IPropertyStore = link.cast()?;
+            properties.SetValue(&PKEY_Title, &PROPVARIANT::from(intent.title))?;
+            properties.Commit()?;
+            tasks.AddObject(&link)?;
+        }
+
+        list.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+        list.CommitList()
+    }
+}
@@ -0,0 +1,140 @@
+//! Keyboard layout detection for YULA Desktop
+//!
+//! Reports the active input source so the frontend can adapt shortcut hints
+//! and input affordances. The identifier is platform-specific: the input
+//! source id on macOS (`com.apple.keylayout.Turkish`), the keyboard layout
+//! handle of the main window's thread on Windows (`041F041F`), and the
+//! current GNOME input source (`xkb:tr`) or XKB layout (`tr`) on Linux.
+//! A background monitor emits `keyboard-layout-changed` on switches.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// How often the active layout is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Read the active keyboard layout identifier
+fn current<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        // Text Input Sources must be queried on the main thread
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.run_on_main_thread(move || {
+            let _ = tx.send(macos::input_source_id());
+        })
+        .ok()?;
+        rx.recv_timeout(Duration::from_secs(1)).ok().flatten()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyboardLayout;
+        use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+        // Layouts are per thread; ours is the one the webview receives input on
+        let hwnd = crate::window::main_window(app).ok()?.hwnd().ok()?;
+        let thread = unsafe { GetWindowThreadProcessId(hwnd, None) };
+        let layout = unsafe { GetKeyboardLayout(thread) };
+        Some(format!("{:08X}", layout.0 as usize as u32))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = app;
+
+        // GNOME keeps the active source first in the most-recently-used list,
+        // e.g. `[('xkb', 'tr'), ('xkb', 'us')]`
+        let mru = crate::platform::output(
+            "gsettings",
+            &["get", "org.gnome.desktop.input-sources", "mru-sources"],
+        );
+        let gnome = mru.as_deref().and_then(|mru| {
+            let mut fields = mru.split('\'').skip(1).step_by(2);
+            Some(format!("{}:{}", fields.next()?, fields.next()?))
+        });
+        if gnome.is_some() {
+            return gnome;
+        }
+
+        let query = crate::platform::output("setxkbmap", &["-query"])?;
+        query
+            .lines()
+            .find_map(|line| line.strip_prefix("layout:"))
+            .map(|layout| layout.trim().to_string())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = app;
+        None
+    }
+}
+
+/// Start the background monitor that emits `keyboard-layout-changed`
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let handle = app.clone();
+            let layout = tauri::async_runtime::spawn_blocking(move || current(&handle))
+                .await
+                .ok()
+                .flatten();
+
+            if let Some(layout) = layout {
+                let state = app.state::<crate::AppState>();
+                let previous = state.keyboard_layout.lock().unwrap().replace(layout.clone());
+                if previous.is_some_and(|previous| previous != layout) {
+                    log::info!("Keyboard layout changed: {}", layout);
+                    crate::events::emit(&app, crate::events::KEYBOARD_LAYOUT_CHANGED, layout);
+                }
+            }
+
+            crate::tasks::tick(&app, "keyboard-layout", POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Get the active keyboard layout / input source identifier, if detectable
+#[tauri::command]
+pub async fn get_keyboard_layout<R: Runtime>(app: AppHandle<R>) -> Option<String> {
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || current(&handle))
+        .await
+        .ok()
+        .flatten()
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_foundation::NSString;
+    use std::ffi::c_void;
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        static kTISPropertyInputSourceID: *const c_void;
+        fn TISCopyCurrentKeyboardInputSource() -> *mut c_void;
+        fn TISGetInputSourceProperty(source: *mut c_void, key: *const c_void) -> *const c_void;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    /// Id of the current keyboard input source, e.g. `com.apple.keylayout.US`
+    pub fn input_source_id() -> Option<String> {
+        unsafe {
+            let source = TISCopyCurrentKeyboardInputSource();
+            if source.is_null() {
+                return None;
+            }
+
+            // The property is a CFString, toll-free bridged to NSString
+            let id = TISGetInputSourceProperty(source, kTISPropertyInputSourceID);
+            let id = (id as *const NSString).as_ref().map(|id| id.to_string());
+            CFRelease(source);
+            id
+        }
+    }
+}
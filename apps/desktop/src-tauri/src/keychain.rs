@@ -0,0 +1,37 @@
+//! OS credential store access for YULA Desktop
+//!
+//! Secrets are kept in the macOS Keychain, the Windows Credential Manager or
+//! the Secret Service on Linux, under the app identifier as the service
+//! name, suffixed with the profile for profiles other than the default one.
+//! Nothing here is exposed to the frontend directly; backend features
+//! that need a secret go through these helpers.
+
+use tauri::{AppHandle, Runtime};
+
+fn entry<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(&crate::profiles::keychain_service(app), name).map_err(|e| e.to_string())
+}
+
+/// Store a secret, replacing any previous value
+pub fn set<R: Runtime>(app: &AppHandle<R>, name: &str, secret: &str) -> Result<(), String> {
+    entry(app, name)?
+        .set_password(secret)
+        .map_err(|e| e.to_string())
+}
+
+/// Read a secret, `None` if it was never stored
+pub fn get<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<Option<String>, String> {
+    match entry(app, name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Remove a secret if it exists
+pub fn delete<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<(), String> {
+    match entry(app, name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,371 @@
+//! Kiosk mode for YULA Desktop
+//!
+//! Locks the app down for signage and kiosk deployments: the main window is
+//! moved to one display and made fullscreen, the tray icon is hidden, and
+//! secondary windows are refused as under the single-window policy. Mini
+//! mode and layouts, which would toggle always-on-top and move the window,
+//! are refused too, as is anything that would hide the window, and menu bar
+//! mode is suspended. Leaving fullscreen or minimizing is undone at once.
+//! Leaving requires the PIN set with `set_kiosk_pin`, stored as a salted
+//! Argon2 hash in the OS credential store. Wrong PINs count against every
+//! caller at once, and too many lock `exit_kiosk_mode` for a while, longer
+//! each time. The chosen display is persisted, and kiosk mode is re-entered
+//! on the next launch until it is left.
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, PhysicalPosition, Runtime};
+
+use crate::error::CommandError;
+
+/// Credential store entry holding the PIN hash
+const PIN_ENTRY: &str = "kiosk-pin";
+
+/// Shortest accepted PIN
+const MIN_PIN_LEN: usize = 4;
+
+/// Pause after a wrong PIN, to slow down guessing
+const WRONG_PIN_DELAY: Duration = Duration::from_secs(1);
+
+/// Wrong PINs allowed before `exit_kiosk_mode` locks
+const MAX_PIN_ATTEMPTS: u32 = 5;
+
+/// First lockout, doubled for every further wrong PIN
+const PIN_LOCKOUT: Duration = Duration::from_secs(30);
+
+/// Longest lockout
+const MAX_PIN_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Wrong PINs entered since the last right one, shared by all callers
+#[derive(Debug, Default)]
+pub struct PinAttempts {
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+impl PinAttempts {
+    /// Count an attempt before it is checked, so parallel calls can't get
+    /// more guesses in than the limit
+    fn begin(&mut self, now: Instant) -> Result<(), CommandError> {
+        if let Some(until) = self.locked_until.filter(|until| *until > now) {
+            return Err(CommandError::PermissionDenied(format!(
+                "Too many wrong PINs; try again in {} seconds",
+                (until - now).as_secs().max(1)
+            )));
+        }
+        self.failures += 1;
+        if self.failures >= MAX_PIN_ATTEMPTS {
+            let doublings = (self.failures - MAX_PIN_ATTEMPTS).min(16);
+            let lockout = PIN_LOCKOUT.saturating_mul(1 << doublings).min(MAX_PIN_LOCKOUT);
+            self.locked_until = Some(now + lockout);
+        }
+        Ok(())
+    }
+
+    fn succeeded(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Whether kiosk mode is on
+pub fn is_active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<crate::AppState>()
+        .is_some_and(|state| state.kiosk_mode.load(Ordering::Relaxed))
+}
+
+/// Refuse `action` while kiosk mode is on
+pub fn ensure_inactive<R: Runtime>(app: &AppHandle<R>, action: &str) -> Result<(), String> {
+    if is_active(app) {
+        Err(format!("{} is disabled in kiosk mode", action))
+    } else {
+        Ok(())
+    }
+}
+
+/// Hash a PIN into a PHC string with a fresh salt
+fn pin_hash(pin: &str) -> Result<String, String> {
+    let salt: [u8; 16] = rand::random();
+    let salt = SaltString::encode_b64(&salt).map_err(|e| e.to_string())?;
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether a stored hash predates Argon2
+fn is_legacy(stored: &str) -> bool {
+    PasswordHash::new(stored).is_err()
+}
+
+/// Check a PIN against its stored hash
+///
+/// Hashes from before Argon2 are plain BLAKE3 hex and still verify, so a
+/// kiosk locked down by an older version can be left; `exit_kiosk_mode`
+/// replaces them with an Argon2 hash once the PIN is known.
+fn verify_pin(pin: &str, stored: &str) -> bool {
+    match PasswordHash::new(stored) {
+        Ok(hash) => Argon2::default().verify_password(pin.as_bytes(), &hash).is_ok(),
+        // `Hash` equality runs in constant time
+        Err(_) => {
+            blake3::Hash::from_hex(stored).is_ok_and(|hash| hash == blake3::hash(pin.as_bytes()))
+        }
+    }
+}
+
+fn set_tray_visible<R: Runtime>(app: &AppHandle<R>, visible: bool) {
+    let result = crate::tray::tray(app)
+        .and_then(|tray| tray.set_visible(visible).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to change tray visibility: {}", e);
+    }
+}
+
+/// Move the main window fullscreen onto a display, by its index in
+/// `get_display_arrangement`
+fn lock_down<R: Runtime>(app: &AppHandle<R>, display_index: u32) -> Result<(), CommandError> {
+    let displays = crate::displays::arrangement(app)?;
+    let display = displays.get(display_index as usize).ok_or_else(|| {
+        CommandError::NotFound(format!("No display at index {}", display_index))
+    })?;
+
+    let window = crate::window::main_window(app)?;
+    // A fullscreen window stays on its display; leave fullscreen to move it
+    window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(display.bounds.x, display.bounds.y))
+        .map_err(|e| e.to_string())?;
+    window.set_always_on_top(false).map_err(|e| e.to_string())?;
+    window.set_fullscreen(true).map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+
+    set_tray_visible(app, false);
+    app.state::<crate::AppState>()
+        .kiosk_mode
+        .store(true, Ordering::Relaxed);
+    // Drops the summon hotkey and the hide-on-blur popover
+    if crate::settings::get(app).menu_bar_mode {
+        crate::menu_bar::apply(app, false)?;
+    }
+    Ok(())
+}
+
+/// Put the main window back to fullscreen if it left it or was minimized
+/// while kiosk mode is on
+pub fn on_resized<R: Runtime>(window: &tauri::Window<R>) {
+    if window.label() != crate::window::MAIN_WINDOW || !is_active(window.app_handle()) {
+        return;
+    }
+    if window.is_minimized().unwrap_or(false) {
+        let _ = window.unminimize();
+    }
+    if !window.is_fullscreen().unwrap_or(true) {
+        log::warn!("Main window left fullscreen in kiosk mode; restoring");
+        if let Err(e) = window.set_fullscreen(true) {
+            log::warn!("Failed to restore kiosk fullscreen: {}", e);
+        }
+    }
+}
+
+/// Re-enter kiosk mode if the app was in it when it quit
+pub fn restore<R: Runtime>(app: &AppHandle<R>) {
+    let Some(display_index) = crate::settings::get(app).kiosk_display else {
+        return;
+    };
+
+    log::info!("Re-entering kiosk mode on display {}", display_index);
+    let result = match lock_down(app, display_index) {
+        // The display may be gone; the primary one is always first
+        Err(CommandError::NotFound(_)) => lock_down(app, 0),
+        result => result,
+    };
+    if let Err(e) = result {
+        log::error!("Failed to re-enter kiosk mode: {}", e);
+    }
+}
+
+/// Set the PIN that leaves kiosk mode
+///
+/// Can't be changed while kiosk mode is on.
+#[tauri::command]
+pub async fn set_kiosk_pin<R: Runtime>(app: AppHandle<R>, pin: String) -> Result<(), CommandError> {
+    if is_active(&app) {
+        return Err(CommandError::PolicyViolation(
+            "The kiosk PIN can't be changed in kiosk mode".to_string(),
+        ));
+    }
+    if pin.chars().count() < MIN_PIN_LEN {
+        return Err(CommandError::Failed(format!(
+            "The PIN must have at least {} characters",
+            MIN_PIN_LEN
+        )));
+    }
+
+    let hash = tokio::task::spawn_blocking(move || pin_hash(&pin))
+        .await
+        .map_err(|e| e.to_string())??;
+    crate::keychain::set(&app, PIN_ENTRY, &hash)?;
+    Ok(())
+}
+
+/// Lock the app fullscreen onto a display, by its index in
+/// `get_display_arrangement`
+///
+/// Requires a PIN set with `set_kiosk_pin`, so kiosk mode can be left again.
+#[tauri::command]
+pub async fn enter_kiosk_mode<R: Runtime>(
+    app: AppHandle<R>,
+    display_index: u32,
+) -> Result<(), CommandError> {
+    if crate::keychain::get(&app, PIN_ENTRY)?.is_none() {
+        return Err(CommandError::Failed(
+            "Set a kiosk PIN before entering kiosk mode".to_string(),
+        ));
+    }
+    // Mini mode would leave the window small and always on top
+    if app.state::<crate::AppState>().mini_mode.lock().unwrap().is_some() {
+        crate::mini_mode::set_mini_mode(app.clone(), false).await?;
+    }
+
+    lock_down(&app, display_index)?;
+    log::info!("Entered kiosk mode on display {}", display_index);
+    crate::settings::update(&app, |settings| settings.kiosk_display = Some(display_index))?;
+    Ok(())
+}
+
+/// Leave kiosk mode, if `pin` matches the one set with `set_kiosk_pin`
+#[tauri::command]
+pub async fn exit_kiosk_mode<R: Runtime>(
+    app: AppHandle<R>,
+    pin: String,
+) -> Result<(), CommandError> {
+    if !is_active(&app) {
+        return Ok(());
+    }
+
+    app.state::<crate::AppState>()
+        .kiosk_pin_attempts
+        .lock()
+        .unwrap()
+        .begin(Instant::now())?;
+    // Nothing can match a PIN that isn't there, so kiosk mode stays on
+    let Some(stored) = crate::keychain::get(&app, PIN_ENTRY)? else {
+        log::error!("The kiosk PIN is missing from the credential store");
+        return Err(CommandError::PermissionDenied(
+            "No kiosk PIN is set".to_string(),
+        ));
+    };
+    let (matches, rehashed) = tokio::task::spawn_blocking(move || {
+        let matches = verify_pin(&pin, &stored);
+        let rehashed = (matches && is_legacy(&stored)).then(|| pin_hash(&pin));
+        (matches, rehashed)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if !matches {
+        log::warn!("Wrong kiosk PIN entered");
+        tokio::time::sleep(WRONG_PIN_DELAY).await;
+        return Err(CommandError::PermissionDenied("Wrong PIN".to_string()));
+    }
+    app.state::<crate::AppState>()
+        .kiosk_pin_attempts
+        .lock()
+        .unwrap()
+        .succeeded();
+    if let Some(hash) = rehashed {
+        match hash.and_then(|hash| crate::keychain::set(&app, PIN_ENTRY, &hash)) {
+            Ok(()) => log::info!("Upgraded the kiosk PIN hash to Argon2"),
+            Err(e) => log::warn!("Failed to upgrade the kiosk PIN hash: {}", e),
+        }
+    }
+
+    app.state::<crate::AppState>()
+        .kiosk_mode
+        .store(false, Ordering::Relaxed);
+    crate::window::main_window(&app)?
+        .set_fullscreen(false)
+        .map_err(|e| e.to_string())?;
+    set_tray_visible(&app, true);
+    crate::menu_bar::init(&app);
+    log::info!("Left kiosk mode");
+    crate::settings::update(&app, |settings| settings.kiosk_display = None)?;
+    Ok(())
+}
+
+/// Check whether kiosk mode is on
+#[tauri::command]
+pub fn is_kiosk_mode<R: Runtime>(app: AppHandle<R>) -> bool {
+    is_active(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_after_too_many_wrong_pins() {
+        let mut attempts = PinAttempts::default();
+        let now = Instant::now();
+        for _ in 0..MAX_PIN_ATTEMPTS {
+            attempts.begin(now).unwrap();
+        }
+        assert!(matches!(
+            attempts.begin(now),
+            Err(CommandError::PermissionDenied(_))
+        ));
+    }
+
+    #[test]
+    fn resets_after_the_right_pin() {
+        let mut attempts = PinAttempts::default();
+        let now = Instant::now();
+        for _ in 0..MAX_PIN_ATTEMPTS - 1 {
+            attempts.begin(now).unwrap();
+        }
+        attempts.succeeded();
+        for _ in 0..MAX_PIN_ATTEMPTS {
+            attempts.begin(now).unwrap();
+        }
+    }
+
+    #[test]
+    fn unlocks_once_the_lockout_ends_and_doubles_it() {
+        let mut attempts = PinAttempts::default();
+        let now = Instant::now();
+        for _ in 0..MAX_PIN_ATTEMPTS {
+            attempts.begin(now).unwrap();
+        }
+        assert!(attempts.begin(now + PIN_LOCKOUT / 2).is_err());
+
+        let later = now + PIN_LOCKOUT;
+        attempts.begin(later).unwrap();
+        assert!(attempts.begin(later + PIN_LOCKOUT).is_err());
+        attempts.begin(later + PIN_LOCKOUT * 2).unwrap();
+    }
+
+    #[test]
+    fn caps_the_lockout() {
+        let mut attempts = PinAttempts::default();
+        let mut now = Instant::now();
+        for _ in 0..MAX_PIN_ATTEMPTS + 20 {
+            attempts.begin(now).unwrap();
+            now += MAX_PIN_LOCKOUT;
+        }
+    }
+
+    #[test]
+    fn verifies_argon2_and_legacy_hashes() {
+        let hash = pin_hash("1234").unwrap();
+        assert!(!is_legacy(&hash));
+        assert!(verify_pin("1234", &hash));
+        assert!(!verify_pin("4321", &hash));
+
+        let legacy = blake3::hash(b"1234").to_hex().to_string();
+        assert!(is_legacy(&legacy));
+        assert!(verify_pin("1234", &legacy));
+        assert!(!verify_pin("4321", &legacy));
+    }
+}
@@ -0,0 +1,91 @@
+//! Launch context for YULA Desktop
+//!
+//! The autostart plugin registers the app with `--minimized`, so its presence
+//! on the command line means the OS launched us at login rather than the
+//! user. The UI uses this to start quietly without stealing focus, and the
+//! main window is created hidden in the tray.
+//!
+//! Events that arrive before the page has loaded would be lost, so files
+//! opened at launch, the notice that the app was updated, and whatever
+//! startup checks find through `emit_when_ready` are held until the frontend
+//! calls `frontend_ready`.
+
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Argument passed by the login item registered through autostart
+pub const AUTOSTART_ARG: &str = "--minimized";
+
+/// Detect how the app was launched and record it in `AppState`
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let auto_launched = std::env::args().skip(1).any(|arg| arg == AUTOSTART_ARG);
+    if auto_launched {
+        log::info!("Launched at login");
+    }
+
+    app.state::<crate::AppState>()
+        .auto_launched
+        .store(auto_launched, Ordering::Relaxed);
+}
+
+/// Check whether the OS launched the app at login
+#[tauri::command]
+pub fn was_auto_launched<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<crate::AppState>()
+        .auto_launched
+        .load(Ordering::Relaxed)
+}
+
+/// Whether the main window should start hidden in the tray
+pub fn should_start_minimized<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<crate::AppState>()
+        .map(|state| state.auto_launched.load(Ordering::Relaxed))
+        .unwrap_or(false)
+}
+
+/// Check whether the main window was started hidden in the tray
+#[tauri::command]
+pub fn get_should_start_minimized<R: Runtime>(app: AppHandle<R>) -> bool {
+    should_start_minimized(&app)
+}
+
+/// Emit an event now if the frontend is listening, otherwise once it calls
+/// `frontend_ready`
+pub fn emit_when_ready<R: Runtime, S: Serialize + Clone>(
+    app: &AppHandle<R>,
+    event: &'static str,
+    payload: S,
+) {
+    let state = app.state::<crate::AppState>();
+    // Checked under the lock so `frontend_ready` can't drain in between
+    let mut held = state.launch_events.lock().unwrap();
+    if state.frontend_ready.load(Ordering::SeqCst) {
+        drop(held);
+        crate::events::emit(app, event, payload);
+        return;
+    }
+    match serde_json::to_value(payload) {
+        Ok(payload) => held.push((event, payload)),
+        Err(e) => log::warn!("Failed to hold {}: {}", event, e),
+    }
+}
+
+/// Signal that the frontend has loaded and is listening for events
+///
+/// Emits the `file-opened` events held back since launch, and the first
+/// time `updated-from` if this launch follows an update, and the events
+/// held by `emit_when_ready`.
+#[tauri::command]
+pub fn frontend_ready<R: Runtime>(app: AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    if !state.frontend_ready.swap(true, Ordering::SeqCst) {
+        log::info!("Frontend ready");
+        crate::updater::announce_updated(&app);
+        let held = std::mem::take(&mut *state.launch_events.lock().unwrap());
+        for (event, payload) in held {
+            crate::events::emit(&app, event, payload);
+        }
+    }
+    crate::file_associations::flush_pending(&app);
+}
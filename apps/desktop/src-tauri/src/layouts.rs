@@ -0,0 +1,225 @@
+//! Saved window layouts for YULA Desktop
+//!
+//! A layout records every open window's label, route, geometry and flags
+//! under a name, so multi-window arrangements can be restored like
+//! workspaces. Layouts are stored in `layouts.json` in the profile's data
+//! directory. Restoring recreates missing secondary windows at their saved
+//! route and moves existing ones, including the main window, into place.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow};
+
+/// File in the app data directory holding the saved layouts
+const LAYOUTS_FILE: &str = "layouts.json";
+
+/// A window's state within a saved layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowLayout {
+    pub label: String,
+    /// Frontend route the window was showing, e.g. `chat/42`
+    pub url: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    pub always_on_top: bool,
+    pub visible: bool,
+}
+
+/// A named arrangement of windows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layout {
+    pub name: String,
+    pub windows: Vec<WindowLayout>,
+    /// When the layout was saved, Unix millis
+    pub saved_at: i64,
+}
+
+fn layouts_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    crate::profiles::data_dir(app).map(|dir| dir.join(LAYOUTS_FILE))
+}
+
+fn read<R: Runtime>(app: &AppHandle<R>) -> Result<BTreeMap<String, Layout>, String> {
+    let path = layouts_path(app)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {}", LAYOUTS_FILE, e))
+}
+
+fn write<R: Runtime>(app: &AppHandle<R>, layouts: &BTreeMap<String, Layout>) -> Result<(), String> {
+    let path = layouts_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(layouts).map_err(|e| e.to_string())?;
+    crate::settings::write_atomic(&path, contents)
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Layout name must not be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Frontend route a window is showing, relative to the app root
+fn route(url: &tauri::Url) -> String {
+    let mut route = url.path().trim_start_matches('/').to_string();
+    if let Some(query) = url.query() {
+        route.push('?');
+        route.push_str(query);
+    }
+    if let Some(fragment) = url.fragment() {
+        route.push('#');
+        route.push_str(fragment);
+    }
+    route
+}
+
+fn capture<R: Runtime>(window: &WebviewWindow<R>) -> Result<WindowLayout, String> {
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    Ok(WindowLayout {
+        label: window.label().to_string(),
+        url: route(&window.url().map_err(|e| e.to_string())?),
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized().map_err(|e| e.to_string())?,
+        fullscreen: window.is_fullscreen().map_err(|e| e.to_string())?,
+        always_on_top: window.is_always_on_top().map_err(|e| e.to_string())?,
+        visible: window.is_visible().map_err(|e| e.to_string())?,
+    })
+}
+
+/// Move a window into its saved place and apply its flags
+fn apply<R: Runtime>(window: &WebviewWindow<R>, layout: &WindowLayout) -> Result<(), String> {
+    // Geometry only sticks on a restored window
+    window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    window
+        .set_size(PhysicalSize::new(layout.width, layout.height))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(layout.x, layout.y))
+        .map_err(|e| e.to_string())?;
+    // The layout may have been saved with a monitor that's gone now
+    crate::window::ensure_on_screen(window)?;
+    window
+        .set_always_on_top(layout.always_on_top)
+        .map_err(|e| e.to_string())?;
+
+    if layout.maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    if layout.fullscreen {
+        window.set_fullscreen(true).map_err(|e| e.to_string())?;
+    }
+    if layout.visible {
+        window.show().map_err(|e| e.to_string())
+    } else {
+        window.hide().map_err(|e| e.to_string())
+    }
+}
+
+/// Save every open window's route, geometry and flags as a named layout
+///
+/// Replaces an existing layout with the same name.
+#[tauri::command]
+pub async fn save_layout<R: Runtime>(app: AppHandle<R>, name: String) -> Result<Layout, String> {
+    let name = validate_name(&name)?;
+
+    let mut windows = app
+        .webview_windows()
+        .values()
+        .map(capture)
+        .collect::<Result<Vec<_>, _>>()?;
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let layout = Layout {
+        name: name.clone(),
+        windows,
+        saved_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let mut layouts = read(&app)?;
+    layouts.insert(name, layout.clone());
+    write(&app, &layouts)?;
+
+    log::info!("Saved layout {} with {} windows", layout.name, layout.windows.len());
+    Ok(layout)
+}
+
+/// Restore a saved layout, creating windows that aren't open
+///
+/// Windows that are already open keep their current page. Windows that
+/// aren't part of the layout are left alone, and under the single-window
+/// policy only windows that are already open are restored.
+#[tauri::command]
+pub async fn restore_layout<R: Runtime>(app: AppHandle<R>, name: String) -> Result<(), String> {
+    crate::kiosk::ensure_inactive(&app, "Restoring a layout")?;
+    let name = validate_name(&name)?;
+    let layout = read(&app)?
+        .remove(&name)
+        .ok_or_else(|| format!("Layout not found: {}", name))?;
+
+    let single_window =
+        crate::window::window_policy(&app) == crate::window::WindowPolicy::SingleWindow;
+    for saved in &layout.windows {
+        let window = match app.get_webview_window(&saved.label) {
+            Some(window) => window,
+            None if single_window => {
+                log::info!("Skipping window {} under the single-window policy", saved.label);
+                continue;
+            }
+            None => match crate::window::build_secondary(&app, &saved.label, &saved.url, false) {
+                Ok(window) => window,
+                Err(e) => {
+                    log::warn!("Failed to open window {}: {}", saved.label, e);
+                    continue;
+                }
+            },
+        };
+        if let Err(e) = apply(&window, saved) {
+            log::warn!("Failed to restore window {}: {}", saved.label, e);
+        }
+    }
+
+    // Focus the main window last so it ends up in front, as after launch
+    if let Some(main) = layout
+        .windows
+        .iter()
+        .find(|saved| saved.label == crate::window::MAIN_WINDOW && saved.visible)
+    {
+        crate::window::get_window(&app, &main.label)?
+            .set_focus()
+            .map_err(|e| e.to_string())?;
+    }
+
+    log::info!("Restored layout {}", layout.name);
+    Ok(())
+}
+
+/// List the saved layouts, sorted by name
+#[tauri::command]
+pub async fn list_layouts<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Layout>, String> {
+    Ok(read(&app)?.into_values().collect())
+}
+
+/// Delete a saved layout
+#[tauri::command]
+pub async fn delete_layout<R: Runtime>(app: AppHandle<R>, name: String) -> Result<(), String> {
+    let name = validate_name(&name)?;
+    let mut layouts = read(&app)?;
+    if layouts.remove(&name).is_none() {
+        return Err(format!("Layout not found: {}", name));
+    }
+    write(&app, &layouts)
+}
@@ -3,10 +3,77 @@
 //! This module provides native desktop functionality for the YULA OS application,
 //! including system notifications, deep linking, tray integration, and auto-updates.
 
-use tauri::{Emitter, Manager};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::Manager;
 
+mod about;
+mod accessibility;
+mod appearance;
+mod arch;
+mod audio;
+mod auto_lock;
+mod autostart;
+mod clipboard;
+mod clock;
+mod command_metrics;
 mod commands;
+mod csp;
+mod deep_link;
+mod dialogs;
+mod disk;
+mod displays;
+mod dock;
+mod effects;
+mod error;
+mod events;
+mod file_associations;
+mod files;
+mod handoff;
+mod intents;
+mod keyboard;
+mod keychain;
+mod kiosk;
+mod launch;
+mod layouts;
+mod local_server;
+mod media;
+mod memory;
+mod menu_bar;
+mod mini_mode;
+mod network;
+mod notifications;
+mod occlusion;
+mod offline_cache;
+mod permissions;
+mod platform;
+mod plugins;
+mod power;
+mod presence;
+mod profiles;
+mod progress;
+mod protocol_handler;
+mod quarantine;
+mod quick_capture;
+mod rollback;
+mod schedule;
+mod screen_capture;
+mod screenshot;
+mod selection;
+mod self_test;
+mod serial;
+mod session_stats;
+mod settings;
+mod shortcuts;
+mod signature;
+mod system_log;
+mod tasks;
+mod throttle;
 mod tray;
+mod updater;
+mod watcher;
+mod webview_storage;
+mod window;
 
 pub use commands::*;
 
@@ -15,50 +82,217 @@ pub use commands::*;
 pub struct AppState {
     pub notification_count: std::sync::atomic::AtomicU32,
     pub is_authenticated: std::sync::atomic::AtomicBool,
+    pub auto_launched: std::sync::atomic::AtomicBool,
+    pub screen_captured: std::sync::atomic::AtomicBool,
+    pub throttled: std::sync::atomic::AtomicBool,
+    pub reduce_motion: std::sync::atomic::AtomicBool,
+    pub high_contrast: std::sync::atomic::AtomicBool,
+    pub record_events: std::sync::atomic::AtomicBool,
+    pub background_suspended: std::sync::atomic::AtomicBool,
+    pub frontend_ready: std::sync::atomic::AtomicBool,
+    pub low_power: std::sync::atomic::AtomicBool,
+    pub on_battery: std::sync::atomic::AtomicBool,
+    pub kiosk_mode: std::sync::atomic::AtomicBool,
+    pub main_transparent: std::sync::atomic::AtomicBool,
+    pub(crate) announced_update: Mutex<Option<String>>,
+    pub(crate) audio_levels: Mutex<Option<audio::AudioLevelMonitor>>,
+    pub(crate) background_resume: tokio::sync::Notify,
+    pub(crate) background_tasks: Mutex<HashMap<String, tasks::TaskStatus>>,
+    pub(crate) clipboard_changed: tokio::sync::Notify,
+    pub(crate) clipboard_monitor: Mutex<clipboard::ClipboardMonitor>,
+    pub(crate) command_metrics: Mutex<command_metrics::CommandMetrics>,
+    pub(crate) content_protected: Mutex<HashMap<String, bool>>,
+    pub(crate) deep_link_history: Mutex<deep_link::DeepLinkHistory>,
+    pub(crate) displays_changed: tokio::sync::Notify,
+    pub(crate) event_log: Mutex<events::EventLog>,
+    pub(crate) file_streams: Mutex<files::FileStreams>,
+    pub(crate) handoff_tokens: Mutex<handoff::HandoffTokens>,
+    pub(crate) keyboard_layout: Mutex<Option<String>>,
+    pub(crate) kiosk_pin_attempts: Mutex<kiosk::PinAttempts>,
+    pub(crate) launch_events: Mutex<Vec<(&'static str, serde_json::Value)>>,
+    pub(crate) local_server: Mutex<Option<local_server::LocalServer>>,
+    pub(crate) local_shortcuts: Mutex<HashMap<u32, shortcuts::ShortcutBinding>>,
+    pub(crate) media_keys: Mutex<Option<media::MediaKeys>>,
+    pub(crate) menu_bar_hotkey: Mutex<Option<tauri_plugin_global_shortcut::Shortcut>>,
+    pub(crate) mini_mode: Mutex<Option<mini_mode::SavedGeometry>>,
+    pub(crate) notifications: Mutex<notifications::NotificationRegistry>,
+    pub(crate) notification_digest: Mutex<notifications::Digest>,
+    pub(crate) notification_rate: Mutex<notifications::RateLimiter>,
+    pub(crate) occluded: Mutex<HashMap<String, bool>>,
+    pub(crate) offline_cache: Mutex<offline_cache::OfflineCache>,
+    pub(crate) operations: Mutex<progress::Operations>,
+    pub(crate) pending_files: Mutex<Vec<std::path::PathBuf>>,
+    pub(crate) plugin_status: Mutex<HashMap<String, plugins::PluginStatus>>,
+    pub(crate) popover_hidden_at: Mutex<Option<std::time::Instant>>,
+    pub(crate) preloaded: Mutex<std::collections::HashSet<String>>,
+    pub(crate) presence: Mutex<Option<presence::PresenceHeartbeat>>,
+    pub(crate) profile: Mutex<Option<String>>,
+    pub(crate) schedule: Mutex<schedule::Schedule>,
+    pub(crate) self_test: Mutex<Option<self_test::SelfTestReport>>,
+    pub(crate) session: Mutex<session_stats::Session>,
+    pub(crate) settings: Mutex<settings::Settings>,
+    pub(crate) shortcuts: Mutex<HashMap<u32, shortcuts::ShortcutBinding>>,
+    pub(crate) size_constraints: Mutex<HashMap<String, window::SizeConstraints>>,
+    pub(crate) skip_taskbar: Mutex<HashMap<String, bool>>,
+    pub(crate) system_logs: Mutex<system_log::SystemLogTails>,
+    pub(crate) tray: Mutex<tray::TrayState>,
+    pub(crate) update_download: updater::DownloadControl,
+    pub(crate) updated_from: Mutex<Option<updater::UpdatedFrom>>,
+    pub(crate) updater_state: Mutex<updater::UpdaterState>,
+    pub(crate) user_agent: Mutex<String>,
+    pub(crate) watchers: Mutex<watcher::DirectoryWatchers>,
+}
+
+/// Stop background work and save session state before the process ends,
+/// whether it exits or relaunches
+pub(crate) fn shutdown<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    watcher::stop_all(app);
+    system_log::stop_all(app);
+    presence::stop(app);
+    audio::stop(app);
+    session_stats::record_runtime(app);
+    rollback::mark_healthy(app);
+}
+
+/// Relaunch the app with `env`, running the shutdown work that
+/// `AppHandle::restart` would skip along with the exit event
+pub(crate) fn restart<R: tauri::Runtime>(app: &tauri::AppHandle<R>, env: &tauri::Env) -> ! {
+    shutdown(app);
+    // Release the single-instance lock first, or the new process would just
+    // hand its arguments back to us
+    app.cleanup_before_exit();
+    tauri::process::restart(env)
+}
+
+/// Prepare the process environment; call from `main` before `run`, while
+/// no other thread can read it
+pub fn init_environment() {
+    #[cfg(target_os = "linux")]
+    window::disable_webkit_compositing();
 }
 
 /// Initialize the Tauri application with all plugins and event handlers
 pub fn run() {
     tauri::Builder::default()
-        .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_deep_link::init())
-        .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_clipboard_manager::init())
-        .plugin(tauri_plugin_autostart::init(
-            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            Some(vec!["--minimized"]),
-        ))
-        .plugin(tauri_plugin_updater::Builder::new().build())
-        .plugin(tauri_plugin_process::init())
-        .plugin(tauri_plugin_os::init())
         .manage(AppState::default())
         .setup(|app| {
             // Initialize logging
             env_logger::init();
             log::info!("YULA Desktop starting...");
+            launch::init(app.handle());
+
+            // Pick the profile before anything resolves its directories
+            profiles::init(app.handle());
+
+            // Register plugins, tolerating individual init failures
+            plugins::init_plugins(app.handle());
+
+            // Load persisted settings before anything reads them
+            settings::load(app.handle());
+            events::init(app.handle());
+
+            // Report clicks on and dismissals of notifications
+            notifications::init(app.handle());
+
+            // Count this launch for the local usage stats
+            session_stats::init(app.handle());
+
+            // Apply the persisted dock icon preference
+            #[cfg(target_os = "macos")]
+            {
+                let policy = settings::get(app.handle()).activation_policy;
+                if let Err(e) = commands::apply_activation_policy(app.handle(), policy) {
+                    log::warn!("Failed to apply activation policy: {}", e);
+                }
+            }
+
+            // Restore custom global shortcuts
+            if plugins::is_available(app.handle(), plugins::GLOBAL_SHORTCUT) {
+                shortcuts::restore(app.handle());
+            }
+
+            // Create the main window now that settings are available
+            window::create_main_window(app.handle())?;
+            appearance::init(app.handle());
 
             // Setup system tray
             tray::setup_tray(app.handle())?;
+            menu_bar::init(app.handle());
+
+            // Lock down again if the app quit in kiosk mode
+            kiosk::restore(app.handle());
 
             // Handle deep links - Tauri 2.x uses plugin setup
             #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
             {
-                use tauri_plugin_deep_link::DeepLinkExt;
-                let handle = app.handle().clone();
-                app.deep_link().on_open_url(move |event| {
-                    let urls = event.urls();
-                    log::info!("Deep link received: {:?}", urls);
-                    if let Some(window) = handle.get_webview_window("main") {
-                        for url in urls {
-                            let _ = window.emit("deep-link", url.to_string());
-                        }
-                        let _ = window.set_focus();
-                    }
-                });
+                if plugins::is_available(app.handle(), plugins::DEEP_LINK) {
+                    deep_link::init(app.handle());
+                }
+            }
+
+            // Warn when links open another side-by-side install
+            protocol_handler::check_launch(app.handle());
+
+            // Warn when login launches still point at a moved or replaced app
+            autostart::check_launch(app.handle());
+
+            // Expose actions to OS automation (jump lists on Windows)
+            match intents::register(app.handle()) {
+                Ok(()) => {}
+                Err(error::CommandError::NotSupported(e)) => log::debug!("{}", e),
+                Err(e) => log::warn!("Failed to register app intents: {}", e),
             }
 
+            // Open files passed by a file association at launch
+            file_associations::handle_launch_args(app.handle());
+
+            // Suggest the native build when running under Rosetta or emulation
+            arch::warn_if_emulated(app.handle());
+
+            // A wrong system clock breaks sign-in; tell the user early
+            clock::start(app.handle());
+
+            // Count crashes of a fresh update and offer rolling it back
+            rollback::check_launch(app.handle());
+            updater::check_launch(app.handle());
+
+            // Watch for screen sharing so private content can be hidden
+            screen_capture::start_monitor(app.handle());
+
+            // Tell the frontend when windows are covered
+            occlusion::start_monitor(app.handle());
+
+            // Follow monitor changes and rescue windows left off-screen
+            displays::start_monitor(app.handle());
+            displays::start_color_monitor(app.handle());
+
+            // Close file streams the frontend forgot about
+            files::start_reaper(app.handle());
+
+            // Watch for connected hardware
+            serial::start_monitor(app.handle());
+
+            // Follow the OS reduce-motion and high-contrast settings
+            accessibility::start_monitor(app.handle());
+
+            // Throttle background work in low-power mode
+            power::start_monitor(app.handle());
+
+            // Lock on idle or sleep where the user asked for it
+            auto_lock::start_monitor(app.handle());
+
+            // Fire scheduled notifications
+            schedule::start(app.handle());
+
+            // Tell the frontend when the input layout switches
+            keyboard::start_monitor(app.handle());
+
+            // Catch subsystems that stop working
+            self_test::start(app.handle());
+
+            // Suggest a reload when the webview grows too large
+            memory::start_monitor(app.handle());
+
             // Open devtools in debug mode
             #[cfg(debug_assertions)]
             {
@@ -80,23 +314,256 @@ pub fn run() {
             commands::install_update,
             commands::set_autostart,
             commands::get_autostart_enabled,
+            autostart::verify_autostart,
+            autostart::repair_autostart,
             commands::open_external_link,
             commands::copy_to_clipboard,
             commands::read_from_clipboard,
             commands::minimize_to_tray,
             commands::quit_app,
+            commands::restart_app,
+            commands::set_activation_policy,
+            commands::get_activation_policy,
+            commands::get_app_directory,
+            commands::open_app_directory,
+            about::show_about_panel,
+            accessibility::get_reduce_motion,
+            accessibility::set_reduce_motion_override,
+            accessibility::get_high_contrast,
+            appearance::set_appearance,
+            appearance::get_appearance,
+            arch::get_cpu_architecture_details,
+            audio::start_audio_level_monitoring,
+            audio::stop_audio_level_monitoring,
+            auto_lock::set_auto_lock,
+            auto_lock::get_auto_lock_settings,
+            clipboard::start_clipboard_monitoring,
+            clipboard::stop_clipboard_monitoring,
+            clipboard::set_clipboard_poll_strategy,
+            clipboard::get_clipboard_poll_strategy,
+            clock::check_clock_skew,
+            csp::get_effective_csp,
+            csp::set_csp_connect_sources,
+            deep_link::get_deep_link_history,
+            deep_link::clear_deep_link_history,
+            deep_link::set_deep_link_route,
+            deep_link::remove_deep_link_route,
+            dialogs::prompt_dialog,
+            dialogs::confirm_dialog,
+            disk::get_disk_usage,
+            displays::get_display_arrangement,
+            displays::get_effective_scaling,
+            displays::get_display_color_info,
+            dock::set_dock_icon,
+            dock::reset_dock_icon,
+            effects::set_window_effects,
+            effects::clear_window_effects,
+            effects::set_window_opacity,
+            effects::set_window_decorations_style,
+            events::get_event_manifest,
+            events::set_event_recording,
+            events::get_recent_events,
+            events::replay_events,
+            file_associations::register_file_association,
+            file_associations::is_file_association_registered,
+            files::open_file_stream,
+            files::read_file_chunk,
+            files::close_file_stream,
+            files::hash_file,
+            handoff::generate_handoff_token,
+            intents::register_app_intents,
+            keyboard::get_keyboard_layout,
+            kiosk::set_kiosk_pin,
+            kiosk::enter_kiosk_mode,
+            kiosk::exit_kiosk_mode,
+            kiosk::is_kiosk_mode,
+            launch::was_auto_launched,
+            launch::get_should_start_minimized,
+            launch::frontend_ready,
+            layouts::save_layout,
+            layouts::restore_layout,
+            layouts::list_layouts,
+            layouts::delete_layout,
+            local_server::start_local_server,
+            local_server::stop_local_server,
+            local_server::get_local_server_token,
+            media::enable_media_keys,
+            media::disable_media_keys,
+            media::set_now_playing,
+            memory::get_webview_memory,
+            memory::set_webview_memory_limit,
+            menu_bar::set_menu_bar_app_mode,
+            menu_bar::get_menu_bar_app_mode,
+            mini_mode::set_mini_mode,
+            mini_mode::is_mini_mode,
+            network::is_metered_connection,
+            notifications::set_notification_rate_limit,
+            notifications::set_notification_digest,
+            notifications::get_delivered_notifications,
+            notifications::remove_notification,
+            notifications::remove_all_notifications,
+            occlusion::is_window_occluded,
+            offline_cache::cache_put,
+            offline_cache::cache_get,
+            offline_cache::cache_delete,
+            offline_cache::cache_clear,
+            permissions::get_permissions_status,
+            permissions::request_notification_permission,
+            permissions::open_system_settings,
+            plugins::get_plugin_status,
+            power::is_low_power_mode,
+            presence::start_presence_heartbeat,
+            presence::stop_presence_heartbeat,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::switch_profile,
+            profiles::delete_profile,
+            progress::begin_operation,
+            progress::update_operation,
+            progress::complete_operation,
+            progress::get_active_operations,
+            protocol_handler::set_as_default_protocol_handler,
+            protocol_handler::is_default_protocol_handler,
+            quarantine::get_quarantine_status,
+            quarantine::clear_quarantine,
+            quick_capture::show_quick_capture,
+            quick_capture::hide_quick_capture,
+            quick_capture::submit_quick_capture_region,
+            rollback::get_rollback_info,
+            rollback::rollback_update,
+            schedule::schedule_notification,
+            schedule::cancel_scheduled_notification,
+            schedule::get_scheduled_notifications,
+            schedule::get_system_timezone,
+            screen_capture::is_screen_being_captured,
+            screenshot::capture_window,
+            selection::get_selected_text,
+            self_test::run_self_test,
+            self_test::set_periodic_self_test,
+            serial::list_serial_ports,
+            session_stats::get_session_stats,
+            session_stats::reset_session_stats,
+            command_metrics::record_command_samples,
+            command_metrics::get_command_metrics,
+            command_metrics::reset_command_metrics,
+            settings::validate_settings,
+            settings::repair_settings,
+            settings::export_settings,
+            settings::export_settings_format,
+            settings::import_settings,
+            shortcuts::register_shortcut,
+            shortcuts::unregister_shortcut,
+            shortcuts::list_shortcuts,
+            shortcuts::register_local_shortcut,
+            shortcuts::unregister_local_shortcut,
+            shortcuts::list_local_shortcuts,
+            signature::verify_app_signature,
+            signature::set_require_valid_signature,
+            system_log::tail_system_log,
+            system_log::stop_tail_system_log,
+            system_log::set_system_log_access,
+            tasks::suspend_background_tasks,
+            tasks::resume_background_tasks,
+            tasks::get_background_task_status,
+            throttle::set_background_throttle,
+            tray::set_tray_icon,
+            tray::set_tray_status,
+            tray::start_tray_blink,
+            tray::stop_tray_blink,
+            tray::get_tray_rect,
+            tray::set_tray_click_behavior,
+            tray::get_tray_click_behavior,
+            updater::get_update_changelog,
+            updater::simulate_update_available,
+            updater::simulate_update_progress,
+            updater::set_update_download_dir,
+            updater::get_updater_state,
+            updater::get_updated_from,
+            updater::pause_update_download,
+            updater::resume_update_download,
+            updater::cancel_update_download,
+            updater::update_dry_run,
+            watcher::watch_directory,
+            watcher::unwatch_directory,
+            webview_storage::get_webview_storage_usage,
+            webview_storage::clear_webview_cache,
+            window::set_hardware_acceleration,
+            window::get_hardware_acceleration,
+            window::set_size_constraints,
+            window::get_size_constraints,
+            window::set_visible_on_all_workspaces,
+            window::force_foreground,
+            window::open_window,
+            window::preload_window,
+            window::show_preloaded_window,
+            window::set_window_policy,
+            window::get_window_policy,
+            window::set_return_focus_on_hide,
+            window::set_skip_taskbar,
+            window::get_skip_taskbar,
+            window::set_content_protection,
+            window::get_content_protection,
+            window::reload_window,
+            window::eval_in_webview,
+            window::get_window_scale_factor,
+            window::get_user_agent,
+            window::set_user_agent,
         ])
-        .on_window_event(|_window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                // On macOS, closing the window just hides it (default behavior)
+        .on_window_event(|window, event| match event {
+            // The kiosk window can't be closed or hidden
+            tauri::WindowEvent::CloseRequested { api, .. }
+                if window.label() == window::MAIN_WINDOW && kiosk::is_active(window.app_handle()) =>
+            {
+                api.prevent_close();
+            }
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                // On macOS, closing the main window just hides it
+                #[cfg(target_os = "macos")]
+                {
+                    if window.label() == window::MAIN_WINDOW {
+                        api.prevent_close();
+                        if let Err(e) = window::hide(window) {
+                            log::warn!("Failed to hide main window: {}", e);
+                        }
+                    }
+                }
                 // On other platforms, we could minimize to tray
                 #[cfg(not(target_os = "macos"))]
                 {
                     // Note: For non-macOS, the window closes - tray minimization
                     // would require additional configuration
+                    let _ = api;
                 }
             }
+            tauri::WindowEvent::Focused(focused) => {
+                throttle::on_focus_changed(window, *focused);
+                tray::on_focus_changed(window, *focused);
+                shortcuts::on_focus_changed(window, *focused);
+                menu_bar::on_focus_changed(window, *focused);
+            }
+            tauri::WindowEvent::Resized(_) => {
+                kiosk::on_resized(window);
+            }
+            tauri::WindowEvent::Destroyed => {
+                window::on_window_destroyed(window);
+            }
+            tauri::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                window::on_scale_factor_changed(window, *scale_factor);
+            }
+            tauri::WindowEvent::ThemeChanged(theme) => {
+                appearance::on_theme_changed(window, *theme);
+            }
+            _ => {}
         })
-        .run(tauri::generate_context!())
-        .expect("Error while running YULA Desktop");
+        .build(tauri::generate_context!())
+        .expect("Error while running YULA Desktop")
+        .run(|app, event| match event {
+            tauri::RunEvent::Exit => shutdown(app),
+            // Files opened from Finder arrive through the app delegate
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                file_associations::on_opened(app, &urls);
+            }
+            _ => {}
+        });
 }
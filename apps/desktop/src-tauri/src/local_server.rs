@@ -0,0 +1,293 @@
+//! Local integration server for YULA Desktop
+//!
+//! Lets third-party tools and browser extensions hand content to the app
+//! over `127.0.0.1`. Every request must carry `Authorization: Bearer
+//! <token>`; the token is generated per start and written with the port to
+//! `local-server.json` in the app data directory, where only local tools
+//! running as the same user can read it: the file is created with mode
+//! `0600` on Unix, and on Windows the per-user app data directory's ACL
+//! already keeps other users out. Requiring the header also forces a CORS
+//! preflight, which we never answer, so arbitrary web pages can't call in.
+//!
+//! API (JSON bodies, forwarded to the frontend as `integration-action`):
+//! - `POST /v1/new-chat` `{ "text"?: string }`
+//! - `POST /v1/open-url` `{ "url": string }`
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+
+/// File the port and token are published in
+const INFO_FILE: &str = "local-server.json";
+
+/// Largest accepted request body
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+type JsonResponse = Response<std::io::Cursor<Vec<u8>>>;
+
+/// A running local server
+pub struct LocalServer {
+    server: Arc<Server>,
+    port: u16,
+    token: String,
+}
+
+impl std::fmt::Debug for LocalServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalServer")
+            .field("port", &self.port)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Contents of `local-server.json`
+#[derive(Debug, Serialize)]
+struct ServerInfo<'a> {
+    port: u16,
+    token: &'a str,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NewChatRequest {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenUrlRequest {
+    url: String,
+}
+
+/// Payload of the `integration-action` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub(crate) enum IntegrationAction {
+    NewChat { text: Option<String> },
+    OpenUrl { url: String },
+}
+
+fn info_path<R: Runtime>(app: &AppHandle<R>) -> Result<std::path::PathBuf, String> {
+    crate::profiles::data_dir(app).map(|dir| dir.join(INFO_FILE))
+}
+
+/// Replace the info file through a temporary file only the current user can
+/// read, so the token is never exposed or half-written
+fn write_info(path: &std::path::Path, info: &str) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    // `create_new` won't follow a file or link left in the way, so clear it
+    match std::fs::remove_file(&tmp_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(info.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, path)
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compare without short-circuiting so timing doesn't leak the token
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> JsonResponse {
+    let header = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    Response::from_string(body.to_string())
+        .with_status_code(StatusCode(status))
+        .with_header(header)
+}
+
+fn error(status: u16, message: &str) -> JsonResponse {
+    json_response(status, serde_json::json!({ "error": message }))
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    let body = if body.trim().is_empty() { "{}" } else { &body };
+    serde_json::from_str(body).map_err(|e| e.to_string())
+}
+
+/// Authenticate and dispatch a single request
+fn handle<R: Runtime>(app: &AppHandle<R>, token: &str, request: &mut Request) -> JsonResponse {
+    let authorized = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .is_some_and(|provided| token_matches(token, provided.trim()));
+    if !authorized {
+        return error(401, "Missing or invalid token");
+    }
+
+    if *request.method() != Method::Post {
+        return error(405, "Method not allowed");
+    }
+
+    let url = request.url().to_string();
+    let action = match url.as_str() {
+        "/v1/new-chat" => match parse_body::<NewChatRequest>(request) {
+            Ok(body) => IntegrationAction::NewChat { text: body.text },
+            Err(e) => return error(400, &e),
+        },
+        "/v1/open-url" => match parse_body::<OpenUrlRequest>(request) {
+            Ok(body) => {
+                let valid = tauri::Url::parse(&body.url)
+                    .is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+                if !valid {
+                    return error(400, "Only http(s) URLs are accepted");
+                }
+                IntegrationAction::OpenUrl { url: body.url }
+            }
+            Err(e) => return error(400, &e),
+        },
+        _ => return error(404, "Not found"),
+    };
+
+    log::info!("Integration request: {:?}", action);
+    if let Some(window) = app.get_webview_window(crate::window::MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    crate::events::emit(app, crate::events::INTEGRATION_ACTION, action);
+
+    json_response(202, serde_json::json!({ "ok": true }))
+}
+
+/// Start the local server, returning the port it listens on
+///
+/// Binds to a random free port when `port` is `None`. Starting while running
+/// returns the current port.
+#[tauri::command]
+pub async fn start_local_server<R: Runtime>(
+    app: AppHandle<R>,
+    port: Option<u16>,
+) -> Result<u16, String> {
+    let state = app.state::<crate::AppState>();
+    let mut local_server = state.local_server.lock().unwrap();
+    if let Some(running) = local_server.as_ref() {
+        return Ok(running.port);
+    }
+
+    let server = Server::http(("127.0.0.1", port.unwrap_or(0))).map_err(|e| e.to_string())?;
+    let port = server
+        .server_addr()
+        .to_ip()
+        .map(|addr| addr.port())
+        .ok_or("Server is not bound to an IP address")?;
+    let server = Arc::new(server);
+    let token = generate_token();
+
+    let path = info_path(&app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let info = serde_json::to_string(&ServerInfo {
+        port,
+        token: &token,
+    })
+    .map_err(|e| e.to_string())?;
+    write_info(&path, &info).map_err(|e| e.to_string())?;
+
+    let app_handle = app.clone();
+    let listener = server.clone();
+    let request_token = token.clone();
+    std::thread::spawn(move || {
+        for mut request in listener.incoming_requests() {
+            let response = handle(&app_handle, &request_token, &mut request);
+            if let Err(e) = request.respond(response) {
+                log::debug!("Failed to respond to integration request: {}", e);
+            }
+        }
+        log::info!("Local server stopped");
+    });
+
+    log::info!("Local server listening on 127.0.0.1:{}", port);
+    *local_server = Some(LocalServer {
+        server,
+        port,
+        token,
+    });
+
+    Ok(port)
+}
+
+/// Stop the local server if it is running
+#[tauri::command]
+pub async fn stop_local_server<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let Some(running) = app
+        .state::<crate::AppState>()
+        .local_server
+        .lock()
+        .unwrap()
+        .take()
+    else {
+        return Ok(());
+    };
+
+    running.server.unblock();
+    let path = info_path(&app)?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Get the token integrations must present, if the server is running
+#[tauri::command]
+pub fn get_local_server_token<R: Runtime>(app: AppHandle<R>) -> Option<String> {
+    app.state::<crate::AppState>()
+        .local_server
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|running| running.token.clone())
+}
+
+// Windows relies on the directory's ACL instead
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_the_info_file_for_the_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("yula-local-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(INFO_FILE);
+
+        write_info(&path, "first").unwrap();
+        write_info(&path, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,5 +6,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    yula_desktop_lib::init_environment();
     yula_desktop_lib::run();
 }
@@ -0,0 +1,192 @@
+//! System media controls for YULA Desktop
+//!
+//! Hooks into the OS media layer (MPRemoteCommandCenter on macOS, System
+//! Media Transport Controls on Windows, MPRIS on Linux) through `souvlaki`,
+//! so hardware media keys control response playback and the OS media
+//! overlay shows what's playing. Key presses are emitted as
+//! `media-key { action }`.
+//!
+//! The platform handles aren't thread-safe, so they live on a dedicated
+//! thread that takes commands over a channel while enabled.
+
+use serde::Serialize;
+use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, PlatformConfig};
+use std::sync::mpsc;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Name the app is registered under with MPRIS
+const DBUS_NAME: &str = "yula";
+
+/// Payload of the `media-key` event
+#[derive(Debug, Clone, Serialize)]
+struct MediaKeyPayload {
+    action: &'static str,
+}
+
+/// Request handled on the media controls thread
+enum MediaCommand {
+    NowPlaying {
+        title: String,
+        artist: Option<String>,
+        artwork: Option<String>,
+    },
+    Stop,
+}
+
+/// Handle to the thread owning the media controls
+pub struct MediaKeys {
+    commands: mpsc::Sender<MediaCommand>,
+}
+
+impl std::fmt::Debug for MediaKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaKeys").finish_non_exhaustive()
+    }
+}
+
+fn action(event: &MediaControlEvent) -> Option<&'static str> {
+    match event {
+        MediaControlEvent::Play => Some("play"),
+        MediaControlEvent::Pause => Some("pause"),
+        MediaControlEvent::Toggle => Some("play-pause"),
+        MediaControlEvent::Next => Some("next"),
+        MediaControlEvent::Previous => Some("previous"),
+        MediaControlEvent::Stop => Some("stop"),
+        _ => None,
+    }
+}
+
+/// Create the controls, forward key presses and serve commands until stopped
+fn run<R: Runtime>(
+    app: AppHandle<R>,
+    hwnd: Option<usize>,
+    commands: mpsc::Receiver<MediaCommand>,
+    ready: mpsc::Sender<Result<(), String>>,
+) {
+    let display_name = app
+        .config()
+        .product_name
+        .clone()
+        .unwrap_or_else(|| "YULA".to_string());
+    let config = PlatformConfig {
+        dbus_name: DBUS_NAME,
+        display_name: &display_name,
+        hwnd: hwnd.map(|hwnd| hwnd as *mut std::ffi::c_void),
+    };
+
+    let mut controls = match MediaControls::new(config) {
+        Ok(controls) => controls,
+        Err(e) => {
+            let _ = ready.send(Err(format!("Failed to create media controls: {:?}", e)));
+            return;
+        }
+    };
+    let handle = app.clone();
+    let attached = controls.attach(move |event| {
+        if let Some(action) = action(&event) {
+            crate::events::emit(
+                &handle,
+                crate::events::MEDIA_KEY,
+                MediaKeyPayload { action },
+            );
+        }
+    });
+    if let Err(e) = attached {
+        let _ = ready.send(Err(format!("Failed to attach media controls: {:?}", e)));
+        return;
+    }
+    let _ = ready.send(Ok(()));
+
+    while let Ok(MediaCommand::NowPlaying {
+        title,
+        artist,
+        artwork,
+    }) = commands.recv()
+    {
+        let metadata = MediaMetadata {
+            title: Some(&title),
+            artist: artist.as_deref(),
+            cover_url: artwork.as_deref(),
+            ..Default::default()
+        };
+        if let Err(e) = controls.set_metadata(metadata) {
+            log::warn!("Failed to set now playing: {:?}", e);
+        }
+        if let Err(e) = controls.set_playback(MediaPlayback::Playing { progress: None }) {
+            log::warn!("Failed to set playback state: {:?}", e);
+        }
+    }
+
+    if let Err(e) = controls.detach() {
+        log::warn!("Failed to detach media controls: {:?}", e);
+    }
+}
+
+/// Start receiving hardware media keys
+#[tauri::command]
+pub async fn enable_media_keys<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    if state.media_keys.lock().unwrap().is_some() {
+        return Ok(());
+    }
+
+    // SMTC is attached to a window on Windows
+    #[cfg(target_os = "windows")]
+    let hwnd = Some(
+        crate::window::main_window(&app)?
+            .hwnd()
+            .map_err(|e| e.to_string())?
+            .0 as usize,
+    );
+    #[cfg(not(target_os = "windows"))]
+    let hwnd = None;
+
+    let (commands, receiver) = mpsc::channel();
+    let (ready, ready_rx) = mpsc::channel();
+    let handle = app.clone();
+    std::thread::Builder::new()
+        .name("media-keys".to_string())
+        .spawn(move || run(handle, hwnd, receiver, ready))
+        .map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || ready_rx.recv())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|_| "Media controls thread exited".to_string())??;
+
+    log::info!("Media keys enabled");
+    *state.media_keys.lock().unwrap() = Some(MediaKeys { commands });
+    Ok(())
+}
+
+/// Stop receiving hardware media keys and clear the OS media overlay
+#[tauri::command]
+pub fn disable_media_keys<R: Runtime>(app: AppHandle<R>) {
+    if let Some(keys) = app.state::<crate::AppState>().media_keys.lock().unwrap().take() {
+        let _ = keys.commands.send(MediaCommand::Stop);
+        log::info!("Media keys disabled");
+    }
+}
+
+/// Show what's playing in the OS media overlay
+///
+/// `artwork` is an image URL (`https://` or `file://`). Requires media keys
+/// to be enabled.
+#[tauri::command]
+pub fn set_now_playing<R: Runtime>(
+    app: AppHandle<R>,
+    title: String,
+    artist: Option<String>,
+    artwork: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    let keys = state.media_keys.lock().unwrap();
+    let keys = keys.as_ref().ok_or("Media keys are not enabled")?;
+    keys.commands
+        .send(MediaCommand::NowPlaying {
+            title,
+            artist,
+            artwork,
+        })
+        .map_err(|_| "Media controls thread exited".to_string())
+}
@@ -0,0 +1,140 @@
+//! Webview memory monitoring for YULA Desktop
+//!
+//! The webview renders in helper processes spawned by the app: WebView2's
+//! `msedgewebview2.exe` on Windows and WebKitGTK's `WebKit*Process` on
+//! Linux. Their resident memory is summed to report the webview's usage. On
+//! macOS the WebKit helpers are XPC services owned by launchd rather than
+//! children of the app, so they can't be attributed and usage is unknown.
+//!
+//! A background monitor emits `webview-memory-high` once usage crosses the
+//! configured limit, and again only after it has dropped back below.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use tauri::{AppHandle, Runtime};
+
+/// How often webview memory is sampled
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Highest accepted memory limit, 1 TiB
+const MAX_LIMIT_MB: u64 = 1024 * 1024;
+
+/// Name fragments of webview helper processes, lowercased
+#[cfg(target_os = "windows")]
+const WEBVIEW_PROCESSES: &[&str] = &["msedgewebview2"];
+#[cfg(not(target_os = "windows"))]
+const WEBVIEW_PROCESSES: &[&str] = &["webkit"];
+
+/// Payload of the `webview-memory-high` event
+#[derive(Debug, Clone, Serialize)]
+struct MemoryHighPayload {
+    rss_bytes: u64,
+    limit_bytes: u64,
+}
+
+/// Sum the resident memory of webview processes descended from the app
+fn webview_rss() -> Option<u64> {
+    let root = sysinfo::get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    // Walk the process tree down from the app, since the webview may be a
+    // grandchild (e.g. WebView2 renderers under its browser process)
+    let mut descendants: HashSet<Pid> = HashSet::from([root]);
+    loop {
+        let before = descendants.len();
+        for (pid, process) in system.processes() {
+            if process.parent().is_some_and(|parent| descendants.contains(&parent)) {
+                descendants.insert(*pid);
+            }
+        }
+        if descendants.len() == before {
+            break;
+        }
+    }
+
+    let webview: Vec<u64> = descendants
+        .iter()
+        .filter(|pid| **pid != root)
+        .filter_map(|pid| system.process(*pid))
+        .filter(|process| {
+            let name = process.name().to_string_lossy().to_lowercase();
+            WEBVIEW_PROCESSES.iter().any(|marker| name.contains(marker))
+        })
+        .map(|process| process.memory())
+        .collect();
+
+    (!webview.is_empty()).then(|| webview.iter().sum())
+}
+
+/// Start the background monitor that emits `webview-memory-high`
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut high = false;
+        loop {
+            crate::tasks::tick(&app, "webview-memory", POLL_INTERVAL).await;
+
+            let Some(limit_mb) = crate::settings::get(&app).webview_memory_limit_mb else {
+                high = false;
+                continue;
+            };
+            let Some(rss) = tauri::async_runtime::spawn_blocking(webview_rss)
+                .await
+                .ok()
+                .flatten()
+            else {
+                continue;
+            };
+
+            // Settings edited by hand aren't bounded like the command's input
+            let limit = limit_mb.saturating_mul(1024 * 1024);
+            if rss > limit && !high {
+                log::warn!("Webview memory {} MiB exceeds {} MiB", rss >> 20, limit_mb);
+                crate::events::emit(
+                    &app,
+                    crate::events::WEBVIEW_MEMORY_HIGH,
+                    MemoryHighPayload {
+                        rss_bytes: rss,
+                        limit_bytes: limit,
+                    },
+                );
+            }
+            high = rss > limit;
+        }
+    });
+}
+
+/// Get the resident memory of the webview processes in bytes, if known
+#[tauri::command]
+pub async fn get_webview_memory() -> Option<u64> {
+    tauri::async_runtime::spawn_blocking(webview_rss)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Check a memory limit before it's stored
+pub fn validate_limit(limit_mb: Option<u64>) -> Result<(), String> {
+    if limit_mb == Some(0) {
+        return Err("Memory limit must be greater than zero".to_string());
+    }
+    if limit_mb.is_some_and(|limit_mb| limit_mb > MAX_LIMIT_MB) {
+        return Err(format!("Memory limit must be at most {} MiB", MAX_LIMIT_MB));
+    }
+    Ok(())
+}
+
+/// Set the memory limit in MiB above which `webview-memory-high` is emitted
+///
+/// `None` disables the check.
+#[tauri::command]
+pub async fn set_webview_memory_limit<R: Runtime>(
+    app: AppHandle<R>,
+    limit_mb: Option<u64>,
+) -> Result<(), String> {
+    validate_limit(limit_mb)?;
+    crate::settings::update(&app, |settings| settings.webview_memory_limit_mb = limit_mb)
+}
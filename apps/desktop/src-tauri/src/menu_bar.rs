@@ -0,0 +1,230 @@
+//! Menu bar app mode for YULA Desktop
+//!
+//! Turns the app into an always-available assistant that lives in the tray:
+//! the dock icon is hidden (macOS accessory policy), clicking the tray icon
+//! or pressing the summon hotkey toggles the main window as a popover
+//! anchored to the icon, the popover hides again when it loses focus, and
+//! it follows the user across workspaces. Turning the mode off restores the
+//! persisted activation policy and workspace preference.
+
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, PhysicalPosition, Runtime, WebviewWindow, Window};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+use crate::error::CommandError;
+
+/// Hotkey summoning the popover unless another one is chosen
+pub const DEFAULT_HOTKEY: &str = "CmdOrCtrl+Shift+Space";
+
+/// Gap between the tray icon and the popover, in physical pixels
+const TRAY_GAP: i32 = 6;
+
+/// How recently the popover must have hidden on blur for a toggle to leave
+/// it hidden; clicking the tray blurs the popover just before the click
+const BLUR_TOGGLE_GRACE: Duration = Duration::from_millis(300);
+
+/// Whether the app runs as a menu bar app; kiosk mode suspends it
+pub fn is_enabled<R: Runtime>(app: &AppHandle<R>) -> bool {
+    crate::settings::get(app).menu_bar_mode && !crate::kiosk::is_active(app)
+}
+
+/// Place a window next to the tray icon, inside the icon's monitor
+///
+/// The popover opens below an icon in the top half of the screen (the macOS
+/// menu bar) and above one in the bottom half (the Windows taskbar).
+fn position_near_tray<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    let Some(tray) = crate::tray::rect(window.app_handle()) else {
+        return Ok(());
+    };
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let (center_x, center_y) = (
+        tray.x + tray.width as i32 / 2,
+        tray.y + tray.height as i32 / 2,
+    );
+
+    let monitor = window
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|monitor| {
+            let (position, size) = (monitor.position(), monitor.size());
+            (position.x..position.x + size.width as i32).contains(&center_x)
+                && (position.y..position.y + size.height as i32).contains(&center_y)
+        });
+    let Some(monitor) = monitor else {
+        return Ok(());
+    };
+    let area = monitor.work_area();
+
+    let x = (center_x - size.width as i32 / 2).clamp(
+        area.position.x,
+        (area.position.x + area.size.width as i32 - size.width as i32).max(area.position.x),
+    );
+    let y = if center_y < monitor.position().y + monitor.size().height as i32 / 2 {
+        tray.y + tray.height as i32 + TRAY_GAP
+    } else {
+        tray.y - size.height as i32 - TRAY_GAP
+    };
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
+
+/// Show the popover next to the tray icon, or hide it when it's in front
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let window = crate::window::main_window(app)?;
+    let in_front = window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false);
+    if in_front {
+        return crate::window::hide(&window.as_ref().window());
+    }
+    // The click that blurred the popover meant to close it
+    let hidden_at = app.state::<crate::AppState>().popover_hidden_at.lock().unwrap().take();
+    if hidden_at.is_some_and(|hidden_at| hidden_at.elapsed() < BLUR_TOGGLE_GRACE) {
+        return Ok(());
+    }
+
+    position_near_tray(&window)?;
+    crate::window::bring_to_front(app)
+}
+
+/// Hide the popover when it loses focus, as native menu bar apps do
+pub fn on_focus_changed<R: Runtime>(window: &Window<R>, focused: bool) {
+    if focused || window.label() != crate::window::MAIN_WINDOW {
+        return;
+    }
+    if is_enabled(window.app_handle()) && window.is_visible().unwrap_or(false) {
+        if let Err(e) = crate::window::hide(window) {
+            log::warn!("Failed to hide popover: {}", e);
+        }
+        *window.state::<crate::AppState>().popover_hidden_at.lock().unwrap() = Some(Instant::now());
+    }
+}
+
+/// Whether a pressed shortcut is the summon hotkey
+pub fn is_hotkey<R: Runtime>(app: &AppHandle<R>, id: u32) -> bool {
+    app.try_state::<crate::AppState>()
+        .and_then(|state| *state.menu_bar_hotkey.lock().unwrap())
+        .is_some_and(|hotkey| hotkey.id() == id)
+}
+
+fn register_hotkey<R: Runtime>(app: &AppHandle<R>, accelerator: &str) -> Result<(), CommandError> {
+    crate::plugins::require(app, crate::plugins::GLOBAL_SHORTCUT)?;
+    let shortcut = crate::shortcuts::parse(accelerator)?;
+    if is_hotkey(app, shortcut.id()) {
+        return Ok(());
+    }
+    unregister_hotkey(app);
+
+    let state = app.state::<crate::AppState>();
+    let taken = state.shortcuts.lock().unwrap().contains_key(&shortcut.id())
+        || state.local_shortcuts.lock().unwrap().contains_key(&shortcut.id());
+    if taken {
+        return Err(CommandError::ShortcutAlreadyRegistered(accelerator.to_string()));
+    }
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| CommandError::ShortcutAlreadyRegistered(format!("{}: {}", accelerator, e)))?;
+
+    *state.menu_bar_hotkey.lock().unwrap() = Some(shortcut);
+    Ok(())
+}
+
+fn unregister_hotkey<R: Runtime>(app: &AppHandle<R>) {
+    let hotkey: Option<Shortcut> = app
+        .state::<crate::AppState>()
+        .menu_bar_hotkey
+        .lock()
+        .unwrap()
+        .take();
+    if let Some(hotkey) = hotkey {
+        if let Err(e) = app.global_shortcut().unregister(hotkey) {
+            log::warn!("Failed to unregister summon hotkey: {}", e);
+        }
+    }
+}
+
+/// Switch into or out of menu bar mode without persisting it
+pub fn apply<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> Result<(), CommandError> {
+    let settings = crate::settings::get(app);
+
+    #[cfg(target_os = "macos")]
+    {
+        let policy = if enabled {
+            crate::commands::ActivationPolicy::Accessory
+        } else {
+            settings.activation_policy
+        };
+        crate::commands::apply_activation_policy(app, policy)?;
+    }
+
+    // Windows can't pin a window to every virtual desktop
+    #[cfg(not(target_os = "windows"))]
+    crate::window::main_window(app)?
+        .set_visible_on_all_workspaces(enabled || settings.visible_on_all_workspaces)
+        .map_err(|e| CommandError::Failed(e.to_string()))?;
+
+    // The popover takes over the left click in menu bar mode
+    crate::tray::apply_click_behavior(app);
+
+    if enabled {
+        register_hotkey(app, &settings.menu_bar_hotkey)
+    } else {
+        unregister_hotkey(app);
+        Ok(())
+    }
+}
+
+/// Restore menu bar mode at launch, once the main window and tray exist
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    if is_enabled(app) {
+        if let Err(e) = apply(app, true) {
+            log::warn!("Failed to restore menu bar mode: {}", e);
+        }
+    }
+}
+
+/// Run as a menu bar app: no dock icon, a tray popover and a summon hotkey
+///
+/// `hotkey` replaces the summon accelerator when given. Disabling restores
+/// the regular dock presence.
+#[tauri::command]
+pub async fn set_menu_bar_app_mode<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+    hotkey: Option<String>,
+) -> Result<(), CommandError> {
+    crate::kiosk::ensure_inactive(&app, "Menu bar mode")?;
+    if let Some(hotkey) = &hotkey {
+        crate::shortcuts::parse(hotkey)?;
+    }
+
+    // `apply` reads the mode from the settings, so they change first and
+    // go back if it fails
+    let previous = crate::settings::get(&app);
+    crate::settings::update(&app, |settings| {
+        settings.menu_bar_mode = enabled;
+        if let Some(hotkey) = hotkey {
+            settings.menu_bar_hotkey = hotkey;
+        }
+    })?;
+
+    if let Err(e) = apply(&app, enabled) {
+        log::warn!("Failed to switch menu bar mode, reverting: {}", e);
+        crate::settings::update(&app, |settings| {
+            settings.menu_bar_mode = previous.menu_bar_mode;
+            settings.menu_bar_hotkey = previous.menu_bar_hotkey;
+        })?;
+        if let Err(e) = apply(&app, previous.menu_bar_mode) {
+            log::warn!("Failed to restore menu bar mode: {}", e);
+        }
+        return Err(e);
+    }
+    log::info!("Menu bar mode: {}", enabled);
+    Ok(())
+}
+
+/// Check whether the app runs as a menu bar app
+#[tauri::command]
+pub fn get_menu_bar_app_mode<R: Runtime>(app: AppHandle<R>) -> bool {
+    is_enabled(&app)
+}
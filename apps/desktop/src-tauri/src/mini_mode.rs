@@ -0,0 +1,120 @@
+//! Mini mode for YULA Desktop
+//!
+//! Turns the main window into a compact, undecorated chat widget pinned
+//! above other windows in the bottom-right corner of the current monitor.
+//! The previous geometry, decorations and size limits are kept in
+//! `AppState` and restored when mini mode is turned off.
+
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalPosition, PhysicalSize, Runtime};
+
+use crate::window::SizeConstraints;
+
+/// Logical size of the mini window
+const MINI_SIZE: (f64, f64) = (360.0, 520.0);
+
+/// Logical gap between the mini window and the screen edges
+const MARGIN: f64 = 16.0;
+
+/// Main window state saved when entering mini mode
+#[derive(Debug, Clone)]
+pub struct SavedGeometry {
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    maximized: bool,
+    decorations: bool,
+    always_on_top: bool,
+    constraints: SizeConstraints,
+}
+
+/// Switch the main window into or out of mini mode
+#[tauri::command]
+pub async fn set_mini_mode<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Result<(), String> {
+    if enabled {
+        crate::kiosk::ensure_inactive(&app, "Mini mode")?;
+    }
+    let window = crate::window::main_window(&app)?;
+    let state = app.state::<crate::AppState>();
+    let mut mini_mode = state.mini_mode.lock().unwrap();
+
+    if enabled == mini_mode.is_some() {
+        return Ok(());
+    }
+
+    if enabled {
+        let saved = SavedGeometry {
+            position: window.outer_position().map_err(|e| e.to_string())?,
+            size: window.inner_size().map_err(|e| e.to_string())?,
+            maximized: window.is_maximized().map_err(|e| e.to_string())?,
+            decorations: window.is_decorated().map_err(|e| e.to_string())?,
+            always_on_top: window.is_always_on_top().map_err(|e| e.to_string())?,
+            constraints: state
+                .size_constraints
+                .lock()
+                .unwrap()
+                .get(crate::window::MAIN_WINDOW)
+                .copied()
+                .unwrap_or_default(),
+        };
+
+        window.unmaximize().map_err(|e| e.to_string())?;
+        // The regular minimum size is larger than the mini window
+        crate::window::apply_size_constraints(&app, &window, None, None)?;
+        window.set_decorations(false).map_err(|e| e.to_string())?;
+        window
+            .set_size(LogicalSize::new(MINI_SIZE.0, MINI_SIZE.1))
+            .map_err(|e| e.to_string())?;
+
+        if let Some(monitor) = window.current_monitor().map_err(|e| e.to_string())? {
+            let scale = monitor.scale_factor();
+            let work_area = monitor.work_area();
+            let margin = (MARGIN * scale).round() as i32;
+            let width = (MINI_SIZE.0 * scale).round() as i32;
+            let height = (MINI_SIZE.1 * scale).round() as i32;
+            window
+                .set_position(PhysicalPosition::new(
+                    work_area.position.x + work_area.size.width as i32 - width - margin,
+                    work_area.position.y + work_area.size.height as i32 - height - margin,
+                ))
+                .map_err(|e| e.to_string())?;
+        }
+
+        window.set_always_on_top(true).map_err(|e| e.to_string())?;
+        crate::window::apply_skip_taskbar(&window, true)?;
+        *mini_mode = Some(saved);
+    } else if let Some(saved) = mini_mode.take() {
+        crate::window::apply_skip_taskbar(&window, false)?;
+        window
+            .set_always_on_top(saved.always_on_top)
+            .map_err(|e| e.to_string())?;
+        window
+            .set_decorations(saved.decorations)
+            .map_err(|e| e.to_string())?;
+        crate::window::apply_size_constraints(
+            &app,
+            &window,
+            saved.constraints.min,
+            saved.constraints.max,
+        )?;
+        window.set_size(saved.size).map_err(|e| e.to_string())?;
+        window
+            .set_position(saved.position)
+            .map_err(|e| e.to_string())?;
+        if saved.maximized {
+            window.maximize().map_err(|e| e.to_string())?;
+        }
+    }
+
+    drop(mini_mode);
+    crate::events::emit(&app, crate::events::MINI_MODE_CHANGED, enabled);
+    Ok(())
+}
+
+/// Check whether the main window is in mini mode
+#[tauri::command]
+pub fn is_mini_mode<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<crate::AppState>()
+        .mini_mode
+        .lock()
+        .unwrap()
+        .is_some()
+}
@@ -0,0 +1,161 @@
+//! Network helpers for YULA Desktop
+//!
+//! Lets the UI ask for consent before large downloads: the size of a remote
+//! file from its `Content-Length`, and whether the active connection is
+//! metered according to the OS network-cost APIs. Background requests also
+//! check whether the machine is online at all before trying.
+
+use std::time::Duration;
+
+/// Timeout for metadata requests
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Size of a remote file from a `HEAD` request, if the server reports it
+pub async fn content_length(url: &tauri::Url) -> Option<u64> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .ok()?;
+
+    match client.head(url.as_str()).send().await {
+        Ok(response) if response.status().is_success() => response.content_length(),
+        Ok(response) => {
+            log::debug!("HEAD {} returned {}", url, response.status());
+            None
+        }
+        Err(e) => {
+            log::debug!("HEAD {} failed: {}", url, e);
+            None
+        }
+    }
+}
+
+/// Check whether the active connection is metered, `None` if unknown
+fn metered() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+        let profile = NetworkInformation::GetInternetConnectionProfile().ok()?;
+        let cost = profile.GetConnectionCost().ok()?;
+        let cost_type = cost.NetworkCostType().ok()?;
+        let metered = cost_type != NetworkCostType::Unrestricted
+            || cost.Roaming().unwrap_or(false)
+            || cost.OverDataLimit().unwrap_or(false);
+        Some(metered)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // NMMetered: 1 = yes, 3 = guessed yes; 2 and 4 are the "no" values
+        let output = crate::platform::output(
+            "busctl",
+            &[
+                "get-property",
+                "org.freedesktop.NetworkManager",
+                "/org/freedesktop/NetworkManager",
+                "org.freedesktop.NetworkManager",
+                "Metered",
+            ],
+        )?;
+        match output.strip_prefix("u ")?.trim() {
+            "1" | "3" => Some(true),
+            "2" | "4" => Some(false),
+            _ => None,
+        }
+    }
+
+    // NWPath's isExpensive/isConstrained are only delivered through the
+    // asynchronous path monitor, which we don't run yet
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    None
+}
+
+/// Check whether the machine has internet access, `None` if unknown
+fn online() -> Option<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Networking::Connectivity::{NetworkConnectivityLevel, NetworkInformation};
+
+        // No profile at all means no connection
+        let Ok(profile) = NetworkInformation::GetInternetConnectionProfile() else {
+            return Some(false);
+        };
+        let level = profile.GetNetworkConnectivityLevel().ok()?;
+        Some(level == NetworkConnectivityLevel::InternetAccess)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::ffi::c_void;
+
+        #[link(name = "SystemConfiguration", kind = "framework")]
+        extern "C" {
+            fn SCNetworkReachabilityCreateWithName(
+                allocator: *const c_void,
+                nodename: *const std::ffi::c_char,
+            ) -> *const c_void;
+            fn SCNetworkReachabilityGetFlags(target: *const c_void, flags: *mut u32) -> u8;
+        }
+        #[link(name = "CoreFoundation", kind = "framework")]
+        extern "C" {
+            fn CFRelease(cf: *const c_void);
+        }
+
+        const REACHABLE: u32 = 1 << 1;
+        const CONNECTION_REQUIRED: u32 = 1 << 2;
+
+        unsafe {
+            let target = SCNetworkReachabilityCreateWithName(std::ptr::null(), c"yula.ai".as_ptr());
+            if target.is_null() {
+                return None;
+            }
+            let mut flags = 0u32;
+            let known = SCNetworkReachabilityGetFlags(target, &mut flags) != 0;
+            CFRelease(target);
+            known.then_some(flags & REACHABLE != 0 && flags & CONNECTION_REQUIRED == 0)
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // NMConnectivityState: 4 is full, 1-3 are none, portal and limited
+        let output = crate::platform::output(
+            "busctl",
+            &[
+                "get-property",
+                "org.freedesktop.NetworkManager",
+                "/org/freedesktop/NetworkManager",
+                "org.freedesktop.NetworkManager",
+                "Connectivity",
+            ],
+        )?;
+        match output.strip_prefix("u ")?.trim() {
+            "4" => Some(true),
+            "1" | "2" | "3" => Some(false),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    None
+}
+
+/// Whether the machine is online, assuming it is when that's unknown
+pub async fn is_online() -> bool {
+    tauri::async_runtime::spawn_blocking(online)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(true)
+}
+
+/// Check whether the active connection is metered (false if unknown)
+#[tauri::command]
+pub async fn is_metered_connection() -> bool {
+    tauri::async_runtime::spawn_blocking(metered)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
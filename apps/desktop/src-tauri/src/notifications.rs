@@ -0,0 +1,1196 @@
+//! Notification center management for YULA Desktop
+//!
+//! The notification plugin doesn't report what happens to notifications on
+//! desktop, so they're shown natively instead: through the UserNotifications
+//! framework on macOS, as toasts on Windows and over D-Bus on Linux. Every
+//! notification we show is tracked here. Clicking one emits
+//! `notification-activated` and raises the main window; clearing it without
+//! acting emits `notification-dismissed`. On Linux both come from the
+//! server's `ActionInvoked` and `NotificationClosed` signals, watched by a
+//! single listener on the connection the notifications are sent over.
+//! Removal is forwarded to the OS notification center. Unbundled macOS
+//! builds can't use UserNotifications and fall back to the plugin, without
+//! interaction events; bundled ones ask for authorization when the first
+//! notification is shown. Windows toasts are kept alive until they're gone
+//! from the action center, as their events stop once they're dropped.
+//!
+//! Delivery is rate limited: past the configured number per minute,
+//! notifications are dropped and, if enabled, a single summary is shown once
+//! the window frees up. In digest mode they're held instead, and one
+//! "N new messages from X chats" notification is shown when the digest
+//! window ends; clicking it emits `notification-digest-click`.
+
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Maximum number of notifications kept in the registry
+const MAX_TRACKED: usize = 100;
+
+/// Window the rate limit is counted over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Longest accepted digest window, in seconds
+const MAX_DIGEST_SECS: u32 = 60 * 60;
+
+/// Notifications held for a digest; later ones are only counted
+const MAX_DIGEST_ITEMS: usize = 500;
+
+/// Outcome of a notification request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NotificationResult {
+    /// The notification was shown
+    Shown,
+    /// The notification was dropped by the rate limiter
+    RateLimited,
+    /// The notification was held for the next digest
+    Digested,
+}
+
+/// What the user did with a notification
+#[derive(Debug, Clone, Copy)]
+enum Interaction {
+    Activated,
+    Dismissed,
+}
+
+/// Payload of the `notification-activated` and `notification-dismissed` events
+#[derive(Debug, Clone, Serialize)]
+struct InteractionPayload {
+    id: i32,
+}
+
+/// A notification that was shown and not yet removed
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveredNotification {
+    pub id: i32,
+    pub title: String,
+    pub body: String,
+    pub delivered_at: i64,
+}
+
+/// Registry of notifications shown by the app
+#[derive(Debug, Default)]
+pub struct NotificationRegistry {
+    next_id: i32,
+    delivered: Vec<DeliveredNotification>,
+    /// Toasts still in the action center, whose events only fire while
+    /// they're alive
+    #[cfg(target_os = "windows")]
+    toasts: std::collections::HashMap<i32, windows::UI::Notifications::ToastNotification>,
+}
+
+impl NotificationRegistry {
+    /// Allocate an id for a new notification
+    pub fn next_id(&mut self) -> i32 {
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.next_id
+    }
+
+    /// Record a notification that was shown
+    pub fn record(&mut self, id: i32, title: &str, body: &str) {
+        if self.delivered.len() >= MAX_TRACKED {
+            let oldest = self.delivered.remove(0);
+            self.forget(oldest.id);
+        }
+        self.delivered.push(DeliveredNotification {
+            id,
+            title: title.to_string(),
+            body: body.to_string(),
+            delivered_at: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+
+    /// Drop a notification that is no longer showing
+    fn forget(&mut self, id: i32) {
+        self.delivered.retain(|n| n.id != id);
+        #[cfg(target_os = "windows")]
+        self.toasts.remove(&id);
+    }
+}
+
+/// Sliding-window limiter for notification delivery
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    sent: VecDeque<Instant>,
+    suppressed: u32,
+}
+
+impl RateLimiter {
+    /// Count a notification against the limit, returning whether it may be shown
+    fn allow(&mut self, per_minute: Option<u32>) -> bool {
+        let Some(limit) = per_minute else {
+            return true;
+        };
+
+        let now = Instant::now();
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+
+        if self.sent.len() < limit as usize {
+            self.sent.push_back(now);
+            true
+        } else {
+            self.suppressed += 1;
+            false
+        }
+    }
+
+    /// Time until the oldest counted notification leaves the window
+    fn retry_after(&self) -> Duration {
+        self.sent
+            .front()
+            .map(|sent| RATE_WINDOW.saturating_sub(sent.elapsed()))
+            .unwrap_or_default()
+    }
+}
+
+/// A rate-limited notification held for the digest
+#[derive(Debug, Clone)]
+struct DigestItem {
+    group: String,
+    title: String,
+    body: String,
+}
+
+/// Payload of the `notification-digest-click` event
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationDigest {
+    /// Notifications the digest stood for
+    pub count: usize,
+    /// Groups of the ones held, e.g. chat ids, in the order they first arrived
+    pub groups: Vec<String>,
+}
+
+/// Notifications held for the next digest, and the last one shown
+#[derive(Debug, Default)]
+pub struct Digest {
+    pending: Vec<DigestItem>,
+    /// Notifications past `MAX_DIGEST_ITEMS`, counted but not held
+    overflow: usize,
+    /// Notification id of the last digest shown
+    shown: Option<(i32, NotificationDigest)>,
+}
+
+fn app_name<R: Runtime>(app: &AppHandle<R>) -> String {
+    app.config()
+        .product_name
+        .clone()
+        .unwrap_or_else(|| "YULA".to_string())
+}
+
+/// Set up interaction reporting; call once at launch
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    #[cfg(target_os = "macos")]
+    {
+        let app = app.clone();
+        macos::init(move |id, interaction| on_interaction(&app, id, interaction));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
+
+/// Ask for permission to show notifications, prompting if the OS hasn't
+/// asked yet
+pub async fn request_permission<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<crate::permissions::PermissionState, String> {
+    #[cfg(target_os = "macos")]
+    {
+        if macos::is_active() {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let tx = std::sync::Mutex::new(Some(tx));
+            macos::request_authorization(move |granted| {
+                if let Some(tx) = tx.lock().unwrap().take() {
+                    let _ = tx.send(granted);
+                }
+            });
+            let granted = rx.await.map_err(|e| e.to_string())?;
+            return Ok(if granted {
+                crate::permissions::PermissionState::Granted
+            } else {
+                crate::permissions::PermissionState::Denied
+            });
+        }
+    }
+
+    use tauri_plugin_notification::NotificationExt;
+
+    crate::plugins::require(app, crate::plugins::NOTIFICATION)?;
+    app.notification()
+        .request_permission()
+        .map_err(|e| e.to_string())?;
+    Ok(crate::permissions::notification_state(app))
+}
+
+/// Report a click on or dismissal of a notification we showed
+fn on_interaction<R: Runtime>(app: &AppHandle<R>, id: i32, interaction: Interaction) {
+    let state = app.state::<crate::AppState>();
+    // Either way it's gone from the notification center
+    state.notifications.lock().unwrap().forget(id);
+
+    match interaction {
+        Interaction::Activated => {
+            log::debug!("Notification {} activated", id);
+            if let Err(e) = crate::window::bring_to_front(app) {
+                log::warn!("Failed to raise main window: {}", e);
+            }
+            crate::events::emit(
+                app,
+                crate::events::NOTIFICATION_ACTIVATED,
+                InteractionPayload { id },
+            );
+
+            let digest = state
+                .notification_digest
+                .lock()
+                .unwrap()
+                .shown
+                .take_if(|(shown, _)| *shown == id);
+            if let Some((_, digest)) = digest {
+                crate::events::emit(app, crate::events::NOTIFICATION_DIGEST_CLICK, digest);
+            }
+        }
+        Interaction::Dismissed => {
+            log::debug!("Notification {} dismissed", id);
+            crate::events::emit(
+                app,
+                crate::events::NOTIFICATION_DISMISSED,
+                InteractionPayload { id },
+            );
+        }
+    }
+}
+
+/// Show a notification through the notification plugin
+#[cfg(target_os = "macos")]
+fn show_with_plugin<R: Runtime>(
+    app: &AppHandle<R>,
+    id: i32,
+    title: &str,
+    body: &str,
+    icon: Option<&str>,
+) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    let mut notification = app.notification().builder();
+    notification = notification.id(id).title(title).body(body);
+
+    if let Some(icon_path) = icon {
+        notification = notification.icon(icon_path);
+    }
+
+    notification.show().map_err(|e| e.to_string())
+}
+
+/// Show a notification and track it, returning its id
+fn deliver<R: Runtime>(
+    app: &AppHandle<R>,
+    title: &str,
+    body: &str,
+    icon: Option<&str>,
+) -> Result<i32, String> {
+    let id = app
+        .state::<crate::AppState>()
+        .notifications
+        .lock()
+        .unwrap()
+        .next_id();
+
+    #[cfg(target_os = "macos")]
+    {
+        if macos::is_active() {
+            // UserNotifications always shows the app icon
+            macos::show(id, title, body);
+        } else {
+            show_with_plugin(app, id, title, body, icon)?;
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    let toast = win::show(app, id, title, body, icon)?;
+
+    #[cfg(target_os = "linux")]
+    linux::show(app, id, title, body, icon)?;
+
+    let state = app.state::<crate::AppState>();
+    let mut registry = state.notifications.lock().unwrap();
+    registry.record(id, title, body);
+    #[cfg(target_os = "windows")]
+    registry.toasts.insert(id, toast);
+
+    Ok(id)
+}
+
+/// Show a notification unless the rate limit has been reached
+pub fn notify<R: Runtime>(
+    app: &AppHandle<R>,
+    title: &str,
+    body: &str,
+    icon: Option<&str>,
+) -> Result<NotificationResult, String> {
+    notify_in_group(app, None, title, body, icon)
+}
+
+/// Show a notification unless the rate limit has been reached, counting it
+/// under `group` (the title if `None`) when it's held for a digest
+pub fn notify_in_group<R: Runtime>(
+    app: &AppHandle<R>,
+    group: Option<&str>,
+    title: &str,
+    body: &str,
+    icon: Option<&str>,
+) -> Result<NotificationResult, String> {
+    let settings = crate::settings::get(app);
+    let (allowed, first_dropped, retry_after) = {
+        let state = app.state::<crate::AppState>();
+        let mut limiter = state.notification_rate.lock().unwrap();
+        let allowed = limiter.allow(settings.notification_rate_limit);
+        (allowed, limiter.suppressed == 1, limiter.retry_after())
+    };
+
+    if allowed {
+        deliver(app, title, body, icon)?;
+        return Ok(NotificationResult::Shown);
+    }
+
+    log::debug!("Notification rate limited: {}", title);
+    if let Some(window_secs) = settings.notification_digest_secs {
+        let state = app.state::<crate::AppState>();
+        // Held notifications are covered by the digest, not the summary
+        state.notification_rate.lock().unwrap().suppressed = 0;
+        let mut digest = state.notification_digest.lock().unwrap();
+        if digest.pending.len() >= MAX_DIGEST_ITEMS {
+            digest.overflow += 1;
+            return Ok(NotificationResult::Digested);
+        }
+        digest.pending.push(DigestItem {
+            group: group.unwrap_or(title).to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+        });
+        if digest.pending.len() == 1 {
+            schedule_digest(app, Duration::from_secs(window_secs.into()));
+        }
+        return Ok(NotificationResult::Digested);
+    }
+    if settings.notification_summary && first_dropped {
+        schedule_summary(app, retry_after);
+    }
+
+    Ok(NotificationResult::RateLimited)
+}
+
+/// Show a single "N new notifications" summary once the window frees up
+fn schedule_summary<R: Runtime>(app: &AppHandle<R>, delay: Duration) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+
+        let suppressed = {
+            let state = app.state::<crate::AppState>();
+            let mut limiter = state.notification_rate.lock().unwrap();
+            let suppressed = std::mem::take(&mut limiter.suppressed);
+            if suppressed > 0 {
+                // The summary takes a slot like any other notification
+                limiter.sent.push_back(Instant::now());
+            }
+            suppressed
+        };
+        if suppressed == 0 {
+            return;
+        }
+
+        let body = if suppressed == 1 {
+            "1 new notification".to_string()
+        } else {
+            format!("{} new notifications", suppressed)
+        };
+        if let Err(e) = deliver(&app, &app_name(&app), &body, None) {
+            log::warn!("Failed to show notification summary: {}", e);
+        }
+    });
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Show the held notifications as one digest once `window` has passed
+fn schedule_digest<R: Runtime>(app: &AppHandle<R>, window: Duration) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(window).await;
+
+        let state = app.state::<crate::AppState>();
+        let (items, overflow) = {
+            let mut digest = state.notification_digest.lock().unwrap();
+            (std::mem::take(&mut digest.pending), std::mem::take(&mut digest.overflow))
+        };
+        let count = items.len() + overflow;
+        let mut groups: Vec<String> = Vec::new();
+        for item in &items {
+            if !groups.contains(&item.group) {
+                groups.push(item.group.clone());
+            }
+        }
+        let (title, body) = match items.as_slice() {
+            [] => return,
+            [item] if count == 1 => (item.title.clone(), item.body.clone()),
+            _ if groups.len() == 1 => (items[0].title.clone(), plural(count, "new message")),
+            _ => (
+                app_name(&app),
+                format!(
+                    "{} from {}",
+                    plural(count, "new message"),
+                    plural(groups.len(), "chat")
+                ),
+            ),
+        };
+
+        // The digest takes a slot like any other notification
+        state.notification_rate.lock().unwrap().sent.push_back(Instant::now());
+        match deliver(&app, &title, &body, None) {
+            Ok(id) => {
+                let digest = NotificationDigest {
+                    count,
+                    groups,
+                };
+                state.notification_digest.lock().unwrap().shown = Some((id, digest));
+            }
+            Err(e) => log::warn!("Failed to show notification digest: {}", e),
+        }
+    });
+}
+
+/// Check a per-minute notification limit before it's stored
+pub fn validate_rate_limit(per_minute: Option<u32>) -> Result<(), String> {
+    if per_minute == Some(0) {
+        return Err("Rate limit must be at least one per minute".to_string());
+    }
+    Ok(())
+}
+
+/// Check a digest window before it's stored
+pub fn validate_digest_secs(window_secs: u32) -> Result<(), String> {
+    if !(1..=MAX_DIGEST_SECS).contains(&window_secs) {
+        return Err(format!(
+            "Digest window must be between 1 and {} seconds",
+            MAX_DIGEST_SECS
+        ));
+    }
+    Ok(())
+}
+
+/// Set how many notifications may be shown per minute (`None` for no limit)
+#[tauri::command]
+pub async fn set_notification_rate_limit<R: Runtime>(
+    app: AppHandle<R>,
+    per_minute: Option<u32>,
+    summarize: bool,
+) -> Result<(), String> {
+    validate_rate_limit(per_minute)?;
+    crate::settings::update(&app, |settings| {
+        settings.notification_rate_limit = per_minute;
+        settings.notification_summary = summarize;
+    })
+}
+
+/// Hold rate-limited notifications for `window_secs` and show them as one
+/// digest, instead of dropping them
+#[tauri::command]
+pub async fn set_notification_digest<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+    window_secs: u32,
+) -> Result<(), String> {
+    if enabled {
+        validate_digest_secs(window_secs)?;
+    }
+
+    crate::settings::update(&app, |settings| {
+        settings.notification_digest_secs = enabled.then_some(window_secs);
+    })
+}
+
+/// Ids of our notifications the OS notification center still holds, `None`
+/// where it can't be asked
+async fn os_delivered<R: Runtime>(app: &AppHandle<R>) -> Result<Option<HashSet<i32>>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app;
+        if !macos::is_active() {
+            return Ok(None);
+        }
+        macos::delivered_ids().await.map(Some)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        win::delivered_ids(&app.config().identifier).map(Some)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Ok(None)
+    }
+}
+
+/// Get notifications still showing in the notification center
+///
+/// Asks the notification center on macOS and Windows, so notifications the
+/// user cleared there are left out. Linux servers can't be asked, and
+/// unbundled macOS builds don't know the ids, so there it's the ones shown
+/// and not yet clicked, dismissed or removed.
+#[tauri::command]
+pub async fn get_delivered_notifications<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<DeliveredNotification>, String> {
+    let showing = os_delivered(&app).await?;
+    let state = app.state::<crate::AppState>();
+    let mut registry = state.notifications.lock().unwrap();
+    if let Some(showing) = showing {
+        let gone: Vec<i32> = registry
+            .delivered
+            .iter()
+            .map(|n| n.id)
+            .filter(|id| !showing.contains(id))
+            .collect();
+        for id in gone {
+            registry.forget(id);
+        }
+    }
+    Ok(registry.delivered.clone())
+}
+
+/// Remove a single delivered notification
+#[tauri::command]
+pub async fn remove_notification<R: Runtime>(app: AppHandle<R>, id: i32) -> Result<(), String> {
+    let removed = {
+        let state = app.state::<crate::AppState>();
+        let mut registry = state.notifications.lock().unwrap();
+        let removed = registry
+            .delivered
+            .iter()
+            .find(|n| n.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Notification not found: {}", id))?;
+        registry.forget(id);
+        removed
+    };
+
+    #[cfg(target_os = "macos")]
+    macos::remove_delivered(removed.id, &removed.title, &removed.body);
+
+    #[cfg(target_os = "windows")]
+    win::remove(&app.config().identifier, removed.id)?;
+
+    #[cfg(target_os = "linux")]
+    linux::remove(removed.id);
+
+    Ok(())
+}
+
+/// Remove all delivered notifications
+#[tauri::command]
+pub async fn remove_all_notifications<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    {
+        let state = app.state::<crate::AppState>();
+        let mut registry = state.notifications.lock().unwrap();
+        registry.delivered.clear();
+        #[cfg(target_os = "windows")]
+        registry.toasts.clear();
+    }
+
+    #[cfg(target_os = "macos")]
+    macos::remove_all_delivered();
+
+    #[cfg(target_os = "windows")]
+    win::clear_history(&app.config().identifier)?;
+
+    #[cfg(target_os = "linux")]
+    linux::remove_all();
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use block2::{DynBlock, RcBlock};
+    use objc2::rc::Retained;
+    use objc2::runtime::{Bool, NSObject, NSObjectProtocol, ProtocolObject};
+    use objc2::{define_class, msg_send, AllocAnyThread};
+    use objc2_foundation::{NSArray, NSBundle, NSError, NSSet, NSString, NSUserNotificationCenter};
+    use objc2_user_notifications::{
+        UNAuthorizationOptions, UNMutableNotificationContent, UNNotification,
+        UNNotificationCategory, UNNotificationCategoryOptions,
+        UNNotificationDefaultActionIdentifier, UNNotificationDismissActionIdentifier,
+        UNNotificationPresentationOptions, UNNotificationRequest, UNNotificationResponse,
+        UNUserNotificationCenter, UNUserNotificationCenterDelegate,
+    };
+    use std::collections::HashSet;
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::OnceLock;
+
+    use super::Interaction;
+
+    /// Category whose notifications report being dismissed
+    const CATEGORY: &str = "yula";
+
+    /// Set once authorization was asked for, on the first notification
+    static AUTHORIZATION_ASKED: AtomicBool = AtomicBool::new(false);
+
+    type Handler = Box<dyn Fn(i32, Interaction) + Send + Sync>;
+
+    /// Set once UserNotifications is in use
+    static HANDLER: OnceLock<Handler> = OnceLock::new();
+
+    define_class!(
+        #[unsafe(super(NSObject))]
+        #[name = "YULANotificationDelegate"]
+        struct Delegate;
+
+        unsafe impl NSObjectProtocol for Delegate {}
+
+        unsafe impl UNUserNotificationCenterDelegate for Delegate {
+            #[unsafe(method(userNotificationCenter:didReceiveNotificationResponse:withCompletionHandler:))]
+            fn did_receive_response(
+                &self,
+                _center: &UNUserNotificationCenter,
+                response: &UNNotificationResponse,
+                completion_handler: &DynBlock<dyn Fn()>,
+            ) {
+                let (action, id) = unsafe {
+                    let id = response.notification().request().identifier().to_string();
+                    (response.actionIdentifier(), id.parse::<i32>().ok())
+                };
+                let interaction = unsafe {
+                    if &*action == UNNotificationDefaultActionIdentifier {
+                        Some(Interaction::Activated)
+                    } else if &*action == UNNotificationDismissActionIdentifier {
+                        Some(Interaction::Dismissed)
+                    } else {
+                        None
+                    }
+                };
+                if let (Some(id), Some(interaction)) = (id, interaction) {
+                    if let Some(handler) = HANDLER.get() {
+                        handler(id, interaction);
+                    }
+                }
+                completion_handler.call(());
+            }
+
+            // Show banners even while the app is frontmost, as on other platforms
+            #[unsafe(method(userNotificationCenter:willPresentNotification:withCompletionHandler:))]
+            fn will_present(
+                &self,
+                _center: &UNUserNotificationCenter,
+                _notification: &UNNotification,
+                completion_handler: &DynBlock<dyn Fn(UNNotificationPresentationOptions)>,
+            ) {
+                completion_handler.call((UNNotificationPresentationOptions::Banner
+                    | UNNotificationPresentationOptions::List
+                    | UNNotificationPresentationOptions::Sound,));
+            }
+        }
+    );
+
+    /// Whether notifications go through UserNotifications
+    pub fn is_active() -> bool {
+        HANDLER.get().is_some()
+    }
+
+    /// Deliver through UserNotifications and report interactions to
+    /// `handler`, if this is a bundled app; the framework refuses others
+    pub fn init(handler: impl Fn(i32, Interaction) + Send + Sync + 'static) {
+        if NSBundle::mainBundle().bundleIdentifier().is_none() {
+            log::info!("Not running from an app bundle; notification clicks won't be reported");
+            return;
+        }
+        if HANDLER.set(Box::new(handler)).is_err() {
+            return;
+        }
+
+        unsafe {
+            let center = UNUserNotificationCenter::currentNotificationCenter();
+            let delegate = Delegate::alloc().set_ivars(());
+            let delegate: Retained<Delegate> = msg_send![super(delegate), init];
+            center.setDelegate(Some(ProtocolObject::from_ref(&*delegate)));
+            // The center holds its delegate weakly; this one lives as long as the app
+            std::mem::forget(delegate);
+
+            // Dismissals are only reported for categories that ask for them
+            let category =
+                UNNotificationCategory::categoryWithIdentifier_actions_intentIdentifiers_options(
+                    &NSString::from_str(CATEGORY),
+                    &NSArray::new(),
+                    &NSArray::new(),
+                    UNNotificationCategoryOptions::CustomDismissAction,
+                );
+            center.setNotificationCategories(&NSSet::from_retained_slice(&[category]));
+        }
+    }
+
+    /// Ask for permission to show notifications, prompting only while it
+    /// is undetermined, and pass on whether it was granted
+    pub fn request_authorization(then: impl Fn(bool) + Send + 'static) {
+        AUTHORIZATION_ASKED.store(true, Ordering::SeqCst);
+        let completion = RcBlock::new(move |granted: Bool, _error: *mut NSError| {
+            if !granted.as_bool() {
+                log::info!("Notifications weren't authorized");
+            }
+            then(granted.as_bool());
+        });
+        unsafe {
+            UNUserNotificationCenter::currentNotificationCenter()
+                .requestAuthorizationWithOptions_completionHandler(
+                    UNAuthorizationOptions::Alert
+                        | UNAuthorizationOptions::Sound
+                        | UNAuthorizationOptions::Badge,
+                    &completion,
+                );
+        }
+    }
+
+    pub fn show(id: i32, title: &str, body: &str) {
+        // Requests added before authorization is settled are dropped
+        if !AUTHORIZATION_ASKED.load(Ordering::SeqCst) {
+            let (title, body) = (title.to_string(), body.to_string());
+            request_authorization(move |granted| {
+                if granted {
+                    add_request(id, &title, &body);
+                }
+            });
+            return;
+        }
+        add_request(id, title, body);
+    }
+
+    fn add_request(id: i32, title: &str, body: &str) {
+        unsafe {
+            let content = UNMutableNotificationContent::new();
+            content.setTitle(&NSString::from_str(title));
+            content.setBody(&NSString::from_str(body));
+            content.setCategoryIdentifier(&NSString::from_str(CATEGORY));
+            let request = UNNotificationRequest::requestWithIdentifier_content_trigger(
+                &NSString::from_str(&id.to_string()),
+                &content,
+                None,
+            );
+            UNUserNotificationCenter::currentNotificationCenter()
+                .addNotificationRequest_withCompletionHandler(&request, None);
+        }
+    }
+
+    // Without UserNotifications the plugin delivers through
+    // NSUserNotificationCenter, which only knows the title and body
+    #[allow(deprecated)]
+    pub fn remove_delivered(id: i32, title: &str, body: &str) {
+        if is_active() {
+            let identifier = NSString::from_str(&id.to_string());
+            unsafe {
+                UNUserNotificationCenter::currentNotificationCenter()
+                    .removeDeliveredNotificationsWithIdentifiers(&NSArray::from_retained_slice(
+                        &[identifier],
+                    ));
+            }
+            return;
+        }
+
+        unsafe {
+            let center = NSUserNotificationCenter::defaultUserNotificationCenter();
+            for notification in center.deliveredNotifications().iter() {
+                let matches_title =
+                    notification.title().map(|t| t.to_string()).as_deref() == Some(title);
+                let matches_body =
+                    notification.informativeText().map(|t| t.to_string()).as_deref() == Some(body);
+                if matches_title && matches_body {
+                    center.removeDeliveredNotification(&notification);
+                }
+            }
+        }
+    }
+
+    /// Ids of the notifications still in Notification Center
+    pub async fn delivered_ids() -> Result<HashSet<i32>, String> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = std::sync::Mutex::new(Some(tx));
+        let completion = RcBlock::new(move |notifications: NonNull<NSArray<UNNotification>>| {
+            let ids = unsafe { notifications.as_ref() }
+                .iter()
+                .filter_map(|notification| unsafe {
+                    notification.request().identifier().to_string().parse().ok()
+                })
+                .collect();
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send(ids);
+            }
+        });
+        unsafe {
+            UNUserNotificationCenter::currentNotificationCenter()
+                .getDeliveredNotificationsWithCompletionHandler(&completion);
+        }
+        rx.await.map_err(|e| e.to_string())
+    }
+
+    #[allow(deprecated)]
+    pub fn remove_all_delivered() {
+        unsafe {
+            if is_active() {
+                UNUserNotificationCenter::currentNotificationCenter()
+                    .removeAllDeliveredNotifications();
+            } else {
+                NSUserNotificationCenter::defaultUserNotificationCenter()
+                    .removeAllDeliveredNotifications();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    use std::collections::HashSet;
+    use tauri::{AppHandle, Runtime};
+    use windows::core::{IInspectable, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{
+        ToastDismissalReason, ToastDismissedEventArgs, ToastNotification,
+        ToastNotificationManager,
+    };
+
+    use super::Interaction;
+
+    /// Toast group every notification is tagged under, for removal
+    const GROUP: &str = "yula";
+
+    /// Debug builds aren't installed under an app id, so their toasts are
+    /// shown as PowerShell's, as the notification plugin does
+    const POWERSHELL_APP_ID: &str =
+        r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+    fn app_id(identifier: &str) -> HSTRING {
+        HSTRING::from(if cfg!(debug_assertions) {
+            POWERSHELL_APP_ID
+        } else {
+            identifier
+        })
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn toast_xml(title: &str, body: &str, icon: Option<&str>) -> String {
+        let image = icon
+            .map(|path| {
+                format!(
+                    r#"<image placement="appLogoOverride" src="file:///{}"/>"#,
+                    escape(path)
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            concat!(
+                r#"<toast><visual><binding template="ToastGeneric">"#,
+                "<text>{}</text><text>{}</text>{}",
+                "</binding></visual></toast>"
+            ),
+            escape(title),
+            escape(body),
+            image
+        )
+    }
+
+    fn show_toast<R: Runtime>(
+        app: &AppHandle<R>,
+        id: i32,
+        title: &str,
+        body: &str,
+        icon: Option<&str>,
+    ) -> windows::core::Result<ToastNotification> {
+        let document = XmlDocument::new()?;
+        document.LoadXml(&HSTRING::from(toast_xml(title, body, icon)))?;
+        let toast = ToastNotification::CreateToastNotification(&document)?;
+        toast.SetTag(&HSTRING::from(id.to_string()))?;
+        toast.SetGroup(&HSTRING::from(GROUP))?;
+
+        let handle = app.clone();
+        toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(
+            move |_, _| {
+                super::on_interaction(&handle, id, Interaction::Activated);
+                Ok(())
+            },
+        ))?;
+        let handle = app.clone();
+        toast.Dismissed(&TypedEventHandler::<ToastNotification, ToastDismissedEventArgs>::new(
+            move |_, args| {
+                // Timed-out toasts move to the action center; hidden ones we removed
+                if args.ok()?.Reason()? == ToastDismissalReason::UserCanceled {
+                    super::on_interaction(&handle, id, Interaction::Dismissed);
+                }
+                Ok(())
+            },
+        ))?;
+
+        ToastNotificationManager::CreateToastNotifierWithId(&app_id(&app.config().identifier))?
+            .Show(&toast)?;
+        Ok(toast)
+    }
+
+    /// Show a toast, returning it to be kept alive for its events
+    pub fn show<R: Runtime>(
+        app: &AppHandle<R>,
+        id: i32,
+        title: &str,
+        body: &str,
+        icon: Option<&str>,
+    ) -> Result<ToastNotification, String> {
+        show_toast(app, id, title, body, icon).map_err(|e| e.to_string())
+    }
+
+    pub fn remove(identifier: &str, id: i32) -> Result<(), String> {
+        ToastNotificationManager::History()
+            .and_then(|history| {
+                history.RemoveGroupedTagWithId(
+                    &HSTRING::from(id.to_string()),
+                    &HSTRING::from(GROUP),
+                    &app_id(identifier),
+                )
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    /// Ids of our toasts still in the action center
+    pub fn delivered_ids(identifier: &str) -> Result<HashSet<i32>, String> {
+        let history = ToastNotificationManager::History()
+            .and_then(|history| history.GetHistoryWithId(&app_id(identifier)))
+            .map_err(|e| e.to_string())?;
+        Ok(history
+            .into_iter()
+            .filter(|toast| toast.Group().is_ok_and(|group| group == GROUP))
+            .filter_map(|toast| toast.Tag().ok()?.to_string().parse().ok())
+            .collect())
+    }
+
+    pub fn clear_history(identifier: &str) -> Result<(), String> {
+        ToastNotificationManager::History()
+            .and_then(|history| history.ClearWithId(&app_id(identifier)))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::sync::{LazyLock, Mutex};
+    use tauri::{AppHandle, Runtime};
+    use zbus::blocking::{Connection, MessageIterator};
+    use zbus::zvariant::Value;
+
+    use super::Interaction;
+
+    const DESTINATION: &str = "org.freedesktop.Notifications";
+    const PATH: &str = "/org/freedesktop/Notifications";
+    const INTERFACE: &str = "org.freedesktop.Notifications";
+
+    /// `NotificationClosed` reason for a notification the user dismissed
+    const DISMISSED_BY_USER: u32 = 2;
+
+    /// Session bus connection notifications are sent and watched on; some
+    /// servers send their signals only to the connection that asked
+    static CONNECTION: Mutex<Option<Connection>> = Mutex::new(None);
+
+    /// Our ids by the server's, for notifications that haven't closed
+    static SHOWN: LazyLock<Mutex<HashMap<u32, i32>>> = LazyLock::new(Default::default);
+
+    /// The connection, opened with its signal listener the first time
+    fn connection<R: Runtime>(app: &AppHandle<R>) -> Result<Connection, String> {
+        let mut connection = CONNECTION.lock().unwrap();
+        if let Some(connection) = &*connection {
+            return Ok(connection.clone());
+        }
+        let opened = Connection::session().map_err(|e| e.to_string())?;
+        listen(app, &opened)?;
+        Ok(connection.insert(opened).clone())
+    }
+
+    /// Report clicks and dismissals of every notification from one thread
+    fn listen<R: Runtime>(app: &AppHandle<R>, connection: &Connection) -> Result<(), String> {
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface(INTERFACE)
+            .and_then(|rule| rule.path(PATH))
+            .map_err(|e| e.to_string())?
+            .build();
+        let messages = MessageIterator::for_match_rule(rule, connection, None)
+            .map_err(|e| e.to_string())?;
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for message in messages.flatten() {
+                let header = message.header();
+                let Some(member) = header.member() else {
+                    continue;
+                };
+                match member.as_str() {
+                    "ActionInvoked" => {
+                        let Ok((server_id, action)) = message.body().deserialize::<(u32, String)>()
+                        else {
+                            continue;
+                        };
+                        // Removed so the close that follows isn't a dismissal
+                        let id = SHOWN.lock().unwrap().remove(&server_id);
+                        if let (Some(id), "default") = (id, action.as_str()) {
+                            super::on_interaction(&app, id, Interaction::Activated);
+                        }
+                    }
+                    "NotificationClosed" => {
+                        let Ok((server_id, reason)) = message.body().deserialize::<(u32, u32)>()
+                        else {
+                            continue;
+                        };
+                        let id = SHOWN.lock().unwrap().remove(&server_id);
+                        if let Some(id) = id.filter(|_| reason == DISMISSED_BY_USER) {
+                            super::on_interaction(&app, id, Interaction::Dismissed);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+        Ok(())
+    }
+
+    pub fn show<R: Runtime>(
+        app: &AppHandle<R>,
+        id: i32,
+        title: &str,
+        body: &str,
+        icon: Option<&str>,
+    ) -> Result<(), String> {
+        let hints: HashMap<&str, Value> = HashMap::new();
+        let reply = connection(app)?
+            .call_method(
+                Some(DESTINATION),
+                PATH,
+                Some(INTERFACE),
+                "Notify",
+                &(
+                    super::app_name(app),
+                    0u32,
+                    icon.unwrap_or_default(),
+                    title,
+                    body,
+                    // Servers without action support never report it
+                    vec!["default", "Open"],
+                    hints,
+                    -1i32,
+                ),
+            )
+            .map_err(|e| e.to_string())?;
+        let server_id: u32 = reply.body().deserialize().map_err(|e| e.to_string())?;
+
+        let mut shown = SHOWN.lock().unwrap();
+        // Servers that keep notifications never close them
+        if shown.len() >= super::MAX_TRACKED {
+            if let Some(oldest) = shown.keys().min().copied() {
+                shown.remove(&oldest);
+            }
+        }
+        shown.insert(server_id, id);
+        Ok(())
+    }
+
+    fn close(server_id: u32) {
+        let Some(connection) = CONNECTION.lock().unwrap().clone() else {
+            return;
+        };
+        let result = connection.call_method(
+            Some(DESTINATION),
+            PATH,
+            Some(INTERFACE),
+            "CloseNotification",
+            &(server_id,),
+        );
+        if let Err(e) = result {
+            log::debug!("Failed to close notification: {}", e);
+        }
+    }
+
+    /// Close a notification we showed; closing isn't reported as a dismissal
+    pub fn remove(id: i32) {
+        let mut shown = SHOWN.lock().unwrap();
+        let Some(server_id) = shown
+            .iter()
+            .find_map(|(server_id, shown)| (*shown == id).then_some(*server_id))
+        else {
+            return;
+        };
+        shown.remove(&server_id);
+        drop(shown);
+        close(server_id);
+    }
+
+    pub fn remove_all() {
+        let server_ids: Vec<u32> = SHOWN.lock().unwrap().drain().map(|(key, _)| key).collect();
+        for server_id in server_ids {
+            close(server_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_limit_allows_everything() {
+        let mut limiter = RateLimiter::default();
+        assert!((0..1000).all(|_| limiter.allow(None)));
+        assert_eq!(limiter.suppressed, 0);
+        assert!(limiter.sent.is_empty());
+    }
+
+    #[test]
+    fn drops_past_the_limit_and_counts_them() {
+        let mut limiter = RateLimiter::default();
+        assert!(limiter.allow(Some(2)));
+        assert!(limiter.allow(Some(2)));
+        assert!(!limiter.allow(Some(2)));
+        assert!(!limiter.allow(Some(2)));
+        assert_eq!(limiter.suppressed, 2);
+
+        let retry_after = limiter.retry_after();
+        assert!(retry_after > Duration::ZERO && retry_after <= RATE_WINDOW);
+    }
+
+    #[test]
+    fn frees_slots_once_they_leave_the_window() {
+        let mut limiter = RateLimiter::default();
+        let expired = Instant::now().checked_sub(RATE_WINDOW).unwrap();
+        limiter.sent.push_back(expired);
+        assert_eq!(limiter.retry_after(), Duration::ZERO);
+
+        assert!(limiter.allow(Some(1)));
+        assert_eq!(limiter.sent.len(), 1);
+        assert!(!limiter.allow(Some(1)));
+    }
+}
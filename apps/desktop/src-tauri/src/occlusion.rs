@@ -0,0 +1,123 @@
+//! Window occlusion tracking for YULA Desktop
+//!
+//! A window can be visible yet fully covered by other windows. macOS reports
+//! this through `NSWindow.occlusionState`; on Windows a cloaked window (e.g.
+//! on another virtual desktop) is treated as occluded. Elsewhere only
+//! minimized or hidden windows count. A background monitor emits
+//! `window-occlusion-changed` so the frontend can pause rendering.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+/// How often window occlusion is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Payload of the `window-occlusion-changed` event
+#[derive(Debug, Clone, Serialize)]
+struct OcclusionPayload {
+    label: String,
+    occluded: bool,
+}
+
+/// Check whether a window is out of sight
+fn detect<R: Runtime>(window: &WebviewWindow<R>) -> bool {
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true) {
+        return true;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        /// `NSWindowOcclusionStateVisible`
+        const OCCLUSION_STATE_VISIBLE: usize = 1 << 1;
+
+        // AppKit must be queried on the main thread
+        let Ok(ns_window) = window.ns_window() else {
+            return false;
+        };
+        let ns_window = ns_window as usize;
+        let (tx, rx) = std::sync::mpsc::channel();
+        let queued = window.run_on_main_thread(move || unsafe {
+            let ns_window = &*(ns_window as *const AnyObject);
+            let state: usize = objc2::msg_send![ns_window, occlusionState];
+            let _ = tx.send(state & OCCLUSION_STATE_VISIBLE == 0);
+        });
+        if queued.is_err() {
+            return false;
+        }
+        rx.recv_timeout(Duration::from_secs(1)).unwrap_or(false)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+
+        let Ok(hwnd) = window.hwnd() else {
+            return false;
+        };
+        let mut cloaked: u32 = 0;
+        let result = unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_CLOAKED,
+                &mut cloaked as *mut u32 as *mut _,
+                std::mem::size_of::<u32>() as u32,
+            )
+        };
+        result.is_ok() && cloaked != 0
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    false
+}
+
+/// Start the background monitor that emits `window-occlusion-changed`
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            crate::tasks::tick(&app, "occlusion", POLL_INTERVAL).await;
+
+            let handle = app.clone();
+            let states = tauri::async_runtime::spawn_blocking(move || {
+                handle
+                    .webview_windows()
+                    .into_iter()
+                    .map(|(label, window)| (label, detect(&window)))
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default();
+
+            let state = app.state::<crate::AppState>();
+            let changed: Vec<OcclusionPayload> = {
+                let mut known = state.occluded.lock().unwrap();
+                known.retain(|label, _| states.iter().any(|(l, _)| l == label));
+                states
+                    .into_iter()
+                    .filter(|(label, now)| known.insert(label.clone(), *now) != Some(*now))
+                    .map(|(label, occluded)| OcclusionPayload { label, occluded })
+                    .collect()
+            };
+
+            for payload in changed {
+                log::debug!("Window {} occluded: {}", payload.label, payload.occluded);
+                crate::events::emit(&app, crate::events::WINDOW_OCCLUSION_CHANGED, payload);
+            }
+        }
+    });
+}
+
+/// Check whether a window is fully covered, minimized or hidden
+#[tauri::command]
+pub async fn is_window_occluded<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<bool, String> {
+    let window = crate::window::get_window(&app, &label)?;
+    tauri::async_runtime::spawn_blocking(move || detect(&window))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,340 @@
+//! Encrypted offline cache for YULA Desktop
+//!
+//! A size-bounded store for sensitive data the frontend needs offline, such
+//! as recent chats, so it doesn't have to do crypto in JS. Each entry is a
+//! file in the profile's cache directory, named by a hash of its key and
+//! sealed with XChaCha20-Poly1305. The encryption key is derived from a
+//! random secret kept in the OS credential store, and the entry's key is
+//! authenticated along with the value so files can't be swapped between
+//! keys. Once the entries outgrow `MAX_CACHE_BYTES` the least recently used
+//! are evicted, going by file modification times, which reads refresh.
+//!
+//! If the secret goes missing the existing entries can't be read any more,
+//! so they're deleted and a new secret is generated. Entries that fail to
+//! decrypt are dropped and read as missing.
+
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::CommandError;
+
+/// Directory in the profile's cache directory holding the entries
+const CACHE_DIR: &str = "offline";
+
+/// Credential store entry holding the secret the encryption key comes from
+const SECRET_ENTRY: &str = "offline-cache-secret";
+
+/// Context string for deriving the encryption key from the secret
+const KEY_CONTEXT: &str = "YULA Desktop offline cache 2026-10-14 entry encryption";
+
+/// Total size of the sealed entries before the least recently used go
+const MAX_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+const NONCE_LEN: usize = 24;
+
+/// Poly1305 tag appended to every ciphertext
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    /// Size of the sealed file
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// Encryption key and the entries on disk, loaded on first use
+#[derive(Default)]
+pub struct OfflineCache {
+    key: Option<[u8; 32]>,
+    /// Entries by file name
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl std::fmt::Debug for OfflineCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OfflineCache")
+            .field("unlocked", &self.key.is_some())
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+impl OfflineCache {
+    /// Get the encryption key and the cache directory, loading both the
+    /// first time
+    fn open<R: Runtime>(&mut self, app: &AppHandle<R>) -> Result<([u8; 32], PathBuf), String> {
+        let dir = crate::profiles::cache_dir(app)?.join(CACHE_DIR);
+        if let Some(key) = self.key {
+            return Ok((key, dir));
+        }
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+        let secret = match crate::keychain::get(app, SECRET_ENTRY)? {
+            Some(secret) => secret,
+            None => {
+                // Entries sealed under a lost secret can't be read any more
+                clear_dir(&dir)?;
+                let secret: [u8; 32] = rand::random();
+                let secret = base64::engine::general_purpose::STANDARD.encode(secret);
+                crate::keychain::set(app, SECRET_ENTRY, &secret)?;
+                log::info!("Generated a new offline cache secret");
+                secret
+            }
+        };
+        let key = blake3::derive_key(KEY_CONTEXT, secret.as_bytes());
+
+        self.entries = scan(&dir);
+        self.key = Some(key);
+        Ok((key, dir))
+    }
+
+    fn total_size(&self) -> u64 {
+        self.entries.values().map(|entry| entry.size).sum()
+    }
+
+    /// Delete the least recently used entries until at most `budget` bytes
+    /// are left
+    fn evict(&mut self, dir: &Path, budget: u64) {
+        while self.total_size() > budget {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(name, _)| name.clone())
+            else {
+                return;
+            };
+            self.entries.remove(&oldest);
+            if let Err(e) = std::fs::remove_file(dir.join(&oldest)) {
+                log::warn!("Failed to evict offline cache entry: {}", e);
+            }
+        }
+    }
+}
+
+/// File name of an entry; hashed so keys don't show on disk
+fn file_name(key: &str) -> String {
+    blake3::hash(key.as_bytes()).to_hex().to_string()
+}
+
+/// Read the sizes and last use of the entries on disk
+fn scan(dir: &Path) -> HashMap<String, CacheEntry> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    read_dir
+        .flatten()
+        .filter_map(|file| {
+            let metadata = file.metadata().ok().filter(|metadata| metadata.is_file())?;
+            // Left behind by a write that was cut off
+            if file.path().extension().is_some_and(|ext| ext == "tmp") {
+                let _ = std::fs::remove_file(file.path());
+                return None;
+            }
+            let entry = CacheEntry {
+                size: metadata.len(),
+                last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            };
+            Some((file.file_name().to_string_lossy().to_string(), entry))
+        })
+        .collect()
+}
+
+/// Delete every entry, keeping the directory
+fn clear_dir(dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())
+}
+
+/// Encrypt a value, returning the nonce followed by the ciphertext
+fn seal(key: &[u8; 32], cache_key: &str, value: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let payload = Payload {
+        msg: value,
+        aad: cache_key.as_bytes(),
+    };
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(XNonce::from_slice(&nonce), payload)
+        .map_err(|_| "Failed to encrypt the cache entry".to_string())?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt a sealed value, `None` if it was tampered with or sealed under
+/// another key
+fn unseal(key: &[u8; 32], cache_key: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN + TAG_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let payload = Payload {
+        msg: ciphertext,
+        aad: cache_key.as_bytes(),
+    };
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(XNonce::from_slice(nonce), payload)
+        .ok()
+}
+
+/// Store a value under `key`, replacing any previous one
+///
+/// Evicts the least recently used entries to stay within the size cap.
+#[tauri::command]
+pub async fn cache_put<R: Runtime>(
+    app: AppHandle<R>,
+    key: String,
+    value: Vec<u8>,
+) -> Result<(), CommandError> {
+    let sealed_size = (value.len() + NONCE_LEN + TAG_LEN) as u64;
+    if sealed_size > MAX_CACHE_BYTES {
+        return Err(CommandError::Failed(format!(
+            "Values over {} MB don't fit in the offline cache",
+            MAX_CACHE_BYTES / 1024 / 1024
+        )));
+    }
+
+    let state = app.state::<crate::AppState>();
+    let mut cache = state.offline_cache.lock().unwrap();
+    let (cipher_key, dir) = cache.open(&app)?;
+    let sealed = seal(&cipher_key, &key, &value)?;
+
+    // The entry being replaced doesn't count against the cap
+    let name = file_name(&key);
+    cache.entries.remove(&name);
+    cache.evict(&dir, MAX_CACHE_BYTES - sealed_size);
+
+    crate::settings::write_atomic(&dir.join(&name), &sealed)?;
+    cache.entries.insert(
+        name,
+        CacheEntry {
+            size: sealed_size,
+            last_used: SystemTime::now(),
+        },
+    );
+    Ok(())
+}
+
+/// Read the value stored under `key`, `None` if there is none
+#[tauri::command]
+pub async fn cache_get<R: Runtime>(
+    app: AppHandle<R>,
+    key: String,
+) -> Result<Option<Vec<u8>>, CommandError> {
+    let state = app.state::<crate::AppState>();
+    let mut cache = state.offline_cache.lock().unwrap();
+    let (cipher_key, dir) = cache.open(&app)?;
+    let name = file_name(&key);
+    if !cache.entries.contains_key(&name) {
+        return Ok(None);
+    }
+
+    let path = dir.join(&name);
+    let sealed = match std::fs::read(&path) {
+        Ok(sealed) => sealed,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            cache.entries.remove(&name);
+            return Ok(None);
+        }
+        Err(e) => return Err(CommandError::Failed(e.to_string())),
+    };
+    let Some(value) = unseal(&cipher_key, &key, &sealed) else {
+        log::warn!("Dropping an offline cache entry that doesn't decrypt");
+        cache.entries.remove(&name);
+        let _ = std::fs::remove_file(&path);
+        return Ok(None);
+    };
+
+    // The modification time records use across launches
+    let now = SystemTime::now();
+    if let Some(entry) = cache.entries.get_mut(&name) {
+        entry.last_used = now;
+    }
+    let touched = std::fs::File::options()
+        .write(true)
+        .open(&path)
+        .and_then(|file| file.set_modified(now));
+    if let Err(e) = touched {
+        log::debug!("Failed to mark offline cache entry used: {}", e);
+    }
+    Ok(Some(value))
+}
+
+/// Remove the value stored under `key`, if any
+#[tauri::command]
+pub async fn cache_delete<R: Runtime>(app: AppHandle<R>, key: String) -> Result<(), CommandError> {
+    let state = app.state::<crate::AppState>();
+    let mut cache = state.offline_cache.lock().unwrap();
+    let (_, dir) = cache.open(&app)?;
+    let name = file_name(&key);
+    cache.entries.remove(&name);
+    match std::fs::remove_file(dir.join(&name)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(CommandError::Failed(e.to_string())),
+    }
+}
+
+/// Remove every value from the offline cache
+#[tauri::command]
+pub async fn cache_clear<R: Runtime>(app: AppHandle<R>) -> Result<(), CommandError> {
+    let state = app.state::<crate::AppState>();
+    let mut cache = state.offline_cache.lock().unwrap();
+    let (_, dir) = cache.open(&app)?;
+    clear_dir(&dir)?;
+    cache.entries.clear();
+    log::info!("Offline cache cleared");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn round_trips_a_value() {
+        let sealed = seal(&KEY, "chats/recent", b"hello").unwrap();
+        assert_eq!(sealed.len(), NONCE_LEN + b"hello".len() + TAG_LEN);
+        assert_eq!(
+            unseal(&KEY, "chats/recent", &sealed).as_deref(),
+            Some(&b"hello"[..])
+        );
+    }
+
+    #[test]
+    fn uses_a_fresh_nonce_every_time() {
+        let first = seal(&KEY, "chats/recent", b"hello").unwrap();
+        let second = seal(&KEY, "chats/recent", b"hello").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn rejects_an_entry_sealed_under_another_cache_key() {
+        let sealed = seal(&KEY, "chats/recent", b"hello").unwrap();
+        assert_eq!(unseal(&KEY, "chats/archived", &sealed), None);
+    }
+
+    #[test]
+    fn rejects_another_key_and_tampering() {
+        let mut sealed = seal(&KEY, "chats/recent", b"hello").unwrap();
+        assert_eq!(unseal(&[8; 32], "chats/recent", &sealed), None);
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert_eq!(unseal(&KEY, "chats/recent", &sealed), None);
+        assert_eq!(
+            unseal(&KEY, "chats/recent", &sealed[..NONCE_LEN + TAG_LEN - 1]),
+            None
+        );
+    }
+}
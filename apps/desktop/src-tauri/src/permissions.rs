@@ -0,0 +1,263 @@
+//! OS permission status for YULA Desktop
+//!
+//! Aggregates the per-platform permission checks into one structure for the
+//! onboarding checklist. macOS has real TCC permissions for all of them;
+//! Windows only gates the microphone and camera (plus toast notifications);
+//! Linux has no equivalent, so only notifications apply there.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::CommandError;
+
+/// State of a single permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    Undetermined,
+    NotApplicable,
+}
+
+/// A permission the app may need
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Notifications,
+    Microphone,
+    ScreenRecording,
+    Accessibility,
+    Camera,
+}
+
+/// State of every permission the app may need
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionsStatus {
+    pub notifications: PermissionState,
+    pub microphone: PermissionState,
+    pub screen_recording: PermissionState,
+    pub accessibility: PermissionState,
+    pub camera: PermissionState,
+}
+
+/// Whether the app may control the computer through accessibility APIs
+#[cfg(target_os = "macos")]
+pub fn accessibility_granted() -> bool {
+    permission_state(Permission::Accessibility) == PermissionState::Granted
+}
+
+/// Whether the app may capture the screen, including its own windows
+#[cfg(target_os = "macos")]
+pub fn screen_recording_granted() -> bool {
+    permission_state(Permission::ScreenRecording) == PermissionState::Granted
+}
+
+/// Whether the user refused microphone access; an undetermined permission is
+/// asked for when the device is first opened
+pub fn microphone_denied() -> bool {
+    permission_state(Permission::Microphone) == PermissionState::Denied
+}
+
+/// Query the notification permission through the plugin
+pub fn notification_state<R: Runtime>(app: &AppHandle<R>) -> PermissionState {
+    use tauri_plugin_notification::NotificationExt;
+
+    if !crate::plugins::is_available(app, crate::plugins::NOTIFICATION) {
+        return PermissionState::NotApplicable;
+    }
+
+    match app.notification().permission_state() {
+        Ok(tauri_plugin_notification::PermissionState::Granted) => PermissionState::Granted,
+        Ok(tauri_plugin_notification::PermissionState::Denied) => PermissionState::Denied,
+        Ok(_) => PermissionState::Undetermined,
+        Err(e) => {
+            log::debug!("Notification permission unavailable: {}", e);
+            PermissionState::Undetermined
+        }
+    }
+}
+
+/// Query a permission other than notifications
+fn permission_state(permission: Permission) -> PermissionState {
+    #[cfg(target_os = "macos")]
+    {
+        match permission {
+            Permission::Microphone => macos::capture_device_state("soun"),
+            Permission::Camera => macos::capture_device_state("vide"),
+            Permission::ScreenRecording => macos::screen_recording_state(),
+            Permission::Accessibility => macos::accessibility_state(),
+            Permission::Notifications => PermissionState::NotApplicable,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        match permission {
+            Permission::Microphone => win::consent_state("microphone"),
+            Permission::Camera => win::consent_state("webcam"),
+            _ => PermissionState::NotApplicable,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = permission;
+        PermissionState::NotApplicable
+    }
+}
+
+/// Get the state of every permission the app may need
+#[tauri::command]
+pub async fn get_permissions_status<R: Runtime>(app: AppHandle<R>) -> PermissionsStatus {
+    PermissionsStatus {
+        notifications: notification_state(&app),
+        microphone: permission_state(Permission::Microphone),
+        screen_recording: permission_state(Permission::ScreenRecording),
+        accessibility: permission_state(Permission::Accessibility),
+        camera: permission_state(Permission::Camera),
+    }
+}
+
+/// Ask for permission to show notifications, prompting if the OS hasn't
+/// asked yet, and get the resulting state
+///
+/// Otherwise the prompt comes with the first notification shown.
+#[tauri::command]
+pub async fn request_notification_permission<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<PermissionState, CommandError> {
+    Ok(crate::notifications::request_permission(&app).await?)
+}
+
+/// Open the OS settings page where a permission can be granted
+#[tauri::command]
+pub async fn open_system_settings(permission: Permission) -> Result<(), CommandError> {
+    #[cfg(target_os = "macos")]
+    let (opener, uri) = (
+        "open",
+        match permission {
+            Permission::Notifications => {
+                "x-apple.systempreferences:com.apple.preference.notifications"
+            }
+            Permission::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+            Permission::ScreenRecording => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+            Permission::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+            Permission::Camera => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Camera"
+            }
+        },
+    );
+
+    #[cfg(target_os = "windows")]
+    let (opener, uri) = (
+        "explorer",
+        match permission {
+            Permission::Notifications => "ms-settings:notifications",
+            Permission::Microphone => "ms-settings:privacy-microphone",
+            Permission::Camera => "ms-settings:privacy-webcam",
+            Permission::ScreenRecording | Permission::Accessibility => {
+                return Err(CommandError::NotSupported(
+                    "Windows has no setting for this permission".to_string(),
+                ))
+            }
+        },
+    );
+
+    #[cfg(target_os = "linux")]
+    let (opener, uri) = match permission {
+        Permission::Notifications => ("gnome-control-center", "notifications"),
+        _ => {
+            return Err(CommandError::NotSupported(
+                "Linux has no setting for this permission".to_string(),
+            ))
+        }
+    };
+
+    crate::platform::command(opener)
+        .arg(uri)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSString;
+
+    use super::PermissionState;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+    }
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {}
+
+    pub fn accessibility_state() -> PermissionState {
+        // Accessibility can't be "undetermined"; it's off until the user
+        // enables it in System Settings
+        if unsafe { AXIsProcessTrusted() } {
+            PermissionState::Granted
+        } else {
+            PermissionState::Denied
+        }
+    }
+
+    pub fn screen_recording_state() -> PermissionState {
+        if unsafe { CGPreflightScreenCaptureAccess() } {
+            PermissionState::Granted
+        } else {
+            PermissionState::Denied
+        }
+    }
+
+    /// `AVCaptureDevice` authorization for a media type (`soun`, `vide`)
+    pub fn capture_device_state(media_type: &str) -> PermissionState {
+        let media_type = NSString::from_str(media_type);
+        let status: isize = unsafe {
+            msg_send![
+                class!(AVCaptureDevice),
+                authorizationStatusForMediaType: &*media_type
+            ]
+        };
+
+        // AVAuthorizationStatus: notDetermined, restricted, denied, authorized
+        match status {
+            0 => PermissionState::Undetermined,
+            3 => PermissionState::Granted,
+            _ => PermissionState::Denied,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    use super::PermissionState;
+
+    const CONSENT_STORE: &str =
+        r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore";
+
+    /// Consent stored by Settings > Privacy for a capability
+    pub fn consent_state(capability: &str) -> PermissionState {
+        let key = format!(r"{}\{}", CONSENT_STORE, capability);
+        match crate::platform::output("reg", &["query", &key, "/v", "Value"]) {
+            Some(output) if output.contains("Allow") => PermissionState::Granted,
+            Some(output) if output.contains("Deny") => PermissionState::Denied,
+            _ => PermissionState::Undetermined,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Platform helpers for YULA Desktop
+//!
+//! Some OS state is only reachable through system tools (`defaults`, `ps`,
+//! `reg`, `gsettings`, ...). These helpers run them without flashing a
+//! console window on Windows.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+
+/// Windows process creation flag that suppresses the console window
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Build a command for a system tool
+pub fn command(program: impl AsRef<OsStr>) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(program);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+/// Windows PowerShell by absolute path, so a `powershell.exe` earlier on
+/// `PATH` can't stand in for it
+#[cfg(target_os = "windows")]
+pub fn powershell() -> std::path::PathBuf {
+    let root = std::env::var_os("SystemRoot").unwrap_or_else(|| r"C:\Windows".into());
+    std::path::PathBuf::from(root).join(r"System32\WindowsPowerShell\v1.0\powershell.exe")
+}
+
+/// Run a system tool and return its trimmed stdout if it succeeded
+pub fn output(program: impl AsRef<OsStr>, args: &[&str]) -> Option<String> {
+    let output = command(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The program a command line runs, e.g. from a registry value or a
+/// desktop entry's `Exec` line
+pub fn program_of(command_line: &str) -> Option<&str> {
+    let command_line = command_line.trim();
+    match command_line.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => command_line.split_whitespace().next(),
+    }
+}
+
+/// Whether two paths name the same file, ignoring case on Windows
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    let (a, b) = (
+        a.canonicalize().unwrap_or_else(|_| a.to_path_buf()),
+        b.canonicalize().unwrap_or_else(|_| b.to_path_buf()),
+    );
+    if cfg!(target_os = "windows") {
+        a.to_string_lossy().eq_ignore_ascii_case(&b.to_string_lossy())
+    } else {
+        a == b
+    }
+}
@@ -0,0 +1,121 @@
+//! Plugin initialization for YULA Desktop
+//!
+//! Plugins are registered from `setup` rather than on the builder so that a
+//! plugin failing to initialize (e.g. autostart on a locked-down machine)
+//! doesn't abort launch. Each outcome is recorded in `AppState` so commands
+//! can fail cleanly and the frontend can hide unavailable features.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{plugin::Plugin, AppHandle, Manager, Runtime};
+
+pub const NOTIFICATION: &str = "notification";
+pub const DEEP_LINK: &str = "deep-link";
+pub const SHELL: &str = "shell";
+pub const FS: &str = "fs";
+pub const DIALOG: &str = "dialog";
+pub const CLIPBOARD: &str = "clipboard-manager";
+pub const AUTOSTART: &str = "autostart";
+pub const UPDATER: &str = "updater";
+pub const PROCESS: &str = "process";
+pub const OS: &str = "os";
+pub const GLOBAL_SHORTCUT: &str = "global-shortcut";
+pub const SINGLE_INSTANCE: &str = "single-instance";
+
+/// Initialization outcome of a single plugin
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginStatus {
+    pub available: bool,
+    pub error: Option<String>,
+}
+
+/// Register all plugins, recording which ones failed
+pub fn init_plugins<R: Runtime>(app: &AppHandle<R>) {
+    // Registered first so a second instance exits before doing any work
+    init(
+        app,
+        SINGLE_INSTANCE,
+        tauri_plugin_single_instance::init(crate::deep_link::on_second_instance),
+    );
+    init(app, NOTIFICATION, tauri_plugin_notification::init());
+    init(app, DEEP_LINK, tauri_plugin_deep_link::init());
+    init(app, SHELL, tauri_plugin_shell::init());
+    init(app, FS, tauri_plugin_fs::init());
+    init(app, DIALOG, tauri_plugin_dialog::init());
+    init(app, CLIPBOARD, tauri_plugin_clipboard_manager::init());
+    init(
+        app,
+        AUTOSTART,
+        tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![crate::launch::AUTOSTART_ARG]),
+        ),
+    );
+    init(app, UPDATER, tauri_plugin_updater::Builder::new().build());
+    init(app, PROCESS, tauri_plugin_process::init());
+    init(app, OS, tauri_plugin_os::init());
+    init(
+        app,
+        GLOBAL_SHORTCUT,
+        tauri_plugin_global_shortcut::Builder::new()
+            .with_handler(crate::shortcuts::handle_shortcut)
+            .build(),
+    );
+}
+
+fn init<R: Runtime, P: Plugin<R> + 'static>(app: &AppHandle<R>, name: &str, plugin: P) {
+    let status = match app.plugin(plugin) {
+        Ok(()) => PluginStatus {
+            available: true,
+            error: None,
+        },
+        Err(e) => {
+            log::error!("Plugin {} failed to initialize: {}", name, e);
+            PluginStatus {
+                available: false,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        state
+            .plugin_status
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), status);
+    }
+}
+
+/// Check whether a plugin initialized successfully
+pub fn is_available<R: Runtime>(app: &AppHandle<R>, name: &str) -> bool {
+    app.try_state::<crate::AppState>()
+        .and_then(|state| {
+            state
+                .plugin_status
+                .lock()
+                .unwrap()
+                .get(name)
+                .map(|status| status.available)
+        })
+        .unwrap_or(false)
+}
+
+/// Fail with a clear error if a plugin is unavailable
+pub fn require<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<(), String> {
+    if is_available(app, name) {
+        Ok(())
+    } else {
+        Err(format!("The {} plugin is unavailable on this system", name))
+    }
+}
+
+/// Get the initialization status of every plugin
+#[tauri::command]
+pub fn get_plugin_status<R: Runtime>(app: AppHandle<R>) -> HashMap<String, PluginStatus> {
+    app.state::<crate::AppState>()
+        .plugin_status
+        .lock()
+        .unwrap()
+        .clone()
+}
@@ -0,0 +1,149 @@
+//! Low-power mode detection for YULA Desktop
+//!
+//! Follows macOS Low Power Mode, Windows battery saver and the Linux
+//! `power-saver` power profile. The OS setting is polled and
+//! `low-power-mode-changed` is emitted when it flips. While it is on, the
+//! app is throttled as if the main window were hidden, whether or not
+//! background throttling is enabled. Where the mode can't be queried it is
+//! reported as off.
+//!
+//! The monitor also tracks whether the machine runs on battery, for work
+//! that should slow down away from AC power without a full throttle.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// How often the OS setting is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Read the OS low-power setting, `None` if it can't be queried
+fn system_low_power() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::{AnyObject, Sel};
+
+        // `isLowPowerModeEnabled` exists since macOS 12
+        unsafe {
+            let info: *mut AnyObject =
+                objc2::msg_send![objc2::class!(NSProcessInfo), processInfo];
+            let info = info.as_ref()?;
+            let selector: Sel = objc2::sel!(isLowPowerModeEnabled);
+            let supported: bool = objc2::msg_send![info, respondsToSelector: selector];
+            if !supported {
+                return None;
+            }
+            let enabled: bool = objc2::msg_send![info, isLowPowerModeEnabled];
+            Some(enabled)
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+        Some(status.SystemStatusFlag == 1)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let profile = crate::platform::output("powerprofilesctl", &["get"])?;
+        Some(profile == "power-saver")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    None
+}
+
+/// Whether the machine draws from a battery, `None` if it can't be queried
+fn system_on_battery() -> Option<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        // e.g. `Now drawing from 'Battery Power'`
+        let status = crate::platform::output("pmset", &["-g", "batt"])?;
+        Some(status.lines().next()?.contains("'Battery Power'"))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.ok()?;
+        // 255 means the line status is unknown
+        match status.ACLineStatus {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // On battery when there's a mains supply and none of them is online
+        let mut mains = std::fs::read_dir("/sys/class/power_supply")
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                std::fs::read_to_string(path.join("type")).is_ok_and(|kind| kind.trim() == "Mains")
+            })
+            .peekable();
+        mains.peek()?;
+        Some(!mains.any(|path| {
+            std::fs::read_to_string(path.join("online")).is_ok_and(|online| online.trim() == "1")
+        }))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    None
+}
+
+/// Whether the OS is in low-power mode, as last seen by the monitor
+pub fn is_low_power<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<crate::AppState>()
+        .map(|state| state.low_power.load(Ordering::SeqCst))
+        .unwrap_or(false)
+}
+
+/// Whether the machine runs on battery, as last seen by the monitor
+pub fn is_on_battery<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<crate::AppState>()
+        .map(|state| state.on_battery.load(Ordering::SeqCst))
+        .unwrap_or(false)
+}
+
+fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let low_power = system_low_power().unwrap_or(false);
+    let state = app.state::<crate::AppState>();
+    state
+        .on_battery
+        .store(system_on_battery().unwrap_or(false), Ordering::SeqCst);
+    if state.low_power.swap(low_power, Ordering::SeqCst) != low_power {
+        log::info!("Low power mode changed: {}", low_power);
+        crate::events::emit(app, crate::events::LOW_POWER_MODE_CHANGED, low_power);
+        crate::throttle::refresh(app);
+    }
+}
+
+/// Start the background monitor that emits `low-power-mode-changed`
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    refresh(app);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            crate::tasks::tick(&app, "low-power", POLL_INTERVAL).await;
+            let handle = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || refresh(&handle)).await;
+        }
+    });
+}
+
+/// Check whether the OS is in low-power or battery-saver mode (false if unknown)
+#[tauri::command]
+pub fn is_low_power_mode<R: Runtime>(app: AppHandle<R>) -> bool {
+    is_low_power(&app)
+}
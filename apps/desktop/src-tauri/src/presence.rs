@@ -0,0 +1,113 @@
+//! Presence heartbeat for YULA Desktop
+//!
+//! Keeps the user's online status fresh for other users by sending a small
+//! authenticated `POST` to the presence endpoint on an interval, from the
+//! backend so it keeps going while the window is hidden in the tray. Each
+//! ping emits `presence-sent` or `presence-failed`. Pings are skipped while
+//! the machine is offline and pause with the other background tasks; the
+//! interval is not stretched by throttling, since the server expires
+//! presence that isn't renewed in time. The token is only held in memory.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, Url};
+
+/// Task name in the background task list
+const TASK_NAME: &str = "presence";
+
+/// Allowed heartbeat intervals, in seconds
+const INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 5..=3600;
+
+/// Timeout for each ping
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The running heartbeat
+#[derive(Debug)]
+pub struct PresenceHeartbeat {
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+async fn ping(client: &reqwest::Client, endpoint: &Url, token: &str) -> Result<(), String> {
+    let response = client
+        .post(endpoint.as_str())
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Presence endpoint returned {}", response.status()))
+    }
+}
+
+/// Stop the heartbeat if one is running
+pub fn stop<R: Runtime>(app: &AppHandle<R>) {
+    let heartbeat = app
+        .try_state::<crate::AppState>()
+        .and_then(|state| state.presence.lock().unwrap().take());
+    if let Some(heartbeat) = heartbeat {
+        heartbeat.task.abort();
+        crate::tasks::forget(app, TASK_NAME);
+        log::info!("Presence heartbeat stopped");
+    }
+}
+
+/// Ping `endpoint` every `interval_secs` with `token` as the bearer token
+///
+/// Replaces a running heartbeat. The endpoint must use HTTPS.
+#[tauri::command]
+pub async fn start_presence_heartbeat<R: Runtime>(
+    app: AppHandle<R>,
+    interval_secs: u64,
+    endpoint: String,
+    token: String,
+) -> Result<(), String> {
+    if !INTERVAL_RANGE.contains(&interval_secs) {
+        return Err(format!(
+            "Interval must be between {} and {} seconds",
+            INTERVAL_RANGE.start(),
+            INTERVAL_RANGE.end()
+        ));
+    }
+    let endpoint = Url::parse(&endpoint).map_err(|e| e.to_string())?;
+    if endpoint.scheme() != "https" {
+        return Err("Presence endpoint must use HTTPS".to_string());
+    }
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    stop(&app);
+    log::info!("Presence heartbeat every {}s to {}", interval_secs, endpoint);
+
+    let interval = Duration::from_secs(interval_secs);
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            if crate::network::is_online().await {
+                match ping(&client, &endpoint, &token).await {
+                    Ok(()) => crate::events::emit(
+                        &handle,
+                        crate::events::PRESENCE_SENT,
+                        chrono::Utc::now().timestamp_millis(),
+                    ),
+                    Err(e) => {
+                        log::debug!("Presence ping failed: {}", e);
+                        crate::events::emit(&handle, crate::events::PRESENCE_FAILED, e);
+                    }
+                }
+            }
+            crate::tasks::tick_unthrottled(&handle, TASK_NAME, interval).await;
+        }
+    });
+
+    *app.state::<crate::AppState>().presence.lock().unwrap() = Some(PresenceHeartbeat { task });
+    Ok(())
+}
+
+/// Stop the presence heartbeat
+#[tauri::command]
+pub fn stop_presence_heartbeat<R: Runtime>(app: AppHandle<R>) {
+    stop(&app);
+}
@@ -0,0 +1,283 @@
+//! Profiles for YULA Desktop
+//!
+//! Each profile is a separate account workspace with its own settings,
+//! local data, webview storage and keychain namespace. The default profile
+//! uses the app directories directly, so existing installs keep their data;
+//! other profiles live under a `profiles/<name>` subdirectory of each. The
+//! profile is chosen at launch with `--profile <name>`, and switching
+//! relaunches the app with that flag.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// Command line flag selecting the profile
+pub const PROFILE_ARG: &str = "--profile";
+
+/// Name of the profile that uses the app directories directly
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Subdirectory of the app directories holding the other profiles
+const PROFILES_DIR: &str = "profiles";
+
+/// Longest allowed profile name
+const MAX_NAME_LEN: usize = 64;
+
+/// A profile and whether this instance runs it
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+}
+
+/// Profile named on the command line, `None` for the default profile
+fn from_args(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = if arg == PROFILE_ARG {
+            args.next().cloned()
+        } else {
+            arg.strip_prefix("--profile=").map(str::to_string)
+        };
+        if let Some(name) = name {
+            return Some(name).filter(|name| name != DEFAULT_PROFILE && validate(name).is_ok());
+        }
+    }
+    None
+}
+
+/// Read the profile from the launch arguments into `AppState`
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let profile = from_args(&args);
+    if let Some(profile) = &profile {
+        log::info!("Using profile {}", profile);
+    }
+    *app.state::<crate::AppState>().profile.lock().unwrap() = profile;
+}
+
+/// The active profile, `None` for the default profile
+pub fn active<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.try_state::<crate::AppState>()
+        .and_then(|state| state.profile.lock().unwrap().clone())
+}
+
+fn validate(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Profile names use letters, digits, '-' and '_' (at most {} characters)",
+            MAX_NAME_LEN
+        ))
+    }
+}
+
+/// Resolve an app directory for a profile
+fn scoped(base: Result<PathBuf, tauri::Error>, profile: Option<&str>) -> Result<PathBuf, String> {
+    let base = base.map_err(|e| e.to_string())?;
+    Ok(match profile {
+        Some(profile) => base.join(PROFILES_DIR).join(profile),
+        None => base,
+    })
+}
+
+/// Config directory of the profile named on the command line, resolved as
+/// the path resolver would for use before the app is built
+#[cfg(target_os = "linux")]
+pub fn launch_config_dir(identifier: &str) -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    scoped(Ok(base.join(identifier)), from_args(&args).as_deref()).ok()
+}
+
+/// Config directory of the active profile
+pub fn config_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    scoped(app.path().app_config_dir(), active(app).as_deref())
+}
+
+/// Data directory of the active profile
+pub fn data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    scoped(app.path().app_data_dir(), active(app).as_deref())
+}
+
+/// Cache directory of the active profile
+pub fn cache_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    scoped(app.path().app_cache_dir(), active(app).as_deref())
+}
+
+/// Keychain service name of the active profile
+pub fn keychain_service<R: Runtime>(app: &AppHandle<R>) -> String {
+    let identifier = &app.config().identifier;
+    match active(app) {
+        Some(profile) => format!("{}.{}.{}", identifier, PROFILES_DIR, profile),
+        None => identifier.clone(),
+    }
+}
+
+/// Identifier of a profile's WKWebView data store
+#[cfg(target_os = "macos")]
+pub fn webview_store_id(profile: &str) -> [u8; 16] {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(profile.as_bytes());
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&digest[..16]);
+    id
+}
+
+/// Keep a webview's cookies and local storage within the active profile
+///
+/// The default profile keeps the webview's default storage.
+pub fn isolate_webview<'a, R: Runtime, M: Manager<R>>(
+    app: &AppHandle<R>,
+    builder: WebviewWindowBuilder<'a, R, M>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    let Some(profile) = active(app) else {
+        return builder;
+    };
+
+    // WKWebView ignores the data directory; macOS 14+ keys stores by id
+    #[cfg(target_os = "macos")]
+    {
+        builder.data_store_identifier(webview_store_id(&profile))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    match scoped(app.path().app_data_dir(), Some(&profile)) {
+        Ok(dir) => builder.data_directory(dir.join("webview")),
+        Err(e) => {
+            log::warn!("Webview storage is shared with the default profile: {}", e);
+            builder
+        }
+    }
+}
+
+/// Restart the app with the given arguments in place of the current ones
+fn relaunch<R: Runtime>(app: &AppHandle<R>, args: Vec<String>) -> ! {
+    let mut env = app.env();
+    env.args_os = std::iter::once(env.args_os.first().cloned().unwrap_or_default())
+        .chain(args.into_iter().map(Into::into))
+        .collect();
+    crate::restart(app, &env)
+}
+
+/// List the profiles, default first
+#[tauri::command]
+pub async fn list_profiles<R: Runtime>(app: AppHandle<R>) -> Result<Vec<ProfileInfo>, String> {
+    let active = active(&app);
+    let root = scoped(app.path().app_data_dir(), None)?.join(PROFILES_DIR);
+    let mut names: Vec<String> = match std::fs::read_dir(root) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| validate(name).is_ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+
+    let default = ProfileInfo {
+        name: DEFAULT_PROFILE.to_string(),
+        active: active.is_none(),
+    };
+    Ok(std::iter::once(default)
+        .chain(names.into_iter().map(|name| ProfileInfo {
+            active: active.as_deref() == Some(name.as_str()),
+            name,
+        }))
+        .collect())
+}
+
+/// Create an empty profile
+#[tauri::command]
+pub async fn create_profile<R: Runtime>(app: AppHandle<R>, name: String) -> Result<(), String> {
+    validate(&name)?;
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile always exists".to_string());
+    }
+
+    let data = scoped(app.path().app_data_dir(), Some(&name))?;
+    if data.exists() {
+        return Err(format!("Profile already exists: {}", name));
+    }
+    for dir in [data, scoped(app.path().app_config_dir(), Some(&name))?] {
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+
+    log::info!("Created profile {}", name);
+    Ok(())
+}
+
+/// Relaunch the app in another profile
+#[tauri::command]
+pub async fn switch_profile<R: Runtime>(app: AppHandle<R>, name: String) -> Result<(), String> {
+    let target = (name != DEFAULT_PROFILE).then_some(name);
+    if let Some(name) = &target {
+        validate(name)?;
+        if !scoped(app.path().app_data_dir(), Some(name))?.is_dir() {
+            return Err(format!("Profile not found: {}", name));
+        }
+    }
+    if target == active(&app) {
+        return Ok(());
+    }
+
+    // Keep other flags, such as `--minimized`, but replace the profile
+    let mut args = Vec::new();
+    let mut current = std::env::args().skip(1);
+    while let Some(arg) = current.next() {
+        if arg == PROFILE_ARG {
+            current.next();
+        } else if !arg.starts_with("--profile=") {
+            args.push(arg);
+        }
+    }
+    if let Some(name) = &target {
+        args.extend([PROFILE_ARG.to_string(), name.clone()]);
+    }
+
+    log::info!("Switching to profile {:?}", target);
+    relaunch(&app, args)
+}
+
+/// Delete a profile and its local data
+///
+/// The active and default profiles can't be deleted. Secrets stored in the
+/// profile's keychain namespace are left in place.
+#[tauri::command]
+pub async fn delete_profile<R: Runtime>(app: AppHandle<R>, name: String) -> Result<(), String> {
+    validate(&name)?;
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile can't be deleted".to_string());
+    }
+    if active(&app).as_deref() == Some(name.as_str()) {
+        return Err("The active profile can't be deleted".to_string());
+    }
+
+    let data = scoped(app.path().app_data_dir(), Some(&name))?;
+    if !data.is_dir() {
+        return Err(format!("Profile not found: {}", name));
+    }
+    for dir in [
+        data,
+        scoped(app.path().app_config_dir(), Some(&name))?,
+        scoped(app.path().app_cache_dir(), Some(&name))?,
+    ] {
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+        }
+    }
+
+    log::info!("Deleted profile {}", name);
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Progress of multi-step operations for YULA Desktop
+//!
+//! Long native operations (an export gathering, rendering and writing, for
+//! instance) report through `begin`, `update` and `complete`, and the
+//! frontend can drive its own through the matching commands. Every change
+//! emits `operation-progress`. The most recently started operation that's
+//! still running is the foreground one, and its progress is mirrored onto
+//! the dock or taskbar icon; the icon's bar clears once nothing is running.
+
+use serde::Serialize;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::CommandError;
+
+/// Identifies an operation between `begin` and `complete`
+pub type OperationId = u32;
+
+/// Payload of the `operation-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationProgress {
+    pub id: OperationId,
+    pub name: String,
+    /// The step being worked on, e.g. "Rendering"
+    pub step: Option<String>,
+    /// Between 0 and 1
+    pub fraction: f64,
+    pub completed: bool,
+}
+
+/// Operations that are still running, oldest first
+#[derive(Debug, Default)]
+pub struct Operations {
+    next_id: OperationId,
+    active: Vec<OperationProgress>,
+}
+
+impl Operations {
+    fn position(&self, id: OperationId) -> Result<usize, CommandError> {
+        self.active
+            .iter()
+            .position(|op| op.id == id)
+            .ok_or_else(|| CommandError::NotFound(format!("No running operation {}", id)))
+    }
+}
+
+/// Show the foreground operation's progress on the dock or taskbar icon
+fn reflect<R: Runtime>(app: &AppHandle<R>, foreground: Option<&OperationProgress>) {
+    let Ok(window) = crate::window::main_window(app) else {
+        return;
+    };
+    let state = match foreground {
+        Some(op) => ProgressBarState {
+            status: Some(ProgressBarStatus::Normal),
+            progress: Some((op.fraction * 100.0).round() as u64),
+        },
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        },
+    };
+    if let Err(e) = window.set_progress_bar(state) {
+        log::debug!("Failed to set taskbar progress: {}", e);
+    }
+}
+
+/// Emit an operation's progress, mirroring the foreground one onto the icon
+fn publish<R: Runtime>(app: &AppHandle<R>, op: OperationProgress) {
+    let foreground = app
+        .state::<crate::AppState>()
+        .operations
+        .lock()
+        .unwrap()
+        .active
+        .last()
+        .cloned();
+    reflect(app, foreground.as_ref());
+    crate::events::emit(app, crate::events::OPERATION_PROGRESS, op);
+}
+
+/// Start tracking an operation
+pub fn begin<R: Runtime>(app: &AppHandle<R>, name: &str) -> OperationId {
+    let op = {
+        let state = app.state::<crate::AppState>();
+        let mut operations = state.operations.lock().unwrap();
+        operations.next_id = operations.next_id.wrapping_add(1).max(1);
+        let op = OperationProgress {
+            id: operations.next_id,
+            name: name.to_string(),
+            step: None,
+            fraction: 0.0,
+            completed: false,
+        };
+        operations.active.push(op.clone());
+        op
+    };
+
+    let id = op.id;
+    publish(app, op);
+    id
+}
+
+/// Report the current step of an operation and how far along it is
+///
+/// `fraction` is clamped between 0 and 1. `None` keeps the previous step.
+pub fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    id: OperationId,
+    step: Option<&str>,
+    fraction: f64,
+) -> Result<(), CommandError> {
+    let op = {
+        let state = app.state::<crate::AppState>();
+        let mut operations = state.operations.lock().unwrap();
+        let index = operations.position(id)?;
+        let op = &mut operations.active[index];
+        if let Some(step) = step {
+            op.step = Some(step.to_string());
+        }
+        op.fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        op.clone()
+    };
+
+    publish(app, op);
+    Ok(())
+}
+
+/// Stop tracking a finished operation
+pub fn complete<R: Runtime>(app: &AppHandle<R>, id: OperationId) -> Result<(), CommandError> {
+    let mut op = {
+        let state = app.state::<crate::AppState>();
+        let mut operations = state.operations.lock().unwrap();
+        let index = operations.position(id)?;
+        operations.active.remove(index)
+    };
+
+    op.fraction = 1.0;
+    op.completed = true;
+    publish(app, op);
+    Ok(())
+}
+
+/// Start an operation, returning its id for `update_operation`
+#[tauri::command]
+pub fn begin_operation<R: Runtime>(app: AppHandle<R>, name: String) -> OperationId {
+    begin(&app, &name)
+}
+
+/// Report the step an operation is on and how far along it is, from 0 to 1
+#[tauri::command]
+pub fn update_operation<R: Runtime>(
+    app: AppHandle<R>,
+    id: OperationId,
+    step: Option<String>,
+    fraction: f64,
+) -> Result<(), CommandError> {
+    update(&app, id, step.as_deref(), fraction)
+}
+
+/// Mark an operation finished
+#[tauri::command]
+pub fn complete_operation<R: Runtime>(
+    app: AppHandle<R>,
+    id: OperationId,
+) -> Result<(), CommandError> {
+    complete(&app, id)
+}
+
+/// Get the operations that are still running, oldest first
+#[tauri::command]
+pub fn get_active_operations<R: Runtime>(app: AppHandle<R>) -> Vec<OperationProgress> {
+    app.state::<crate::AppState>()
+        .operations
+        .lock()
+        .unwrap()
+        .active
+        .clone()
+}
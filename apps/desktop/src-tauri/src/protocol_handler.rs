@@ -0,0 +1,208 @@
+//! Default `yula://` handler for YULA Desktop
+//!
+//! Side-by-side installs (stable and beta, or an old copy left behind) each
+//! register the scheme, and the OS sends links to whichever one registered
+//! last. `is_default_protocol_handler` asks the OS where links go, from
+//! Launch Services on macOS, the `HKEY_CLASSES_ROOT\yula` open command on
+//! Windows and the `Exec` line of the `xdg-mime` default entry on Linux, and
+//! compares it with this install. When another install owns the scheme,
+//! `protocol-handler-conflict` is emitted with its path; release builds also
+//! check once at launch. `set_as_default_protocol_handler` claims the scheme.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Runtime};
+
+use crate::deep_link::SCHEME;
+use crate::error::CommandError;
+
+/// Payload of the `protocol-handler-conflict` event
+#[derive(Debug, Clone, Serialize)]
+struct ConflictPayload {
+    /// Executable or app bundle that `yula://` links open
+    owner: String,
+}
+
+/// This install as the OS refers to it: the app bundle on macOS, the
+/// AppImage or executable elsewhere
+fn this_install() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        crate::signature::bundle_path().ok()
+    }
+
+    // AppImages run from a mount point that changes every launch
+    #[cfg(target_os = "linux")]
+    {
+        std::env::var_os("APPIMAGE")
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_exe().ok())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        std::env::current_exe().ok()
+    }
+}
+
+/// The install that `yula://` links currently open, if any is registered
+#[cfg(target_os = "macos")]
+fn owner() -> Option<PathBuf> {
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSString;
+
+    let link = NSString::from_str(&format!("{}://", SCHEME));
+    unsafe {
+        let url: *mut AnyObject = msg_send![class!(NSURL), URLWithString: &*link];
+        let url = url.as_ref()?;
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let workspace = workspace.as_ref()?;
+        let app: *mut AnyObject = msg_send![workspace, URLForApplicationToOpenURL: url];
+        let app = app.as_ref()?;
+        let path: *mut NSString = msg_send![app, path];
+        path.as_ref().map(|path| PathBuf::from(path.to_string()))
+    }
+}
+
+/// The install that `yula://` links currently open, if any is registered
+#[cfg(target_os = "windows")]
+fn owner() -> Option<PathBuf> {
+    // HKCR merges the per-user and per-machine registrations, user first
+    let key = format!(r"HKCR\{}\shell\open\command", SCHEME);
+    let output = crate::platform::output("reg", &["query", &key, "/ve"])?;
+    // `(Default)    REG_SZ    "C:\...\YULA.exe" "%1"`
+    let command = output.lines().find_map(|line| {
+        line.split_once("REG_SZ")
+            .or_else(|| line.split_once("REG_EXPAND_SZ"))
+            .map(|(_, command)| command.trim())
+    })?;
+    crate::platform::program_of(command).map(PathBuf::from)
+}
+
+/// The install that `yula://` links currently open, if any is registered
+#[cfg(target_os = "linux")]
+fn owner() -> Option<PathBuf> {
+    let mime = format!("x-scheme-handler/{}", SCHEME);
+    let desktop_file = crate::platform::output("xdg-mime", &["query", "default", &mime])?;
+    if desktop_file.is_empty() {
+        return None;
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+    let entry = data_home
+        .into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .find_map(|dir| {
+            std::fs::read_to_string(dir.join("applications").join(&desktop_file)).ok()
+        })?;
+
+    let exec = entry.lines().find_map(|line| line.strip_prefix("Exec="))?;
+    let program = crate::platform::program_of(exec)?;
+    // Entries may name a program on the PATH instead of a full path
+    if std::path::Path::new(program).is_absolute() {
+        Some(PathBuf::from(program))
+    } else {
+        crate::platform::output("which", &[program]).map(PathBuf::from)
+    }
+}
+
+/// Check whether this install opens `yula://` links, emitting
+/// `protocol-handler-conflict` when another one does
+fn check<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let Some(owner) = owner() else {
+        log::info!("No handler is registered for {}://", SCHEME);
+        return false;
+    };
+    if this_install().is_some_and(|install| crate::platform::same_path(&install, &owner)) {
+        return true;
+    }
+
+    log::warn!("{}:// links open another install: {}", SCHEME, owner.display());
+    // The launch check finishes before the page can listen
+    crate::launch::emit_when_ready(
+        app,
+        crate::events::PROTOCOL_HANDLER_CONFLICT,
+        ConflictPayload {
+            owner: owner.to_string_lossy().to_string(),
+        },
+    );
+    false
+}
+
+/// Warn at launch when links open another install; skipped in debug builds,
+/// which never own the scheme
+pub fn check_launch<R: Runtime>(app: &AppHandle<R>) {
+    if cfg!(debug_assertions) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        check(&app);
+    });
+}
+
+/// Make this install the one that opens `yula://` links
+#[tauri::command]
+pub async fn set_as_default_protocol_handler<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<(), CommandError> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2_foundation::NSString;
+        use std::ffi::c_void;
+
+        #[link(name = "CoreServices", kind = "framework")]
+        extern "C" {
+            fn LSSetDefaultHandlerForURLScheme(
+                scheme: *const c_void,
+                bundle_id: *const c_void,
+            ) -> i32;
+        }
+
+        // NSString is toll-free bridged to the CFString Launch Services takes
+        let scheme = NSString::from_str(SCHEME);
+        let bundle_id = NSString::from_str(&app.config().identifier);
+        let status = unsafe {
+            LSSetDefaultHandlerForURLScheme(
+                &*scheme as *const NSString as *const c_void,
+                &*bundle_id as *const NSString as *const c_void,
+            )
+        };
+        if status != 0 {
+            return Err(CommandError::Failed(format!(
+                "Launch Services refused the handler (status {})",
+                status
+            )));
+        }
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    {
+        use tauri_plugin_deep_link::DeepLinkExt;
+
+        crate::plugins::require(&app, crate::plugins::DEEP_LINK)?;
+        app.deep_link()
+            .register(SCHEME)
+            .map_err(|e| CommandError::Failed(e.to_string()))?;
+    }
+
+    log::info!("Registered as the {}:// handler", SCHEME);
+    Ok(())
+}
+
+/// Check whether this install opens `yula://` links
+///
+/// Emits `protocol-handler-conflict` when another install does.
+#[tauri::command]
+pub async fn is_default_protocol_handler<R: Runtime>(app: AppHandle<R>) -> bool {
+    tauri::async_runtime::spawn_blocking(move || check(&app))
+        .await
+        .unwrap_or(false)
+}
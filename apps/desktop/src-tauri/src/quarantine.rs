@@ -0,0 +1,85 @@
+//! Gatekeeper quarantine handling for YULA Desktop
+//!
+//! Apps downloaded outside the App Store carry the `com.apple.quarantine`
+//! extended attribute, which makes Gatekeeper check them on first launch
+//! and, for builds it can't verify, shows the "app is damaged" or "can't be
+//! opened" dialog. The frontend can detect the attribute and, once the user
+//! agrees in a native confirmation dialog, strip it from the bundle so later
+//! launches and updates aren't blocked. macOS only.
+
+use tauri::{AppHandle, Runtime};
+
+use crate::error::CommandError;
+
+#[cfg(target_os = "macos")]
+const QUARANTINE_ATTRIBUTE: &str = "com.apple.quarantine";
+
+/// Check whether the app bundle carries the quarantine attribute
+///
+/// Returns `NotSupported` off macOS.
+#[tauri::command]
+pub async fn get_quarantine_status() -> Result<bool, CommandError> {
+    #[cfg(target_os = "macos")]
+    {
+        let bundle = crate::signature::bundle_path()?;
+        // `xattr -p` fails when the attribute isn't set
+        let status = crate::platform::command("xattr")
+            .args(["-p", QUARANTINE_ATTRIBUTE])
+            .arg(&bundle)
+            .output()
+            .map_err(|e| CommandError::Failed(format!("Failed to run xattr: {}", e)))?
+            .status;
+        Ok(status.success())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    Err(CommandError::NotSupported("Quarantine only applies on macOS".to_string()))
+}
+
+/// Remove the quarantine attribute from the app bundle
+///
+/// Asks the user to confirm in a native dialog first and returns whether it
+/// was removed. Fails with `PermissionDenied` when the bundle isn't
+/// writable, e.g. an admin install in /Applications. Returns `NotSupported`
+/// off macOS.
+#[tauri::command]
+pub async fn clear_quarantine<R: Runtime>(app: AppHandle<R>) -> Result<bool, CommandError> {
+    #[cfg(target_os = "macos")]
+    {
+        let bundle = crate::signature::bundle_path()?;
+        let confirmed = crate::dialogs::confirm_dialog(
+            app,
+            "Allow YULA to open normally".to_string(),
+            "macOS marks apps downloaded from the internet as quarantined. Removing the \
+             mark stops the \"can't be opened\" warning on future launches and updates."
+                .to_string(),
+        )
+        .await?;
+        if !confirmed {
+            return Ok(false);
+        }
+
+        let output = crate::platform::command("xattr")
+            .args(["-r", "-d", QUARANTINE_ATTRIBUTE])
+            .arg(&bundle)
+            .output()
+            .map_err(|e| CommandError::Failed(format!("Failed to run xattr: {}", e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(if stderr.contains("Permission denied") {
+                CommandError::PermissionDenied(stderr)
+            } else {
+                CommandError::Failed(stderr)
+            });
+        }
+
+        log::info!("Removed quarantine attribute from {}", bundle.display());
+        Ok(true)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        Err(CommandError::NotSupported("Quarantine only applies on macOS".to_string()))
+    }
+}
@@ -0,0 +1,104 @@
+//! Quick capture overlay for YULA Desktop
+//!
+//! The overlay is a borderless, transparent, always-on-top window covering
+//! the monitor under the cursor. The frontend's `/quick-capture` route draws
+//! the selection and reports it through `submit_quick_capture_region`, which
+//! converts it to screen coordinates, emits `quick-capture-region`, and
+//! closes the overlay.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindowBuilder};
+
+/// Label of the overlay window
+pub const QUICK_CAPTURE_WINDOW: &str = "quick-capture";
+
+/// Captured region in physical screen pixels
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CaptureRegion {
+    pub x: i32,
+    pub y: i32,
+    pub w: u32,
+    pub h: u32,
+}
+
+/// Show the quick capture overlay on the monitor under the cursor
+#[tauri::command]
+pub async fn show_quick_capture<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW) {
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+    crate::window::check_policy(&app, QUICK_CAPTURE_WINDOW).map_err(|e| e.to_string())?;
+
+    let cursor = app.cursor_position().map_err(|e| e.to_string())?;
+    let monitor = match app
+        .monitor_from_point(cursor.x, cursor.y)
+        .map_err(|e| e.to_string())?
+    {
+        Some(monitor) => monitor,
+        None => app
+            .primary_monitor()
+            .map_err(|e| e.to_string())?
+            .ok_or("No monitor available")?,
+    };
+
+    let scale = monitor.scale_factor();
+    let position = monitor.position().to_logical::<f64>(scale);
+    let size = monitor.size().to_logical::<f64>(scale);
+
+    let builder = WebviewWindowBuilder::new(
+        &app,
+        QUICK_CAPTURE_WINDOW,
+        crate::window::frontend_url("quick-capture"),
+    );
+    let builder = crate::csp::attach(&app, builder);
+    let window = crate::profiles::isolate_webview(&app, builder)
+        .title("Quick Capture")
+        .position(position.x, position.y)
+        .inner_size(size.width, size.height)
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .resizable(false)
+        .shadow(false)
+        .focused(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    // An overlay shouldn't show up in the taskbar or the window switcher
+    crate::window::apply_skip_taskbar(&window, true)
+}
+
+/// Close the quick capture overlay without capturing
+#[tauri::command]
+pub async fn hide_quick_capture<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Report the region selected in the overlay (logical overlay coordinates)
+#[tauri::command]
+pub async fn submit_quick_capture_region<R: Runtime>(
+    app: AppHandle<R>,
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+) -> Result<CaptureRegion, String> {
+    let window = crate::window::get_window(&app, QUICK_CAPTURE_WINDOW)?;
+    let origin = window.outer_position().map_err(|e| e.to_string())?;
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+
+    let region = CaptureRegion {
+        x: origin.x + (x * scale).round() as i32,
+        y: origin.y + (y * scale).round() as i32,
+        w: (w * scale).round() as u32,
+        h: (h * scale).round() as u32,
+    };
+
+    crate::events::emit(&app, crate::events::QUICK_CAPTURE_REGION, region);
+    window.close().map_err(|e| e.to_string())?;
+
+    Ok(region)
+}
@@ -0,0 +1,361 @@
+//! Update rollback for YULA Desktop
+//!
+//! Before `install_update` replaces the app, the current installation is
+//! copied to `rollback/` in the app data dir and described in
+//! `rollback.json`. Every launch of the updated version leaves a marker file
+//! that is removed once the app has run for `HEALTHY_AFTER` or exits
+//! cleanly, so a marker found at startup means the previous launch crashed.
+//! After `CRASH_THRESHOLD` crashes in a row `offer-rollback` is emitted once
+//! the frontend is ready, and `rollback_update` restores the backup and
+//! restarts. The metadata is only removed once the restore succeeded, so a
+//! failed one can be tried again.
+//!
+//! The installation is the `.app` bundle on macOS, the install directory on
+//! Windows and the AppImage on Linux; other Linux packages are managed by
+//! the system package manager and can't be rolled back here. A Windows
+//! install directory the user can't write to, as with per-machine installs,
+//! is restored by an elevated script after a UAC prompt.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Rollback metadata file in the app data dir
+const INFO_FILE: &str = "rollback.json";
+
+/// Directory in the app data dir holding the previous installation
+const BACKUP_DIR: &str = "rollback";
+
+/// Marker present while a launch hasn't yet proven healthy
+const LAUNCH_MARKER: &str = "launch.marker";
+
+/// Consecutive crashes of the updated version before rollback is offered
+const CRASH_THRESHOLD: u32 = 3;
+
+/// How long a launch must run before it no longer counts as a crash
+const HEALTHY_AFTER: Duration = Duration::from_secs(30);
+
+/// Previous installation kept for rolling back an update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollbackInfo {
+    pub previous_version: String,
+    pub updated_version: String,
+    /// Installation replaced by the update
+    pub installed_path: PathBuf,
+    /// Copy of the installation before the update
+    pub backup_path: PathBuf,
+    /// When the backup was taken, Unix millis
+    pub recorded_at: i64,
+    /// Consecutive launches of the updated version that crashed
+    pub crash_count: u32,
+}
+
+fn data_path<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(name))
+        .map_err(|e| e.to_string())
+}
+
+fn read<R: Runtime>(app: &AppHandle<R>) -> Option<RollbackInfo> {
+    let contents = std::fs::read_to_string(data_path(app, INFO_FILE).ok()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write<R: Runtime>(app: &AppHandle<R>, info: &RollbackInfo) -> Result<(), String> {
+    let path = data_path(app, INFO_FILE)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(info).map_err(|e| e.to_string())?;
+    crate::settings::write_atomic(&path, contents)
+}
+
+/// Locate the installation an update would replace
+fn installation() -> Result<PathBuf, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        exe.ancestors()
+            .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+            .map(Path::to_path_buf)
+            .ok_or_else(|| "Not running from an app bundle".to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        exe.parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| "Executable has no parent directory".to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::env::var_os("APPIMAGE")
+            .map(PathBuf::from)
+            .ok_or_else(|| "Rollback is only supported for AppImage installs".to_string())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    Err("Rollback is not supported on this platform".to_string())
+}
+
+/// Copy a file or directory tree, preserving symlinks where possible
+fn copy_all(from: &Path, to: &Path) -> std::io::Result<()> {
+    let file_type = std::fs::symlink_metadata(from)?.file_type();
+    if file_type.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else if file_type.is_symlink() {
+        copy_symlink(from, to)
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::copy(from, to).map(|_| ())
+}
+
+#[cfg(unix)]
+fn remove_all(path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Back up the current installation before an update replaces it
+pub async fn record<R: Runtime>(app: &AppHandle<R>, updated_version: &str) -> Result<(), String> {
+    let installed_path = installation()?;
+    let file_name = installed_path
+        .file_name()
+        .ok_or("Installation path has no file name")?;
+    let backup_root = data_path(app, BACKUP_DIR)?;
+    let backup_path = backup_root.join(file_name);
+
+    log::info!(
+        "Backing up {} to {}",
+        installed_path.display(),
+        backup_path.display()
+    );
+    let (from, to) = (installed_path.clone(), backup_path.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        if backup_root.exists() {
+            std::fs::remove_dir_all(&backup_root)?;
+        }
+        std::fs::create_dir_all(&backup_root)?;
+        copy_all(&from, &to)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to back up installation: {}", e))?;
+
+    write(
+        app,
+        &RollbackInfo {
+            previous_version: app.package_info().version.to_string(),
+            updated_version: updated_version.to_string(),
+            installed_path,
+            backup_path,
+            recorded_at: chrono::Utc::now().timestamp_millis(),
+            crash_count: 0,
+        },
+    )
+}
+
+/// Count a crash if the previous launch didn't finish, and offer rollback
+pub fn check_launch<R: Runtime>(app: &AppHandle<R>) {
+    let Some(mut info) = read(app) else {
+        return;
+    };
+    if info.updated_version != app.package_info().version.to_string() {
+        return;
+    }
+    let Ok(marker) = data_path(app, LAUNCH_MARKER) else {
+        return;
+    };
+
+    if marker.exists() {
+        info.crash_count += 1;
+        log::warn!(
+            "Previous launch of {} crashed ({} in a row)",
+            info.updated_version,
+            info.crash_count
+        );
+    }
+    if let Err(e) = std::fs::write(&marker, b"") {
+        log::warn!("Failed to write launch marker: {}", e);
+    }
+    if let Err(e) = write(app, &info) {
+        log::warn!("Failed to update rollback metadata: {}", e);
+    }
+
+    if info.crash_count >= CRASH_THRESHOLD && info.backup_path.exists() {
+        log::warn!("Offering rollback to {}", info.previous_version);
+        crate::launch::emit_when_ready(app, crate::events::OFFER_ROLLBACK, info);
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(HEALTHY_AFTER).await;
+        mark_healthy(&app);
+    });
+}
+
+/// Clear the launch marker and reset the crash count
+pub fn mark_healthy<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(marker) = data_path(app, LAUNCH_MARKER) else {
+        return;
+    };
+    if !marker.exists() {
+        return;
+    }
+    let _ = std::fs::remove_file(&marker);
+
+    if let Some(mut info) = read(app) {
+        if info.crash_count > 0 {
+            info.crash_count = 0;
+            if let Err(e) = write(app, &info) {
+                log::warn!("Failed to update rollback metadata: {}", e);
+            }
+        }
+    }
+}
+
+/// Get the rollback metadata, if a previous installation is kept
+#[tauri::command]
+pub fn get_rollback_info<R: Runtime>(app: AppHandle<R>) -> Option<RollbackInfo> {
+    read(&app).filter(|info| info.backup_path.exists())
+}
+
+/// Reinstall the version that was replaced by the last update and restart
+#[tauri::command]
+pub async fn rollback_update<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let info = get_rollback_info(app.clone()).ok_or("No previous version to roll back to")?;
+    log::warn!(
+        "Rolling back from {} to {}",
+        info.updated_version,
+        info.previous_version
+    );
+
+    let info_file = data_path(&app, INFO_FILE)?;
+    let marker = data_path(&app, LAUNCH_MARKER)?;
+
+    #[cfg(unix)]
+    {
+        let (installed, backup) = (info.installed_path.clone(), info.backup_path.clone());
+        tauri::async_runtime::spawn_blocking(move || restore(&installed, &backup))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to restore previous version: {}", e))?;
+        let _ = std::fs::remove_file(info_file);
+        let _ = std::fs::remove_file(marker);
+        crate::restart(&app, &app.env());
+    }
+
+    #[cfg(windows)]
+    {
+        // Running executables can't be replaced, so a script waits for us
+        // to exit before copying the backup back and relaunching
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let elevate = !is_writable(&info.installed_path);
+        let script = std::env::temp_dir().join("yula-rollback.cmd");
+        let contents = format!(
+            concat!(
+                "@echo off\r\n",
+                ":wait\r\n",
+                "tasklist /fi \"PID eq {pid}\" | find \"{pid}\" >nul ",
+                "&& (timeout /t 1 /nobreak >nul & goto wait)\r\n",
+                "robocopy \"{backup}\" \"{installed}\" /MIR /NFL /NDL /NJH /NJS >nul\r\n",
+                // robocopy exit codes from 8 up mean the copy failed
+                "if errorlevel 8 goto relaunch\r\n",
+                "del /f /q \"{info_file}\" \"{marker}\"\r\n",
+                "rmdir /s /q \"{backup}\"\r\n",
+                ":relaunch\r\n",
+                "{launcher} \"{exe}\"\r\n",
+            ),
+            pid = std::process::id(),
+            backup = info.backup_path.display(),
+            installed = info.installed_path.display(),
+            info_file = info_file.display(),
+            marker = marker.display(),
+            // An elevated script would start the app elevated too; Explorer
+            // starts it as the signed-in user
+            launcher = if elevate { "explorer.exe" } else { "start \"\"" },
+            exe = exe.display(),
+        );
+        std::fs::write(&script, contents).map_err(|e| e.to_string())?;
+
+        if elevate {
+            log::info!("Install directory needs elevation; asking for it");
+            let command = format!(
+                "Start-Process -FilePath cmd.exe -ArgumentList '/C','\"{}\"' -Verb RunAs \
+                 -WindowStyle Hidden",
+                script.display()
+            );
+            let status = tauri::async_runtime::spawn_blocking(move || {
+                crate::platform::command(crate::platform::powershell())
+                    .args(["-NoProfile", "-NonInteractive", "-Command", &command])
+                    .status()
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to start rollback: {}", e))?;
+            // Fails when the UAC prompt is declined
+            if !status.success() {
+                return Err("Rollback needs administrator rights".to_string());
+            }
+        } else {
+            crate::platform::command("cmd")
+                .arg("/C")
+                .arg(&script)
+                .spawn()
+                .map_err(|e| format!("Failed to start rollback: {}", e))?;
+        }
+        app.exit(0);
+        Ok(())
+    }
+}
+
+/// Whether this process can write to a directory
+#[cfg(windows)]
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".yula-write-test-{}", std::process::id()));
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+/// Swap the backup in place of the installation, keeping the latter until done
+#[cfg(unix)]
+fn restore(installed: &Path, backup: &Path) -> std::io::Result<()> {
+    let replaced = installed.with_extension("replaced");
+    if replaced.exists() {
+        remove_all(&replaced)?;
+    }
+    std::fs::rename(installed, &replaced)?;
+
+    if let Err(e) = copy_all(backup, installed) {
+        let _ = remove_all(installed);
+        let _ = std::fs::rename(&replaced, installed);
+        return Err(e);
+    }
+
+    let _ = remove_all(&replaced);
+    let _ = remove_all(backup);
+    Ok(())
+}
@@ -0,0 +1,242 @@
+//! Scheduled notifications for YULA Desktop
+//!
+//! The notification plugin can't schedule on desktop, so pending
+//! notifications are kept here and fired by a background loop. Each one is
+//! stored as a wall-clock time in an IANA time zone (the system zone when
+//! none is given) and its UTC fire time is recomputed on every tick, so a
+//! DST transition, a changed system zone or an adjusted clock never makes a
+//! reminder fire an hour off. Local times skipped by a spring-forward
+//! transition fire at the first valid time after the gap; ambiguous times
+//! in a fall-back transition use the earlier occurrence.
+//!
+//! Pending notifications live in memory and don't survive a restart.
+
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Longest the loop sleeps, bounding how late a clock change or a newly
+/// scheduled notification is noticed
+const MAX_TICK: Duration = Duration::from_secs(5);
+
+/// A notification waiting to be shown
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledNotification {
+    pub id: u32,
+    pub title: String,
+    pub body: String,
+    /// Wall-clock time the notification is due, e.g. `2026-03-29T09:00:00`
+    pub local_time: NaiveDateTime,
+    /// IANA zone of `local_time`; `None` follows the system zone
+    pub timezone: Option<String>,
+    /// Resolved fire time, Unix millis
+    pub fire_at: i64,
+}
+
+/// Pending scheduled notifications
+#[derive(Debug, Default)]
+pub struct Schedule {
+    next_id: u32,
+    pending: HashMap<u32, ScheduledNotification>,
+}
+
+/// Look up the system time zone
+fn system_timezone() -> Result<Tz, String> {
+    let name = iana_time_zone::get_timezone().map_err(|e| e.to_string())?;
+    name.parse::<Tz>().map_err(|e| e.to_string())
+}
+
+fn parse_timezone(timezone: Option<&str>) -> Result<Tz, String> {
+    match timezone {
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| format!("Unknown time zone: {}", name)),
+        None => system_timezone(),
+    }
+}
+
+/// Resolve a wall-clock time in `tz` to UTC across DST transitions
+fn resolve(local_time: NaiveDateTime, tz: Tz) -> Option<DateTime<Utc>> {
+    if let Some(time) = tz.from_local_datetime(&local_time).earliest() {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    // Skipped by a spring-forward gap; gaps are at most a few hours long
+    (1..=4 * 60).find_map(|minutes| {
+        tz.from_local_datetime(&(local_time + ChronoDuration::minutes(minutes)))
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+    })
+}
+
+/// Recompute fire times and take every notification that is due
+fn take_due<R: Runtime>(app: &AppHandle<R>) -> (Vec<ScheduledNotification>, Option<i64>) {
+    let system = system_timezone().ok();
+    let now = Utc::now().timestamp_millis();
+
+    let state = app.state::<crate::AppState>();
+    let mut schedule = state.schedule.lock().unwrap();
+    for scheduled in schedule.pending.values_mut() {
+        let tz = match scheduled.timezone.as_deref() {
+            Some(name) => name.parse::<Tz>().ok(),
+            None => system,
+        };
+        let Some(fire_at) = tz.and_then(|tz| resolve(scheduled.local_time, tz)) else {
+            continue;
+        };
+        let fire_at = fire_at.timestamp_millis();
+        if fire_at != scheduled.fire_at {
+            log::info!(
+                "Scheduled notification {} moved from {} to {}",
+                scheduled.id,
+                scheduled.fire_at,
+                fire_at
+            );
+            scheduled.fire_at = fire_at;
+        }
+    }
+
+    let due: Vec<u32> = schedule
+        .pending
+        .values()
+        .filter(|scheduled| scheduled.fire_at <= now)
+        .map(|scheduled| scheduled.id)
+        .collect();
+    let due: Vec<ScheduledNotification> = due
+        .iter()
+        .filter_map(|id| schedule.pending.remove(id))
+        .collect();
+    let next = schedule.pending.values().map(|scheduled| scheduled.fire_at).min();
+
+    (due, next)
+}
+
+/// Start the loop that shows scheduled notifications when they're due
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let (due, next) = take_due(&app);
+            for scheduled in due {
+                if let Err(e) =
+                    crate::notifications::notify(&app, &scheduled.title, &scheduled.body, None)
+                {
+                    log::warn!("Failed to show scheduled notification: {}", e);
+                }
+            }
+
+            let until_next = next
+                .map(|fire_at| (fire_at - Utc::now().timestamp_millis()).max(0) as u64)
+                .map(Duration::from_millis)
+                .unwrap_or(MAX_TICK);
+            tokio::time::sleep(until_next.min(MAX_TICK)).await;
+        }
+    });
+}
+
+/// Schedule a notification for a wall-clock time in an IANA time zone
+///
+/// `at` is a local time such as `2026-03-29T09:00:00`; `timezone` defaults
+/// to the system zone and keeps following it if it changes.
+#[tauri::command]
+pub async fn schedule_notification<R: Runtime>(
+    app: AppHandle<R>,
+    title: String,
+    body: String,
+    at: String,
+    timezone: Option<String>,
+) -> Result<ScheduledNotification, String> {
+    let local_time = NaiveDateTime::parse_from_str(&at, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(&at, "%Y-%m-%dT%H:%M"))
+        .map_err(|e| format!("Invalid time {:?}: {}", at, e))?;
+    let tz = parse_timezone(timezone.as_deref())?;
+    let fire_at = resolve(local_time, tz).ok_or("Time doesn't exist in that time zone")?;
+    if fire_at <= Utc::now() {
+        return Err(format!("{} is in the past", at));
+    }
+
+    let state = app.state::<crate::AppState>();
+    let mut schedule = state.schedule.lock().unwrap();
+    schedule.next_id = schedule.next_id.wrapping_add(1);
+    let scheduled = ScheduledNotification {
+        id: schedule.next_id,
+        title,
+        body,
+        local_time,
+        timezone,
+        fire_at: fire_at.timestamp_millis(),
+    };
+    schedule.pending.insert(scheduled.id, scheduled.clone());
+
+    Ok(scheduled)
+}
+
+/// Cancel a scheduled notification
+#[tauri::command]
+pub fn cancel_scheduled_notification<R: Runtime>(app: AppHandle<R>, id: u32) -> bool {
+    app.state::<crate::AppState>()
+        .schedule
+        .lock()
+        .unwrap()
+        .pending
+        .remove(&id)
+        .is_some()
+}
+
+/// Get pending scheduled notifications, soonest first
+#[tauri::command]
+pub fn get_scheduled_notifications<R: Runtime>(app: AppHandle<R>) -> Vec<ScheduledNotification> {
+    let mut pending: Vec<ScheduledNotification> = app
+        .state::<crate::AppState>()
+        .schedule
+        .lock()
+        .unwrap()
+        .pending
+        .values()
+        .cloned()
+        .collect();
+    pending.sort_by_key(|scheduled| scheduled.fire_at);
+    pending
+}
+
+/// Get the IANA name of the system time zone, e.g. `Europe/Istanbul`
+#[tauri::command]
+pub fn get_system_timezone() -> Result<String, String> {
+    iana_time_zone::get_timezone().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").unwrap()
+    }
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        local(time).and_utc()
+    }
+
+    #[test]
+    fn resolves_ordinary_times() {
+        let resolved = resolve(local("2026-06-01T09:00"), chrono_tz::Europe::Berlin);
+        assert_eq!(resolved, Some(utc("2026-06-01T07:00")));
+    }
+
+    #[test]
+    fn moves_skipped_times_past_the_gap() {
+        // Clocks jump from 02:00 to 03:00 CEST
+        let resolved = resolve(local("2026-03-29T02:30"), chrono_tz::Europe::Berlin);
+        assert_eq!(resolved, Some(utc("2026-03-29T01:00")));
+    }
+
+    #[test]
+    fn uses_the_earlier_of_repeated_times() {
+        // 02:30 comes once in CEST and again in CET
+        let resolved = resolve(local("2026-10-25T02:30"), chrono_tz::Europe::Berlin);
+        assert_eq!(resolved, Some(utc("2026-10-25T00:30")));
+    }
+}
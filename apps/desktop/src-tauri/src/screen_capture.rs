@@ -0,0 +1,91 @@
+//! Screen capture detection for YULA Desktop
+//!
+//! Neither macOS nor Windows exposes a public API for "the screen is being
+//! shared", so detection is a best-effort heuristic: it looks for processes
+//! that only run while a capture is in progress (Zoom's share host, the
+//! macOS screenshot/recording UI, OBS). A background monitor emits
+//! `screen-capture-changed` whenever the result flips.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// How often the process list is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Processes whose presence indicates an active capture, lowercased
+#[cfg(target_os = "macos")]
+const CAPTURE_PROCESSES: &[&str] = &["cpthost", "screencaptureui", "obs"];
+#[cfg(target_os = "windows")]
+const CAPTURE_PROCESSES: &[&str] = &["cpthost.exe", "obs64.exe", "obs32.exe"];
+#[cfg(target_os = "linux")]
+const CAPTURE_PROCESSES: &[&str] = &["zoomsharescreen", "obs"];
+
+/// Check the process list for a known capture process
+fn detect() -> bool {
+    match running_process_names() {
+        Ok(names) => names
+            .iter()
+            .any(|name| CAPTURE_PROCESSES.contains(&name.as_str())),
+        Err(e) => {
+            log::debug!("Screen capture detection unavailable: {}", e);
+            false
+        }
+    }
+}
+
+fn running_process_names() -> Result<Vec<String>, String> {
+    #[cfg(unix)]
+    let output = crate::platform::command("ps")
+        .args(["-A", "-o", "comm="])
+        .output();
+    #[cfg(target_os = "windows")]
+    let output = crate::platform::command("tasklist")
+        .args(["/fo", "csv", "/nh"])
+        .output();
+
+    let output = output.map_err(|e| e.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            // tasklist prints `"name.exe","pid",...`; ps may print full paths
+            let name = line.split(',').next()?.trim().trim_matches('"');
+            let name = name.rsplit(['/', '\\']).next()?;
+            (!name.is_empty()).then(|| name.to_lowercase())
+        })
+        .collect())
+}
+
+/// Start the background monitor that emits `screen-capture-changed`
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let captured = tauri::async_runtime::spawn_blocking(detect)
+                .await
+                .unwrap_or(false);
+            let state = app.state::<crate::AppState>();
+            if state.screen_captured.swap(captured, Ordering::SeqCst) != captured {
+                log::info!("Screen capture state changed: {}", captured);
+                crate::events::emit(&app, crate::events::SCREEN_CAPTURE_CHANGED, captured);
+            }
+
+            crate::tasks::tick(&app, "screen-capture", POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Check whether the screen appears to be shared or recorded
+///
+/// Best effort: this only recognizes a few known capture tools by process
+/// name, so a capture by any other app, including a browser tab share,
+/// reads as `false`. Use `set_content_protection` to actually keep a window
+/// out of captures.
+#[tauri::command]
+pub fn is_screen_being_captured<R: Runtime>(app: AppHandle<R>) -> bool {
+    app.state::<crate::AppState>()
+        .screen_captured
+        .load(Ordering::SeqCst)
+}
@@ -0,0 +1,168 @@
+//! Window screenshots for YULA Desktop
+//!
+//! Captures one of our own windows as a PNG for bug reports, so nothing
+//! else on screen ends up in the attachment. Neither webview engine exposes
+//! a snapshot API through Tauri, so the OS capture tools are used, each
+//! given the window itself rather than a screen area so windows on top of
+//! it don't show: macOS `screencapture` with the window's id, `PrintWindow`
+//! on Windows, run from PowerShell to encode the PNG with System.Drawing,
+//! and ImageMagick `import` with the X11 window id. Wayland doesn't let apps
+//! read window contents at all.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::error::CommandError;
+
+/// Temporary file the capture tool writes to
+fn temp_file() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "yula-capture-{}-{}.png",
+        std::process::id(),
+        rand::random::<u32>()
+    ))
+}
+
+/// Run a capture tool, failing if it didn't produce the file
+fn run_tool(mut command: std::process::Command, output: &Path) -> Result<(), CommandError> {
+    let result = command
+        .output()
+        .map_err(|e| CommandError::Failed(format!("Failed to start capture: {}", e)))?;
+    if !result.status.success() || !output.exists() {
+        return Err(CommandError::Failed(format!(
+            "Capture failed: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn capture<R: Runtime>(window: &WebviewWindow<R>, output: &Path) -> Result<(), CommandError> {
+    use objc2::runtime::AnyObject;
+
+    if !crate::permissions::screen_recording_granted() {
+        return Err(CommandError::PermissionDenied(
+            "Screen recording access is needed to capture the window".to_string(),
+        ));
+    }
+
+    // The window id is read on the main thread, where AppKit expects it
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as usize;
+    let (tx, rx) = std::sync::mpsc::channel();
+    window
+        .run_on_main_thread(move || unsafe {
+            let ns_window = ns_window as *mut AnyObject;
+            if let Some(ns_window) = ns_window.as_ref() {
+                let number: isize = objc2::msg_send![ns_window, windowNumber];
+                let _ = tx.send(number);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    let number = rx
+        .recv_timeout(std::time::Duration::from_secs(1))
+        .map_err(|_| CommandError::Failed("Window id unavailable".to_string()))?;
+
+    let mut command = crate::platform::command("screencapture");
+    command
+        .args(["-x", "-o", "-t", "png", "-l"])
+        .arg(number.to_string())
+        .arg(output);
+    run_tool(command, output)
+}
+
+#[cfg(target_os = "windows")]
+fn capture<R: Runtime>(window: &WebviewWindow<R>, output: &Path) -> Result<(), CommandError> {
+    let hwnd = window.hwnd().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+
+    // PW_RENDERFULLCONTENT (2) includes the WebView2 content, which is
+    // composited by DirectComposition and missing from a plain PrintWindow.
+    // PowerShell must be DPI aware to see the physical pixels Tauri reports.
+    let script = format!(
+        "Add-Type -AssemblyName System.Drawing; \
+         Add-Type -Name Win -Namespace Yula -MemberDefinition \
+         '[DllImport(\"user32.dll\")] public static extern bool SetProcessDPIAware(); \
+         [DllImport(\"user32.dll\")] public static extern bool PrintWindow(\
+         IntPtr hwnd, IntPtr hdc, uint flags);'; \
+         [void][Yula.Win]::SetProcessDPIAware(); \
+         $bitmap = New-Object System.Drawing.Bitmap {w}, {h}; \
+         $graphics = [System.Drawing.Graphics]::FromImage($bitmap); \
+         $hdc = $graphics.GetHdc(); \
+         $printed = [Yula.Win]::PrintWindow([IntPtr]{hwnd}, $hdc, 2); \
+         $graphics.ReleaseHdc($hdc); \
+         if (-not $printed) {{ exit 1 }}; \
+         $bitmap.Save('{path}', [System.Drawing.Imaging.ImageFormat]::Png)",
+        w = size.width,
+        h = size.height,
+        hwnd = hwnd.0 as isize,
+        path = output.display().to_string().replace('\'', "''"),
+    );
+    let mut command = crate::platform::command(crate::platform::powershell());
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    run_tool(command, output)
+}
+
+#[cfg(target_os = "linux")]
+fn capture<R: Runtime>(window: &WebviewWindow<R>, output: &Path) -> Result<(), CommandError> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    let handle = window.window_handle().map_err(|e| e.to_string())?;
+    let xid = match handle.as_raw() {
+        RawWindowHandle::Xlib(handle) => format!("0x{:x}", handle.window),
+        RawWindowHandle::Xcb(handle) => format!("0x{:x}", handle.window.get()),
+        _ => {
+            return Err(CommandError::NotSupported(
+                "Wayland doesn't allow capturing window contents".to_string(),
+            ))
+        }
+    };
+    let mut command = crate::platform::command("import");
+    command
+        .args(["-silent", "-window"])
+        .arg(xid)
+        .arg(format!("png:{}", output.display()));
+    run_tool(command, output)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn capture<R: Runtime>(_window: &WebviewWindow<R>, _output: &Path) -> Result<(), CommandError> {
+    Err(CommandError::NotSupported(
+        "Window capture is not supported on this platform".to_string(),
+    ))
+}
+
+/// Capture one of our windows as a base64-encoded PNG
+///
+/// The window must be visible. Fails with `PermissionDenied` on macOS
+/// until screen recording access is granted.
+#[tauri::command]
+pub async fn capture_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<String, CommandError> {
+    use base64::Engine;
+
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| CommandError::NotFound(format!("Window {}", label)))?;
+    let visible = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+    if !visible {
+        return Err(CommandError::Failed(format!("Window {} is not visible", label)));
+    }
+
+    let output = temp_file();
+    let result = tauri::async_runtime::spawn_blocking({
+        let output = output.clone();
+        move || capture(&window, &output)
+    })
+    .await
+    .map_err(|e| CommandError::Failed(e.to_string()))
+    .and_then(|result| result)
+    .and_then(|()| std::fs::read(&output).map_err(|e| CommandError::Failed(e.to_string())));
+    let _ = std::fs::remove_file(&output);
+
+    let png = result?;
+    log::info!("Captured window {} ({} bytes)", label, png.len());
+    Ok(base64::engine::general_purpose::STANDARD.encode(png))
+}
@@ -0,0 +1,212 @@
+//! System-wide text selection for YULA Desktop
+//!
+//! Reads the text selected in the frontmost app. macOS asks the focused
+//! element through the accessibility API, which needs the accessibility
+//! permission. Linux reads the X11/Wayland primary selection. Where neither
+//! works, a copy shortcut is simulated and the clipboard read, restoring the
+//! user's previous clipboard text afterwards. Clipboard contents other than
+//! text, such as images or files, can't be read back and are lost: the
+//! clipboard is cleared instead.
+
+use tauri::{AppHandle, Runtime};
+
+use crate::error::CommandError;
+
+/// How long to wait for the frontmost app to handle the simulated copy
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const COPY_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Simulate a copy and read the result, restoring the clipboard afterwards
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+async fn copy_selection<R: Runtime>(app: &AppHandle<R>) -> Result<Option<String>, CommandError> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    crate::plugins::require(app, crate::plugins::CLIPBOARD)?;
+
+    // `None` for an empty clipboard or one holding something other than text
+    let previous = app.clipboard().read_text().ok();
+
+    let copied = tauri::async_runtime::spawn_blocking(send_copy_shortcut)
+        .await
+        .map_err(|e| CommandError::Failed(e.to_string()))?;
+    let selected = match copied {
+        Ok(()) => {
+            tokio::time::sleep(COPY_DELAY).await;
+            // An unchanged clipboard means nothing was selected
+            app.clipboard()
+                .read_text()
+                .ok()
+                .filter(|text| !text.is_empty() && Some(text) != previous.as_ref())
+        }
+        Err(e) => {
+            log::warn!("Failed to simulate copy: {}", e);
+            None
+        }
+    };
+
+    // Don't leave the selection behind where the previous contents can't
+    // be put back
+    if selected.is_some() {
+        let restored = match previous {
+            Some(previous) => app.clipboard().write_text(previous),
+            None => app.clipboard().clear(),
+        };
+        if let Err(e) = restored {
+            log::warn!("Failed to restore clipboard: {}", e);
+        }
+    }
+
+    Ok(selected)
+}
+
+#[cfg(target_os = "macos")]
+fn send_copy_shortcut() -> Result<(), String> {
+    let status = crate::platform::command("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to keystroke \"c\" using command down",
+        ])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("osascript exited with {}", status))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn send_copy_shortcut() -> Result<(), String> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        VIRTUAL_KEY, VK_C, VK_CONTROL,
+    };
+
+    let key = |vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+    let inputs = [
+        key(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+        key(VK_C, KEYBD_EVENT_FLAGS(0)),
+        key(VK_C, KEYEVENTF_KEYUP),
+        key(VK_CONTROL, KEYEVENTF_KEYUP),
+    ];
+
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize == inputs.len() {
+        Ok(())
+    } else {
+        Err("Input was blocked by another application".to_string())
+    }
+}
+
+/// Get the text selected in the frontmost app, if any
+///
+/// Returns `PermissionDenied` on macOS until the accessibility permission is
+/// granted. Where the selection has to be copied, non-text clipboard
+/// contents are lost.
+#[tauri::command]
+pub async fn get_selected_text<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<String>, CommandError> {
+    #[cfg(target_os = "macos")]
+    {
+        if !crate::permissions::accessibility_granted() {
+            return Err(CommandError::PermissionDenied(
+                "Accessibility permission is required to read the selection".to_string(),
+            ));
+        }
+
+        let selected = tauri::async_runtime::spawn_blocking(macos::focused_selection)
+            .await
+            .map_err(|e| CommandError::Failed(e.to_string()))?;
+        match selected {
+            Some(text) => Ok(Some(text)),
+            // Some apps (e.g. Electron) don't expose selections through AX
+            None => copy_selection(&app).await,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        copy_selection(&app).await
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = app;
+        let selected = tauri::async_runtime::spawn_blocking(|| {
+            crate::platform::output("wl-paste", &["--primary", "--no-newline"])
+                .or_else(|| crate::platform::output("xclip", &["-o", "-selection", "primary"]))
+                .or_else(|| crate::platform::output("xsel", &["--primary", "--output"]))
+        })
+        .await
+        .map_err(|e| CommandError::Failed(e.to_string()))?;
+        Ok(selected.filter(|text| !text.is_empty()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_foundation::NSString;
+    use std::ffi::c_void;
+
+    type AXUIElementRef = *const c_void;
+    type CFTypeRef = *const c_void;
+
+    /// `kAXErrorSuccess`
+    const AX_SUCCESS: i32 = 0;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+        fn AXUIElementCopyAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFTypeRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// Copy an attribute of an element; the caller owns the returned value
+    unsafe fn copy_attribute(element: AXUIElementRef, name: &str) -> Option<CFTypeRef> {
+        // NSString is toll-free bridged with CFString
+        let attribute = NSString::from_str(name);
+        let mut value: CFTypeRef = std::ptr::null();
+        let result = AXUIElementCopyAttributeValue(
+            element,
+            &*attribute as *const NSString as CFTypeRef,
+            &mut value,
+        );
+        (result == AX_SUCCESS && !value.is_null()).then_some(value)
+    }
+
+    /// Selected text of the focused element, if it exposes one
+    pub fn focused_selection() -> Option<String> {
+        unsafe {
+            let system = AXUIElementCreateSystemWide();
+            let focused = copy_attribute(system, "AXFocusedUIElement");
+            CFRelease(system);
+            let focused = focused?;
+
+            let selected = copy_attribute(focused, "AXSelectedText");
+            CFRelease(focused);
+            let selected = selected?;
+
+            let text = (*(selected as *const NSString)).to_string();
+            CFRelease(selected);
+            (!text.is_empty()).then_some(text)
+        }
+    }
+}
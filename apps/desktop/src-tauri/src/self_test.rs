@@ -0,0 +1,234 @@
+//! Subsystem self-test for YULA Desktop
+//!
+//! `run_self_test` checks the pieces users most often report as broken:
+//! notification permission, updater reachability, the clipboard, the
+//! settings file and the OS keychain. Background runs only read the
+//! clipboard; a run the user asked for also writes a probe to it, when it
+//! holds text, which is restored afterwards. When periodic testing is
+//! enabled the test also runs in the background, and `self-test-failed` is
+//! emitted with the checks that passed last time but fail now.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// How often the periodic self-test runs
+const TEST_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Keychain entry written and removed by the keychain check
+const KEYCHAIN_PROBE: &str = "self-test";
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+    /// The check couldn't run without side effects
+    Skipped,
+}
+
+/// Result of a single subsystem check
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: Option<String>,
+}
+
+/// Results of a full self-test run
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    /// Whether no check failed
+    pub passed: bool,
+    pub checks: Vec<SelfTestCheck>,
+    /// When the test ran, Unix millis
+    pub ran_at: i64,
+}
+
+fn check(name: &str, result: Result<Option<String>, String>) -> SelfTestCheck {
+    let (status, message) = match result {
+        Ok(message) => (CheckStatus::Passed, message),
+        Err(message) => (CheckStatus::Failed, Some(message)),
+    };
+    SelfTestCheck {
+        name: name.to_string(),
+        status,
+        message,
+    }
+}
+
+fn check_notifications<R: Runtime>(app: &AppHandle<R>) -> SelfTestCheck {
+    use crate::permissions::PermissionState;
+
+    let result = match crate::permissions::notification_state(app) {
+        PermissionState::Granted => Ok(None),
+        PermissionState::NotApplicable => Err("Notification plugin unavailable".to_string()),
+        state => Err(format!("Notification permission is {:?}", state)),
+    };
+    check("notifications", result)
+}
+
+async fn check_updater<R: Runtime>(app: &AppHandle<R>) -> SelfTestCheck {
+    check("updater", updater_reachable(app).await)
+}
+
+async fn updater_reachable<R: Runtime>(app: &AppHandle<R>) -> Result<Option<String>, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    crate::plugins::require(app, crate::plugins::UPDATER)?;
+    let update = app
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| format!("Update server unreachable: {}", e))?;
+    Ok(update.map(|update| format!("Update {} available", update.version)))
+}
+
+/// Check the clipboard, writing to it only if `round_trip`, as clipboard
+/// managers would record the probe
+fn check_clipboard<R: Runtime>(app: &AppHandle<R>, round_trip: bool) -> SelfTestCheck {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    if let Err(e) = crate::plugins::require(app, crate::plugins::CLIPBOARD) {
+        return check("clipboard", Err(e));
+    }
+
+    // Non-text contents can't be restored, so leave them alone
+    let Ok(previous) = app.clipboard().read_text() else {
+        return SelfTestCheck {
+            name: "clipboard".to_string(),
+            status: CheckStatus::Skipped,
+            message: Some("Clipboard holds non-text content".to_string()),
+        };
+    };
+    if !round_trip {
+        return check("clipboard", Ok(None));
+    }
+
+    let probe = format!("yula-self-test-{}", chrono::Utc::now().timestamp_millis());
+    let result = app
+        .clipboard()
+        .write_text(&probe)
+        .and_then(|_| app.clipboard().read_text())
+        .map_err(|e| e.to_string())
+        .and_then(|read| {
+            if read == probe {
+                Ok(None)
+            } else {
+                Err("Clipboard contents didn't round-trip".to_string())
+            }
+        });
+    if let Err(e) = app.clipboard().write_text(previous) {
+        log::warn!("Failed to restore clipboard after self-test: {}", e);
+    }
+
+    check("clipboard", result)
+}
+
+fn check_settings<R: Runtime>(app: &AppHandle<R>) -> SelfTestCheck {
+    let validation = crate::settings::validate_settings(app.clone());
+    let result = match validation.error {
+        None => Ok(None),
+        Some(error) => Err(error),
+    };
+    check("settings", result)
+}
+
+fn check_keychain<R: Runtime>(app: &AppHandle<R>) -> SelfTestCheck {
+    let probe = chrono::Utc::now().timestamp_millis().to_string();
+    let result = crate::keychain::set(app, KEYCHAIN_PROBE, &probe)
+        .and_then(|_| crate::keychain::get(app, KEYCHAIN_PROBE))
+        .and_then(|read| {
+            if read.as_deref() == Some(probe.as_str()) {
+                Ok(None)
+            } else {
+                Err("Keychain entry didn't round-trip".to_string())
+            }
+        });
+    let _ = crate::keychain::delete(app, KEYCHAIN_PROBE);
+
+    check("keychain", result)
+}
+
+/// Run every check and remember the report; `manual` runs were asked for
+/// by the user
+async fn run<R: Runtime>(app: &AppHandle<R>, manual: bool) -> SelfTestReport {
+    let mut checks = vec![check_notifications(app), check_updater(app).await];
+
+    let handle = app.clone();
+    let blocking = tauri::async_runtime::spawn_blocking(move || {
+        vec![
+            check_clipboard(&handle, manual),
+            check_settings(&handle),
+            check_keychain(&handle),
+        ]
+    })
+    .await;
+    match blocking {
+        Ok(results) => checks.extend(results),
+        Err(e) => checks.push(check("self-test", Err(e.to_string()))),
+    }
+
+    let report = SelfTestReport {
+        passed: checks.iter().all(|check| check.status != CheckStatus::Failed),
+        checks,
+        ran_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let previous = app
+        .state::<crate::AppState>()
+        .self_test
+        .lock()
+        .unwrap()
+        .replace(report.clone());
+    if let Some(previous) = previous {
+        let regressed: Vec<SelfTestCheck> = report
+            .checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Failed)
+            .filter(|check| {
+                previous
+                    .checks
+                    .iter()
+                    .any(|old| old.name == check.name && old.status == CheckStatus::Passed)
+            })
+            .cloned()
+            .collect();
+        if !regressed.is_empty() {
+            log::warn!("Self-test regressions: {:?}", regressed);
+            crate::events::emit(app, crate::events::SELF_TEST_FAILED, regressed);
+        }
+    }
+
+    report
+}
+
+/// Start the background task running the self-test periodically when enabled
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            crate::tasks::tick(&app, "self-test", TEST_INTERVAL).await;
+            if crate::settings::get(&app).periodic_self_test {
+                run(&app, false).await;
+            }
+        }
+    });
+}
+
+/// Check that critical subsystems work
+#[tauri::command]
+pub async fn run_self_test<R: Runtime>(app: AppHandle<R>) -> SelfTestReport {
+    run(&app, true).await
+}
+
+/// Enable or disable running the self-test in the background
+#[tauri::command]
+pub async fn set_periodic_self_test<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.periodic_self_test = enabled)
+}
@@ -0,0 +1,98 @@
+//! Serial and USB device enumeration for YULA Desktop
+//!
+//! Lists connected serial ports for the hardware integration flow. Opening
+//! ports isn't supported yet. The port list is polled and
+//! `serial-devices-changed` is emitted with the new list whenever a device
+//! is added or removed.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+/// How often connected devices are checked
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Kind of device behind a serial port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PortType {
+    Usb,
+    Pci,
+    Bluetooth,
+    Unknown,
+}
+
+/// A connected serial port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SerialPortInfo {
+    pub port_name: String,
+    #[serde(rename = "type")]
+    pub port_type: PortType,
+    pub usb_vid: Option<u16>,
+    pub usb_pid: Option<u16>,
+    pub manufacturer: Option<String>,
+}
+
+impl From<serialport::SerialPortInfo> for SerialPortInfo {
+    fn from(info: serialport::SerialPortInfo) -> Self {
+        let (port_type, usb) = match info.port_type {
+            serialport::SerialPortType::UsbPort(usb) => (PortType::Usb, Some(usb)),
+            serialport::SerialPortType::PciPort => (PortType::Pci, None),
+            serialport::SerialPortType::BluetoothPort => (PortType::Bluetooth, None),
+            serialport::SerialPortType::Unknown => (PortType::Unknown, None),
+        };
+
+        Self {
+            port_name: info.port_name,
+            port_type,
+            usb_vid: usb.as_ref().map(|usb| usb.vid),
+            usb_pid: usb.as_ref().map(|usb| usb.pid),
+            manufacturer: usb.and_then(|usb| usb.manufacturer),
+        }
+    }
+}
+
+fn available_ports() -> Result<Vec<SerialPortInfo>, String> {
+    let mut ports: Vec<SerialPortInfo> = serialport::available_ports()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(SerialPortInfo::from)
+        .collect();
+    ports.sort_by(|a, b| a.port_name.cmp(&b.port_name));
+    Ok(ports)
+}
+
+/// Start the background monitor that emits `serial-devices-changed`
+pub fn start_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut known: Option<Vec<SerialPortInfo>> = None;
+        loop {
+            let ports = tauri::async_runtime::spawn_blocking(available_ports).await;
+            match ports {
+                Ok(Ok(ports)) => {
+                    if known.as_ref().is_some_and(|known| *known != ports) {
+                        log::info!("Serial devices changed: {} connected", ports.len());
+                        crate::events::emit(
+                            &app,
+                            crate::events::SERIAL_DEVICES_CHANGED,
+                            ports.clone(),
+                        );
+                    }
+                    known = Some(ports);
+                }
+                Ok(Err(e)) => log::debug!("Serial port enumeration failed: {}", e),
+                Err(e) => log::debug!("Serial port enumeration panicked: {}", e),
+            }
+
+            crate::tasks::tick(&app, "serial-devices", POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// List connected serial ports
+#[tauri::command]
+pub async fn list_serial_ports() -> Result<Vec<SerialPortInfo>, String> {
+    tauri::async_runtime::spawn_blocking(available_ports)
+        .await
+        .map_err(|e| e.to_string())?
+}
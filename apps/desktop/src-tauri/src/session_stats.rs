@@ -0,0 +1,166 @@
+//! Local usage statistics for YULA Desktop
+//!
+//! Counts launches and total time the app has been running, for the usage
+//! dashboard. The counters are kept in `session_stats.json` in the profile's
+//! data directory and never leave the device. Each launch increments the
+//! session count; the session's runtime is added on a graceful quit, so a
+//! crash loses only the runtime of that session.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// File in the profile's data directory holding the counters
+const STATS_FILE: &str = "session_stats.json";
+
+/// Counters persisted across launches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StoredStats {
+    total_sessions: u32,
+    total_runtime_secs: u64,
+}
+
+/// The running session
+#[derive(Debug)]
+pub struct Session {
+    started: Instant,
+    /// Unix millis at launch
+    started_at: i64,
+    /// Start of the runtime not yet added to the stored total
+    unrecorded_since: Instant,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            started_at: chrono::Utc::now().timestamp_millis(),
+            unrecorded_since: now,
+        }
+    }
+}
+
+/// Usage statistics, including the running session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStats {
+    pub app_uptime_secs: u64,
+    /// Uptime for display, e.g. `2h 5m`
+    pub app_uptime_formatted: String,
+    /// When this session started, Unix millis
+    pub session_start: i64,
+    pub total_sessions: u32,
+    pub total_runtime_secs: u64,
+}
+
+fn stats_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    crate::profiles::data_dir(app).map(|dir| dir.join(STATS_FILE))
+}
+
+fn read<R: Runtime>(app: &AppHandle<R>) -> Result<StoredStats, String> {
+    let path = stats_path(app)?;
+    if !path.exists() {
+        return Ok(StoredStats::default());
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {}", STATS_FILE, e))
+}
+
+fn write<R: Runtime>(app: &AppHandle<R>, stats: &StoredStats) -> Result<(), String> {
+    let path = stats_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(stats).map_err(|e| e.to_string())?;
+    crate::settings::write_atomic(&path, contents)
+}
+
+/// Format a duration as its two largest units, e.g. `1d 3h` or `4m 12s`
+fn format_duration(secs: u64) -> String {
+    let units = [
+        ("d", secs / 86_400),
+        ("h", secs / 3_600 % 24),
+        ("m", secs / 60 % 60),
+        ("s", secs % 60),
+    ];
+    let first = units.iter().position(|(_, n)| *n > 0).unwrap_or(3);
+    units[first..]
+        .iter()
+        .take(2)
+        .map(|(unit, n)| format!("{}{}", n, unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Start the session clock and count this launch
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    *app.state::<crate::AppState>().session.lock().unwrap() = Session::default();
+
+    let result = read(app).and_then(|mut stats| {
+        stats.total_sessions = stats.total_sessions.saturating_add(1);
+        write(app, &stats)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to count session: {}", e);
+    }
+}
+
+/// Add this session's runtime to the stored total, e.g. on quit
+pub fn record_runtime<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<crate::AppState>() else {
+        return;
+    };
+    let elapsed = {
+        let mut session = state.session.lock().unwrap();
+        let elapsed = session.unrecorded_since.elapsed().as_secs();
+        session.unrecorded_since = Instant::now();
+        elapsed
+    };
+
+    let result = read(app).and_then(|mut stats| {
+        stats.total_runtime_secs = stats.total_runtime_secs.saturating_add(elapsed);
+        write(app, &stats)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to record session runtime: {}", e);
+    }
+}
+
+/// Get the uptime of this session and the counters across launches
+///
+/// The total runtime includes the running session.
+#[tauri::command]
+pub fn get_session_stats<R: Runtime>(app: AppHandle<R>) -> Result<SessionStats, String> {
+    let stored = read(&app)?;
+    let state = app.state::<crate::AppState>();
+    let session = state.session.lock().unwrap();
+
+    let app_uptime_secs = session.started.elapsed().as_secs();
+    Ok(SessionStats {
+        app_uptime_secs,
+        app_uptime_formatted: format_duration(app_uptime_secs),
+        session_start: session.started_at,
+        total_sessions: stored.total_sessions,
+        total_runtime_secs: stored
+            .total_runtime_secs
+            .saturating_add(session.unrecorded_since.elapsed().as_secs()),
+    })
+}
+
+/// Clear the counters, keeping the running session as the first one
+#[tauri::command]
+pub fn reset_session_stats<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    state.session.lock().unwrap().unrecorded_since = Instant::now();
+
+    let stats = StoredStats {
+        total_sessions: 1,
+        total_runtime_secs: 0,
+    };
+    write(&app, &stats)?;
+
+    log::info!("Session stats reset");
+    Ok(())
+}
@@ -0,0 +1,605 @@
+//! Persistent settings for YULA Desktop
+//!
+//! Settings are stored as JSON in the app config directory. They are loaded
+//! once in `setup` and cached in `AppState`; every change is written back to
+//! disk immediately. A file that fails to parse is moved aside to
+//! `settings.bak` and replaced with defaults.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::appearance::AppearanceMode;
+use crate::auto_lock::AutoLockSettings;
+use crate::clipboard::ClipboardPollStrategy;
+use crate::commands::ActivationPolicy;
+use crate::effects::WindowEffects;
+use crate::error::CommandError;
+use crate::shortcuts::ShortcutBinding;
+use crate::tray::TrayClickBehavior;
+use crate::window::WindowPolicy;
+
+const SETTINGS_FILE: &str = "settings.json";
+const BACKUP_FILE: &str = "settings.bak";
+
+/// Fields that describe this machine rather than the user's preferences,
+/// left out of exports
+const MACHINE_SPECIFIC_FIELDS: &[&str] = &[
+    "hardware_acceleration",
+    "emulation_warning_shown",
+    "update_download_dir",
+    "kiosk_display",
+];
+
+/// File format for exported settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Json,
+    /// Human-editable, with each field's default noted in a comment
+    Toml,
+}
+
+/// Failure reading the settings file
+enum SettingsError {
+    /// The file couldn't be read
+    Io(String),
+    /// The file was read but isn't valid settings JSON
+    Corrupt(String),
+}
+
+/// Result of validating the settings file
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsValidation {
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+/// Payload of the `settings-reset` event
+#[derive(Debug, Clone, Serialize)]
+struct SettingsResetPayload {
+    reason: String,
+    backup_path: Option<String>,
+}
+
+/// User preferences persisted across launches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Custom global shortcut bindings
+    pub shortcuts: Vec<ShortcutBinding>,
+    /// Whether the webview renders with GPU acceleration (applied at launch)
+    pub hardware_acceleration: bool,
+    /// macOS activation policy (dock icon presence)
+    pub activation_policy: ActivationPolicy,
+    /// Background blur applied to the main window
+    pub window_effects: Option<WindowEffects>,
+    /// Opacity of the main window
+    pub window_opacity: f64,
+    /// Record emitted events for replay (advanced)
+    pub record_events: bool,
+    /// Custom webview user agent (applied at launch)
+    pub user_agent: Option<String>,
+    /// Extra CSP `connect-src` sources, e.g. alternate API hosts
+    pub csp_connect_sources: Vec<String>,
+    /// Reduce background activity while the window is hidden
+    pub background_throttle: bool,
+    /// App-level reduce-motion preference; `None` follows the OS
+    pub reduce_motion_override: Option<bool>,
+    /// Show the main window on every workspace / virtual desktop
+    pub visible_on_all_workspaces: bool,
+    /// Maximum notifications shown per minute; `None` disables the limit
+    pub notification_rate_limit: Option<u32>,
+    /// Summarize rate-limited notifications instead of dropping them silently
+    pub notification_summary: bool,
+    /// Seconds to collect rate-limited notifications into one digest for;
+    /// `None` drops them
+    pub notification_digest_secs: Option<u32>,
+    /// Deep-link path prefixes mapped to the window label handling them
+    pub deep_link_routes: BTreeMap<String, String>,
+    /// Webview memory in MiB above which a reload is suggested; `None` disables
+    pub webview_memory_limit_mb: Option<u64>,
+    /// Run the subsystem self-test in the background
+    pub periodic_self_test: bool,
+    /// Whether the "native version available" notice was shown
+    pub emulation_warning_shown: bool,
+    /// Allow tailing the OS logs from the frontend (advanced)
+    pub system_log_access: bool,
+    /// Whether secondary windows may be opened
+    pub window_policy: WindowPolicy,
+    /// Native light/dark appearance; `System` follows the OS
+    pub appearance: AppearanceMode,
+    /// Hand focus back to the previous app when our last window hides (macOS)
+    pub return_focus_on_hide: bool,
+    /// Where updates are downloaded; `None` uses the system temp directory
+    pub update_download_dir: Option<PathBuf>,
+    /// Lock after a period without input or after sleep
+    pub auto_lock: AutoLockSettings,
+    /// Live in the tray without a dock icon, summoned by a hotkey
+    pub menu_bar_mode: bool,
+    /// Accelerator summoning the popover in menu bar mode
+    pub menu_bar_hotkey: String,
+    /// Refuse to install updates while the app's code signature is invalid
+    pub require_valid_signature: bool,
+    /// How the clipboard is watched for `clipboard-changed`
+    pub clipboard_poll_strategy: ClipboardPollStrategy,
+    /// Display kiosk mode is locked to; re-entered on launch while set
+    pub kiosk_display: Option<u32>,
+    /// What a left click on the tray icon does
+    pub tray_click_behavior: TrayClickBehavior,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            shortcuts: Vec::new(),
+            hardware_acceleration: true,
+            activation_policy: ActivationPolicy::Regular,
+            window_effects: None,
+            window_opacity: 1.0,
+            record_events: false,
+            user_agent: None,
+            csp_connect_sources: Vec::new(),
+            background_throttle: false,
+            reduce_motion_override: None,
+            visible_on_all_workspaces: false,
+            notification_rate_limit: Some(10),
+            notification_summary: true,
+            notification_digest_secs: None,
+            deep_link_routes: BTreeMap::new(),
+            webview_memory_limit_mb: Some(2048),
+            periodic_self_test: false,
+            emulation_warning_shown: false,
+            system_log_access: false,
+            window_policy: WindowPolicy::MultiWindow,
+            appearance: AppearanceMode::System,
+            return_focus_on_hide: true,
+            update_download_dir: None,
+            auto_lock: AutoLockSettings::default(),
+            menu_bar_mode: false,
+            menu_bar_hotkey: crate::menu_bar::DEFAULT_HOTKEY.to_string(),
+            require_valid_signature: false,
+            clipboard_poll_strategy: ClipboardPollStrategy::Off,
+            kiosk_display: None,
+            tray_click_behavior: TrayClickBehavior::Auto,
+        }
+    }
+}
+
+/// Resolve the path of the settings file
+pub fn settings_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    crate::profiles::config_dir(app).map(|dir| dir.join(SETTINGS_FILE))
+}
+
+/// Load settings from disk into `AppState`, falling back to defaults
+///
+/// A corrupt settings file is backed up and replaced with defaults rather
+/// than failing launch.
+pub fn load<R: Runtime>(app: &AppHandle<R>) {
+    let settings = match read(app) {
+        Ok(settings) => settings,
+        Err(SettingsError::Corrupt(e)) => {
+            log::error!("Settings file is corrupt: {}", e);
+            reset(app, &e)
+        }
+        Err(SettingsError::Io(e)) => {
+            log::warn!("Failed to load settings, using defaults: {}", e);
+            Settings::default()
+        }
+    };
+
+    store(app, settings);
+}
+
+/// Get a snapshot of the current settings
+pub fn get<R: Runtime>(app: &AppHandle<R>) -> Settings {
+    app.try_state::<crate::AppState>()
+        .map(|state| state.settings.lock().unwrap().clone())
+        .unwrap_or_default()
+}
+
+/// Apply a change to the settings and persist the result
+pub fn update<R: Runtime, F: FnOnce(&mut Settings)>(
+    app: &AppHandle<R>,
+    f: F,
+) -> Result<(), String> {
+    let state = app
+        .try_state::<crate::AppState>()
+        .ok_or("App state not available")?;
+
+    let snapshot = {
+        let mut settings = state.settings.lock().unwrap();
+        f(&mut settings);
+        settings.clone()
+    };
+
+    write(app, &snapshot)
+}
+
+fn read<R: Runtime>(app: &AppHandle<R>) -> Result<Settings, SettingsError> {
+    read_path(&settings_path(app).map_err(SettingsError::Io)?)
+}
+
+/// Read the settings of a profile before the app is built, defaults if
+/// they can't be read
+#[cfg(target_os = "linux")]
+pub fn read_at_launch(config_dir: &Path) -> Settings {
+    read_path(&config_dir.join(SETTINGS_FILE)).unwrap_or_default()
+}
+
+fn read_path(path: &Path) -> Result<Settings, SettingsError> {
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| SettingsError::Io(e.to_string()))?;
+    serde_json::from_str(&contents).map_err(|e| SettingsError::Corrupt(e.to_string()))
+}
+
+fn store<R: Runtime>(app: &AppHandle<R>, settings: Settings) {
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        *state.settings.lock().unwrap() = settings;
+    }
+}
+
+/// Back up the current settings file and replace it with defaults
+fn reset<R: Runtime>(app: &AppHandle<R>, reason: &str) -> Settings {
+    let backup_path = match backup(app) {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            log::warn!("Failed to back up settings: {}", e);
+            None
+        }
+    };
+
+    let defaults = Settings::default();
+    if let Err(e) = write(app, &defaults) {
+        log::warn!("Failed to write default settings: {}", e);
+    }
+
+    log::warn!("Settings reset to defaults: {}", reason);
+    // A reset at load happens before the page can listen
+    crate::launch::emit_when_ready(
+        app,
+        crate::events::SETTINGS_RESET,
+        SettingsResetPayload {
+            reason: reason.to_string(),
+            backup_path,
+        },
+    );
+
+    defaults
+}
+
+fn backup<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let path = settings_path(app)?;
+    let backup_path = path.with_file_name(BACKUP_FILE);
+    std::fs::copy(&path, &backup_path).map_err(|e| e.to_string())?;
+    Ok(backup_path)
+}
+
+fn write<R: Runtime>(app: &AppHandle<R>, settings: &Settings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+
+    let contents = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    write_atomic(&path, contents)
+}
+
+/// Replace a file's contents, writing to a temporary file first so a crash
+/// mid-write can't leave it truncated
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    std::fs::write(&tmp_path, contents).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
+
+/// Check whether the settings file on disk is valid
+#[tauri::command]
+pub fn validate_settings<R: Runtime>(app: AppHandle<R>) -> SettingsValidation {
+    match read(&app) {
+        Ok(_) => SettingsValidation {
+            valid: true,
+            error: None,
+        },
+        Err(SettingsError::Io(e)) | Err(SettingsError::Corrupt(e)) => SettingsValidation {
+            valid: false,
+            error: Some(e),
+        },
+    }
+}
+
+/// Back up the settings file and reset it to defaults
+#[tauri::command]
+pub fn repair_settings<R: Runtime>(app: AppHandle<R>) -> Settings {
+    let settings = reset(&app, "Repair requested");
+    store(&app, settings.clone());
+    settings
+}
+
+/// Settings as a JSON object without the machine-specific fields
+fn portable(settings: &Settings) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let mut value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    let object = value.as_object_mut().expect("settings serialize to an object");
+    for field in MACHINE_SPECIFIC_FIELDS {
+        object.remove(*field);
+    }
+    Ok(std::mem::take(object))
+}
+
+/// Drop `null` fields, which TOML can't express, at every level
+fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => serde_json::Value::Object(
+            object
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(without_nulls).collect())
+        }
+        value => value,
+    }
+}
+
+/// Render settings as TOML, each field preceded by its default
+///
+/// Plain values come first: in TOML, a key after a `[table]` header would
+/// belong to that table.
+fn to_toml(settings: serde_json::Map<String, serde_json::Value>) -> Result<String, String> {
+    let defaults = portable(&Settings::default())?;
+    let mut plain = String::new();
+    let mut tables = String::new();
+
+    for (key, value) in settings {
+        let default = match defaults.get(&key) {
+            Some(serde_json::Value::Null) | None => "unset".to_string(),
+            Some(default) => toml::Value::try_from(without_nulls(default.clone()))
+                .map(|default| default.to_string())
+                .map_err(|e| e.to_string())?,
+        };
+        let mut single = serde_json::Map::new();
+        single.insert(key, without_nulls(value));
+        let rendered = toml::to_string(&single).map_err(|e| e.to_string())?;
+
+        let section = if rendered.starts_with('[') {
+            &mut tables
+        } else {
+            &mut plain
+        };
+        section.push_str(&format!("# Default: {}\n{}\n", default, rendered));
+    }
+
+    Ok(format!(
+        "# YULA Desktop settings\n\
+         # Fields left out keep their current value when imported.\n\n{}{}",
+        plain, tables
+    ))
+}
+
+/// Write the current settings to a file, excluding machine-specific fields
+#[tauri::command]
+pub async fn export_settings<R: Runtime>(app: AppHandle<R>, path: String) -> Result<(), String> {
+    export_settings_format(app, path, ExportFormat::Json).await
+}
+
+/// Write the current settings as JSON or commented TOML
+///
+/// Machine-specific fields are left out, as with `export_settings`.
+#[tauri::command]
+pub async fn export_settings_format<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    format: ExportFormat,
+) -> Result<(), String> {
+    let settings = portable(&get(&app))?;
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?,
+        ExportFormat::Toml => to_toml(settings)?,
+    };
+    std::fs::write(&path, contents).map_err(|e| e.to_string())
+}
+
+/// Parse an imported settings file, choosing the format by extension and
+/// falling back to whichever parses
+fn parse_import(path: &str, contents: &str) -> Result<serde_json::Value, String> {
+    let from_toml = |contents: &str| {
+        toml::from_str::<toml::Value>(contents)
+            .map_err(|e| e.to_string())
+            .and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+    };
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("toml") => from_toml(contents),
+        Some("json") => serde_json::from_str(contents).map_err(|e| e.to_string()),
+        _ => serde_json::from_str(contents).or_else(|json_error| {
+            from_toml(contents).map_err(|_| format!("Neither JSON nor TOML: {}", json_error))
+        }),
+    }
+}
+
+/// Validate a settings file and merge it into the current settings
+///
+/// The file may be JSON or TOML, detected by extension or content. Every
+/// field is checked before anything is applied; unknown or malformed fields,
+/// and values their setters would refuse, reject the whole import with
+/// `InvalidSettings`. Fields applied at launch (shortcuts, user agent, ...)
+/// take effect after a restart.
+#[tauri::command]
+pub async fn import_settings<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<Settings, CommandError> {
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| CommandError::Failed(e.to_string()))?;
+    let imported = parse_import(&path, &contents).map_err(CommandError::Failed)?;
+    let imported = imported
+        .as_object()
+        .ok_or_else(|| CommandError::Failed("Settings file must contain an object".to_string()))?;
+
+    let settings = merge(&get(&app), imported)?;
+    write(&app, &settings).map_err(CommandError::Failed)?;
+    store(&app, settings.clone());
+
+    let fields: Vec<&String> = imported.keys().collect();
+    crate::events::emit(&app, crate::events::SETTINGS_IMPORTED, fields);
+
+    Ok(settings)
+}
+
+/// Run an imported field through the checks its setter applies, normalizing
+/// it in place
+fn check_field(key: &str, settings: &mut Settings) -> Result<(), String> {
+    let accelerator = |accelerator: &str| {
+        crate::shortcuts::parse(accelerator)
+            .map(drop)
+            .map_err(|e| e.to_string())
+    };
+
+    match key {
+        "shortcuts" => settings
+            .shortcuts
+            .iter()
+            .try_for_each(|binding| accelerator(&binding.accelerator)),
+        "window_opacity" => crate::effects::validate_opacity(settings.window_opacity),
+        "user_agent" => {
+            settings.user_agent = crate::window::normalize_user_agent(settings.user_agent.take())?;
+            Ok(())
+        }
+        "csp_connect_sources" => crate::csp::validate_sources(&settings.csp_connect_sources),
+        "notification_rate_limit" => {
+            crate::notifications::validate_rate_limit(settings.notification_rate_limit)
+        }
+        "notification_digest_secs" => settings
+            .notification_digest_secs
+            .map_or(Ok(()), crate::notifications::validate_digest_secs),
+        "deep_link_routes" => {
+            settings.deep_link_routes = std::mem::take(&mut settings.deep_link_routes)
+                .into_iter()
+                .map(|(prefix, label)| {
+                    crate::deep_link::normalize_route(&prefix, &label).map(|prefix| (prefix, label))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(())
+        }
+        "webview_memory_limit_mb" => {
+            crate::memory::validate_limit(settings.webview_memory_limit_mb)
+        }
+        "auto_lock" => crate::auto_lock::validate_idle_minutes(settings.auto_lock.idle_minutes),
+        "menu_bar_hotkey" => accelerator(&settings.menu_bar_hotkey),
+        _ => Ok(()),
+    }
+}
+
+/// Merge imported fields over `current`, collecting every invalid field
+fn merge(
+    current: &Settings,
+    imported: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Settings, CommandError> {
+    let mut merged =
+        serde_json::to_value(current).map_err(|e| CommandError::Failed(e.to_string()))?;
+    let known = merged.as_object_mut().expect("settings serialize to an object");
+
+    let mut bad_fields = Vec::new();
+    for (key, value) in imported {
+        if !known.contains_key(key) || MACHINE_SPECIFIC_FIELDS.contains(&key.as_str()) {
+            bad_fields.push(key.clone());
+            continue;
+        }
+
+        // Deserialize the field on its own so each bad field is reported
+        let mut single = serde_json::Map::new();
+        single.insert(key.clone(), value.clone());
+        let Ok(mut field) = serde_json::from_value::<Settings>(serde_json::Value::Object(single))
+        else {
+            bad_fields.push(key.clone());
+            continue;
+        };
+        if let Err(e) = check_field(key, &mut field) {
+            log::warn!("Rejected imported setting {}: {}", key, e);
+            bad_fields.push(key.clone());
+            continue;
+        }
+
+        // Store the field as its setter would, normalized
+        let mut field =
+            serde_json::to_value(field).map_err(|e| CommandError::Failed(e.to_string()))?;
+        known.insert(key.clone(), field[key.as_str()].take());
+    }
+
+    if !bad_fields.is_empty() {
+        return Err(CommandError::InvalidSettings(bad_fields));
+    }
+
+    serde_json::from_value(merged).map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(json: serde_json::Value) -> Result<Settings, CommandError> {
+        merge(&Settings::default(), json.as_object().unwrap())
+    }
+
+    fn rejected(json: serde_json::Value) -> Vec<String> {
+        match import(json) {
+            Err(CommandError::InvalidSettings(fields)) => fields,
+            other => panic!("expected InvalidSettings, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn applies_valid_fields() {
+        let settings = import(serde_json::json!({
+            "window_opacity": 0.5,
+            "csp_connect_sources": ["https://api.yula.dev"],
+        }))
+        .unwrap();
+        assert_eq!(settings.window_opacity, 0.5);
+        assert_eq!(settings.csp_connect_sources, ["https://api.yula.dev"]);
+    }
+
+    #[test]
+    fn rejects_fields_their_setters_would() {
+        let fields = rejected(serde_json::json!({
+            "csp_connect_sources": ["https://ok.dev", "https://a.dev; script-src *"],
+            "window_opacity": 2.0,
+            "notification_rate_limit": 0,
+            "menu_bar_hotkey": "NotAKey+",
+            "record_events": true,
+        }));
+        assert_eq!(
+            fields,
+            [
+                "csp_connect_sources",
+                "menu_bar_hotkey",
+                "notification_rate_limit",
+                "window_opacity"
+            ]
+        );
+    }
+
+    #[test]
+    fn normalizes_deep_link_routes() {
+        let settings = import(serde_json::json!({
+            "deep_link_routes": { "/chat/": "chat" },
+        }))
+        .unwrap();
+        assert_eq!(settings.deep_link_routes["chat"], "chat");
+
+        let fields = rejected(serde_json::json!({ "deep_link_routes": { "//": "chat" } }));
+        assert_eq!(fields, ["deep_link_routes"]);
+    }
+}
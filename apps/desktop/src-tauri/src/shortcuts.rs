@@ -0,0 +1,312 @@
+//! Custom global shortcuts for YULA Desktop
+//!
+//! Users can bind accelerators to frontend actions. When a bound shortcut is
+//! pressed, a `global-shortcut` event carrying the action id is emitted.
+//! Bindings are persisted in settings and restored at startup.
+//!
+//! Local shortcuts fire only while the main window has focus, so app-only
+//! keybindings don't take accelerators away from other apps. They are
+//! registered with the OS while the main window is focused and released
+//! when it loses focus, and emit `local-shortcut` instead. Local bindings
+//! live only in memory; the frontend registers them when it loads.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::error::CommandError;
+
+/// A shortcut bound to a frontend action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutBinding {
+    pub accelerator: String,
+    pub action_id: String,
+}
+
+/// Payload of the `global-shortcut` and `local-shortcut` events
+#[derive(Debug, Clone, Serialize)]
+struct ShortcutPayload {
+    action_id: String,
+}
+
+/// Whether the main window has focus, which local shortcuts require
+fn main_focused<R: Runtime>(app: &AppHandle<R>) -> bool {
+    crate::window::main_window(app)
+        .and_then(|window| window.is_focused().map_err(|e| e.to_string()))
+        .unwrap_or(false)
+}
+
+/// Handle a shortcut press forwarded by the global-shortcut plugin
+pub fn handle_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    if crate::menu_bar::is_hotkey(app, shortcut.id()) {
+        if let Err(e) = crate::menu_bar::toggle(app) {
+            log::warn!("Failed to toggle popover: {}", e);
+        }
+        return;
+    }
+
+    let local = app.try_state::<crate::AppState>().and_then(|state| {
+        state
+            .local_shortcuts
+            .lock()
+            .unwrap()
+            .get(&shortcut.id())
+            .map(|binding| binding.action_id.clone())
+    });
+    if let Some(action_id) = local {
+        // Focus may have moved on before the release reached us
+        if main_focused(app) {
+            log::info!("Local shortcut triggered: {}", action_id);
+            crate::events::emit(
+                app,
+                crate::events::LOCAL_SHORTCUT,
+                ShortcutPayload { action_id },
+            );
+        }
+        return;
+    }
+
+    let action_id = app.try_state::<crate::AppState>().and_then(|state| {
+        state
+            .shortcuts
+            .lock()
+            .unwrap()
+            .get(&shortcut.id())
+            .map(|binding| binding.action_id.clone())
+    });
+
+    if let Some(action_id) = action_id {
+        log::info!("Global shortcut triggered: {}", action_id);
+        crate::events::emit(
+            app,
+            crate::events::GLOBAL_SHORTCUT,
+            ShortcutPayload { action_id },
+        );
+    }
+}
+
+/// Re-register the bindings stored in settings
+pub fn restore<R: Runtime>(app: &AppHandle<R>) {
+    for binding in crate::settings::get(app).shortcuts {
+        if let Err(e) = bind(app, &binding.accelerator, &binding.action_id) {
+            log::warn!("Failed to restore shortcut {}: {}", binding.accelerator, e);
+        }
+    }
+}
+
+pub fn parse(accelerator: &str) -> Result<Shortcut, CommandError> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| CommandError::InvalidAccelerator(format!("{}: {}", accelerator, e)))
+}
+
+fn bind<R: Runtime>(
+    app: &AppHandle<R>,
+    accelerator: &str,
+    action_id: &str,
+) -> Result<(), CommandError> {
+    let shortcut = parse(accelerator)?;
+    let state = app.state::<crate::AppState>();
+
+    let taken = state.shortcuts.lock().unwrap().contains_key(&shortcut.id())
+        || state.local_shortcuts.lock().unwrap().contains_key(&shortcut.id())
+        || crate::menu_bar::is_hotkey(app, shortcut.id());
+    if taken || app.global_shortcut().is_registered(shortcut) {
+        return Err(CommandError::ShortcutAlreadyRegistered(accelerator.to_string()));
+    }
+
+    // Registration fails when another application already owns the accelerator
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| CommandError::ShortcutAlreadyRegistered(format!("{}: {}", accelerator, e)))?;
+
+    state.shortcuts.lock().unwrap().insert(
+        shortcut.id(),
+        ShortcutBinding {
+            accelerator: accelerator.to_string(),
+            action_id: action_id.to_string(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Register a global shortcut that emits `global-shortcut` with the action id
+#[tauri::command]
+pub async fn register_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    accelerator: String,
+    action_id: String,
+) -> Result<(), CommandError> {
+    crate::plugins::require(&app, crate::plugins::GLOBAL_SHORTCUT)?;
+    bind(&app, &accelerator, &action_id)?;
+
+    crate::settings::update(&app, |settings| {
+        settings.shortcuts.push(ShortcutBinding {
+            accelerator,
+            action_id,
+        });
+    })
+    .map_err(CommandError::from)
+}
+
+/// Unregister a previously registered global shortcut
+#[tauri::command]
+pub async fn unregister_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    accelerator: String,
+) -> Result<(), CommandError> {
+    crate::plugins::require(&app, crate::plugins::GLOBAL_SHORTCUT)?;
+    let shortcut = parse(&accelerator)?;
+
+    let removed = app
+        .state::<crate::AppState>()
+        .shortcuts
+        .lock()
+        .unwrap()
+        .remove(&shortcut.id());
+
+    if removed.is_none() {
+        return Err(CommandError::Failed(format!(
+            "Shortcut not registered: {}",
+            accelerator
+        )));
+    }
+
+    app.global_shortcut()
+        .unregister(shortcut)
+        .map_err(|e| CommandError::Failed(e.to_string()))?;
+
+    crate::settings::update(&app, |settings| {
+        settings.shortcuts.retain(|binding| {
+            parse(&binding.accelerator).map(|s| s.id()).ok() != Some(shortcut.id())
+        });
+    })
+    .map_err(CommandError::from)
+}
+
+/// List all registered global shortcut bindings
+#[tauri::command]
+pub fn list_shortcuts<R: Runtime>(app: AppHandle<R>) -> Vec<ShortcutBinding> {
+    app.state::<crate::AppState>()
+        .shortcuts
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect()
+}
+
+/// Hold or release the local shortcuts as the main window gains or loses focus
+pub fn on_focus_changed<R: Runtime>(window: &tauri::Window<R>, focused: bool) {
+    if window.label() != crate::window::MAIN_WINDOW {
+        return;
+    }
+    let app = window.app_handle();
+    if !crate::plugins::is_available(app, crate::plugins::GLOBAL_SHORTCUT) {
+        return;
+    }
+
+    let bindings: Vec<ShortcutBinding> = app
+        .state::<crate::AppState>()
+        .local_shortcuts
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect();
+    for binding in bindings {
+        let Ok(shortcut) = parse(&binding.accelerator) else {
+            continue;
+        };
+        let result = if focused {
+            app.global_shortcut().register(shortcut)
+        } else {
+            app.global_shortcut().unregister(shortcut)
+        };
+        if let Err(e) = result {
+            log::debug!("Failed to update local shortcut {}: {}", binding.accelerator, e);
+        }
+    }
+}
+
+/// Register a shortcut that emits `local-shortcut` while the main window has focus
+#[tauri::command]
+pub async fn register_local_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    accelerator: String,
+    action_id: String,
+) -> Result<(), CommandError> {
+    crate::plugins::require(&app, crate::plugins::GLOBAL_SHORTCUT)?;
+    let shortcut = parse(&accelerator)?;
+    let state = app.state::<crate::AppState>();
+
+    let taken = state.shortcuts.lock().unwrap().contains_key(&shortcut.id())
+        || state.local_shortcuts.lock().unwrap().contains_key(&shortcut.id())
+        || crate::menu_bar::is_hotkey(&app, shortcut.id());
+    if taken {
+        return Err(CommandError::ShortcutAlreadyRegistered(accelerator));
+    }
+
+    // Outside focus the OS registration waits for the window to be focused
+    if main_focused(&app) {
+        app.global_shortcut().register(shortcut).map_err(|e| {
+            CommandError::ShortcutAlreadyRegistered(format!("{}: {}", accelerator, e))
+        })?;
+    }
+
+    state.local_shortcuts.lock().unwrap().insert(
+        shortcut.id(),
+        ShortcutBinding {
+            accelerator,
+            action_id,
+        },
+    );
+    Ok(())
+}
+
+/// Unregister a shortcut registered with `register_local_shortcut`
+#[tauri::command]
+pub async fn unregister_local_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    accelerator: String,
+) -> Result<(), CommandError> {
+    crate::plugins::require(&app, crate::plugins::GLOBAL_SHORTCUT)?;
+    let shortcut = parse(&accelerator)?;
+
+    let removed = app
+        .state::<crate::AppState>()
+        .local_shortcuts
+        .lock()
+        .unwrap()
+        .remove(&shortcut.id());
+    if removed.is_none() {
+        return Err(CommandError::Failed(format!(
+            "Local shortcut not registered: {}",
+            accelerator
+        )));
+    }
+
+    if app.global_shortcut().is_registered(shortcut) {
+        app.global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| CommandError::Failed(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// List the shortcuts registered with `register_local_shortcut`
+#[tauri::command]
+pub fn list_local_shortcuts<R: Runtime>(app: AppHandle<R>) -> Vec<ShortcutBinding> {
+    app.state::<crate::AppState>()
+        .local_shortcuts
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,177 @@
+//! Code signature verification for YULA Desktop
+//!
+//! Checks at runtime that the installed app is still the one we signed,
+//! as a defense against modified or repackaged installs. macOS verifies the
+//! bundle with `codesign` and asks Gatekeeper (`spctl`) whether it was
+//! notarized; Windows checks the executable's Authenticode signature, which
+//! goes through `WinVerifyTrust`. Linux packages carry no signature we can
+//! check at runtime. The tools are run by absolute path, so nothing earlier
+//! on `PATH` can answer for them.
+//!
+//! A failed check emits `signature-invalid` in release builds (development
+//! builds are unsigned), and installing updates can be made to require a
+//! valid signature first.
+
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+use crate::error::CommandError;
+
+#[cfg(target_os = "macos")]
+const CODESIGN: &str = "/usr/bin/codesign";
+
+#[cfg(target_os = "macos")]
+const SPCTL: &str = "/usr/sbin/spctl";
+
+/// Result of checking the running app's signature
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureStatus {
+    pub signed: bool,
+    /// Signed and unmodified since signing
+    pub valid: bool,
+    /// Apple developer team that signed the bundle (macOS)
+    pub team_id: Option<String>,
+    /// Whether Gatekeeper accepts the bundle as notarized, `None` off macOS
+    pub notarized: Option<bool>,
+}
+
+/// The `.app` bundle containing the executable, or the executable itself
+#[cfg(target_os = "macos")]
+pub(crate) fn bundle_path() -> Result<std::path::PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    Ok(exe
+        .ancestors()
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .map(|path| path.to_path_buf())
+        .unwrap_or(exe))
+}
+
+#[cfg(target_os = "macos")]
+fn check() -> Result<SignatureStatus, CommandError> {
+    let bundle = bundle_path()?;
+
+    // `codesign -d` prints the signing details on stderr and fails when
+    // there's no signature at all
+    let details = crate::platform::command(CODESIGN)
+        .args(["--display", "--verbose=2"])
+        .arg(&bundle)
+        .output()
+        .map_err(|e| CommandError::Failed(format!("Failed to run codesign: {}", e)))?;
+    let signed = details.status.success();
+    let team_id = String::from_utf8_lossy(&details.stderr)
+        .lines()
+        .find_map(|line| line.strip_prefix("TeamIdentifier="))
+        .map(str::trim)
+        .filter(|team| *team != "not set")
+        .map(str::to_string);
+
+    let valid = signed
+        && crate::platform::command(CODESIGN)
+            .args(["--verify", "--deep", "--strict"])
+            .arg(&bundle)
+            .status()
+            .is_ok_and(|status| status.success());
+
+    // Gatekeeper names the source of an accepted bundle, e.g.
+    // `source=Notarized Developer ID`
+    let assessment = crate::platform::command(SPCTL)
+        .args(["--assess", "--type", "execute", "-vv"])
+        .arg(&bundle)
+        .output()
+        .ok()
+        .map(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stderr).contains("Notarized")
+        });
+
+    Ok(SignatureStatus {
+        signed,
+        valid,
+        team_id,
+        notarized: signed.then_some(assessment.unwrap_or(false)),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn check() -> Result<SignatureStatus, CommandError> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let script = format!(
+        "(Get-AuthenticodeSignature -LiteralPath '{}').Status",
+        exe.display().to_string().replace('\'', "''")
+    );
+    let status = crate::platform::output(
+        crate::platform::powershell(),
+        &["-NoProfile", "-NonInteractive", "-Command", &script],
+    )
+    .ok_or_else(|| CommandError::Failed("Failed to check the Authenticode signature".into()))?;
+
+    let (signed, valid) = match status.as_str() {
+        "Valid" => (true, true),
+        // There is a signature, but it doesn't hold up
+        "HashMismatch" | "NotTrusted" | "Incompatible" => (true, false),
+        // `NotSigned`, `UnknownError`, `NotSupportedFileFormat` or anything
+        // unexpected
+        _ => (false, false),
+    };
+    Ok(SignatureStatus {
+        signed,
+        valid,
+        team_id: None,
+        notarized: None,
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn check() -> Result<SignatureStatus, CommandError> {
+    Err(CommandError::NotSupported(
+        "Code signatures can't be verified on this platform".to_string(),
+    ))
+}
+
+/// Check the signature, emitting `signature-invalid` when it fails
+pub async fn verify<R: Runtime>(app: &AppHandle<R>) -> Result<SignatureStatus, CommandError> {
+    let status = tauri::async_runtime::spawn_blocking(check)
+        .await
+        .map_err(|e| CommandError::Failed(e.to_string()))??;
+
+    if !status.valid {
+        log::warn!("App signature check failed: {:?}", status);
+        if !cfg!(debug_assertions) {
+            crate::events::emit(app, crate::events::SIGNATURE_INVALID, status.clone());
+        }
+    }
+    Ok(status)
+}
+
+/// Refuse to install updates unless the running app's signature is valid
+pub async fn check_before_update<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if !crate::settings::get(app).require_valid_signature {
+        return Ok(());
+    }
+    match verify(app).await {
+        Ok(status) if status.valid => Ok(()),
+        Ok(_) => Err("Updates are blocked because the app's signature is invalid".to_string()),
+        // Nothing to verify against; the setting can't apply here
+        Err(CommandError::NotSupported(_)) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Check the running app's code signature and, on macOS, its notarization
+///
+/// Emits `signature-invalid` when the signature is missing or broken in a
+/// release build. Returns `NotSupported` on Linux.
+#[tauri::command]
+pub async fn verify_app_signature<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<SignatureStatus, CommandError> {
+    verify(&app).await
+}
+
+/// Require a valid signature before installing updates
+#[tauri::command]
+pub async fn set_require_valid_signature<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.require_valid_signature = enabled)
+}
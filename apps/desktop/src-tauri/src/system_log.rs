@@ -0,0 +1,245 @@
+//! OS log streaming for YULA Desktop
+//!
+//! Some failures (code signing, notification delivery, crashes) only show
+//! up in the OS logs. `tail_system_log` follows them with the platform's
+//! own tool and emits each line as `system-log-line`: `log stream` on
+//! macOS, a PowerShell loop over the Application event log on Windows, and
+//! `journalctl --follow` on Linux. Tailing is off unless enabled in the
+//! advanced settings, and each tail is limited to a fixed number of lines
+//! per second so a noisy subsystem can't flood the frontend.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Maximum lines emitted per tail each second; the rest are dropped
+const MAX_LINES_PER_SECOND: u32 = 20;
+
+/// Identifier of a running log tail
+pub type StreamId = u32;
+
+/// Log tails started by the frontend
+#[derive(Debug, Default)]
+pub struct SystemLogTails {
+    next_id: StreamId,
+    running: HashMap<StreamId, Child>,
+}
+
+/// Payload of the `system-log-line` event
+#[derive(Debug, Clone, Serialize)]
+struct SystemLogLine {
+    id: StreamId,
+    line: String,
+    /// Lines dropped by the rate limit since the previous one
+    dropped: u32,
+}
+
+/// Subsystems are interpolated into filters, so keep them to identifiers
+fn validate_subsystem(subsystem: &str) -> Result<(), String> {
+    let valid = !subsystem.is_empty()
+        && subsystem
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid subsystem: {}", subsystem))
+    }
+}
+
+/// Name of our executable, e.g. `yula-desktop`
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn exe_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string())
+}
+
+/// Command following the OS log, filtered to a subsystem or to our process
+fn tail_command(subsystem: Option<&str>) -> Result<Command, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let predicate = match subsystem {
+            Some(subsystem) => format!("subsystem == \"{}\"", subsystem),
+            None => format!("process == \"{}\"", exe_name()),
+        };
+        let mut command = crate::platform::command("log");
+        command.args(["stream", "--style", "compact", "--level", "info", "--predicate"]);
+        command.arg(predicate);
+        Ok(command)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // Without a provider, follow entries that mention us, such as
+        // Windows Error Reporting's crash records
+        let filter = match subsystem {
+            Some(subsystem) => format!("$_.ProviderName -eq '{}'", subsystem),
+            None => format!("$_.Message -like '*{}*'", exe_name()),
+        };
+        let script = format!(
+            "$last = (Get-WinEvent -LogName Application -MaxEvents 1).RecordId; \
+             while ($true) {{ Start-Sleep -Seconds 2; \
+             Get-WinEvent -LogName Application -MaxEvents 200 -ErrorAction SilentlyContinue \
+             | Where-Object {{ $_.RecordId -gt $last -and ({}) }} | Sort-Object RecordId \
+             | ForEach-Object {{ $last = $_.RecordId; \
+             \"$($_.TimeCreated.ToString('o')) [$($_.LevelDisplayName)] \
+             $($_.ProviderName): $($_.Message -replace '\\s+', ' ')\" }} }}",
+            filter
+        );
+        let mut command = crate::platform::command("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        Ok(command)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut command = crate::platform::command("journalctl");
+        command.args(["--follow", "--lines", "0", "--output", "short-iso"]);
+        match subsystem {
+            Some(subsystem) => command.args(["--identifier", subsystem]),
+            None => command.arg(format!("_PID={}", std::process::id())),
+        };
+        Ok(command)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = subsystem;
+        Err("System logs are not supported on this platform".to_string())
+    }
+}
+
+/// Emit the tail's lines until it exits, dropping lines over the rate limit
+fn forward<R: Runtime>(app: AppHandle<R>, id: StreamId, stdout: ChildStdout) {
+    let mut window_start = Instant::now();
+    let mut emitted = 0;
+    let mut dropped = 0;
+
+    for line in BufReader::new(stdout).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if window_start.elapsed() >= Duration::from_secs(1) {
+            window_start = Instant::now();
+            emitted = 0;
+        }
+        if emitted >= MAX_LINES_PER_SECOND {
+            dropped += 1;
+            continue;
+        }
+
+        emitted += 1;
+        crate::events::emit(
+            &app,
+            crate::events::SYSTEM_LOG_LINE,
+            SystemLogLine { id, line, dropped },
+        );
+        dropped = 0;
+    }
+
+    // The tail exited on its own, or was killed by `stop`
+    let child = app
+        .state::<crate::AppState>()
+        .system_logs
+        .lock()
+        .unwrap()
+        .running
+        .remove(&id);
+    if let Some(mut child) = child {
+        log::info!("System log tail {} exited", id);
+        let _ = child.wait();
+    }
+}
+
+fn stop(mut child: Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Stop every log tail, e.g. on exit
+pub fn stop_all<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        let running: Vec<Child> = {
+            let mut tails = state.system_logs.lock().unwrap();
+            tails.running.drain().map(|(_, child)| child).collect()
+        };
+        running.into_iter().for_each(stop);
+    }
+}
+
+/// Follow the OS log, emitting `system-log-line` events
+///
+/// `subsystem` is a macOS log subsystem, a Windows event provider, or a
+/// Linux syslog identifier; without one the log is filtered to this app.
+/// Requires system log access to be enabled in the advanced settings.
+#[tauri::command]
+pub async fn tail_system_log<R: Runtime>(
+    app: AppHandle<R>,
+    subsystem: Option<String>,
+) -> Result<StreamId, String> {
+    if !crate::settings::get(&app).system_log_access {
+        return Err("System log access is disabled in the advanced settings".to_string());
+    }
+    if let Some(subsystem) = &subsystem {
+        validate_subsystem(subsystem)?;
+    }
+
+    let mut child = tail_command(subsystem.as_deref())?
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start log tail: {}", e))?;
+    let stdout = child.stdout.take().ok_or("Log tail has no output")?;
+
+    let id = {
+        let state = app.state::<crate::AppState>();
+        let mut tails = state.system_logs.lock().unwrap();
+        tails.next_id = tails.next_id.wrapping_add(1).max(1);
+        let id = tails.next_id;
+        tails.running.insert(id, child);
+        id
+    };
+
+    let handle = app.clone();
+    std::thread::Builder::new()
+        .name(format!("system-log-{}", id))
+        .spawn(move || forward(handle, id, stdout))
+        .map_err(|e| e.to_string())?;
+
+    log::info!("System log tail {} started ({:?})", id, subsystem);
+    Ok(id)
+}
+
+/// Stop a log tail started by `tail_system_log`
+#[tauri::command]
+pub fn stop_tail_system_log<R: Runtime>(app: AppHandle<R>, id: StreamId) {
+    let child = app
+        .state::<crate::AppState>()
+        .system_logs
+        .lock()
+        .unwrap()
+        .running
+        .remove(&id);
+    if let Some(child) = child {
+        stop(child);
+    }
+}
+
+/// Allow or forbid tailing the OS logs; disabling stops running tails
+#[tauri::command]
+pub async fn set_system_log_access<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.system_log_access = enabled)?;
+    if !enabled {
+        stop_all(&app);
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Background task control for YULA Desktop
+//!
+//! Polling monitors wait between iterations with `tick`, which applies the
+//! throttle interval, records the task's status and parks the task while
+//! background activity is suspended. `suspend_background_tasks` pauses every
+//! task at its next tick and `resume_background_tasks` wakes them all, which
+//! gives a single lever to quiet the app when diagnosing CPU or battery use.
+//! Event-driven work (directory watchers, scheduled notifications) isn't
+//! polled and keeps running.
+
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// What a background task is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    Sleeping,
+    Suspended,
+}
+
+/// Status of a registered background task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    /// Polling interval before throttling
+    pub interval_ms: u64,
+    /// When the task last started an iteration, Unix millis
+    pub last_run_at: Option<i64>,
+}
+
+/// Status of all background tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundTaskStatus {
+    pub suspended: bool,
+    pub tasks: Vec<TaskStatus>,
+}
+
+fn set_state<R: Runtime>(app: &AppHandle<R>, name: &str, state: TaskState, interval: Duration) {
+    let app_state = app.state::<crate::AppState>();
+    let mut tasks = app_state.background_tasks.lock().unwrap();
+    let task = tasks.entry(name.to_string()).or_insert_with(|| TaskStatus {
+        name: name.to_string(),
+        state,
+        interval_ms: interval.as_millis() as u64,
+        last_run_at: None,
+    });
+    task.state = state;
+    if state == TaskState::Running {
+        task.last_run_at = Some(chrono::Utc::now().timestamp_millis());
+    }
+}
+
+/// Wait out a task's polling interval, then block while tasks are suspended
+pub async fn tick<R: Runtime>(app: &AppHandle<R>, name: &str, interval: Duration) {
+    set_state(app, name, TaskState::Sleeping, interval);
+    tokio::time::sleep(crate::throttle::interval(app, interval)).await;
+    resume_point(app, name, interval).await;
+}
+
+/// Like `tick`, for tasks whose interval must not stretch while throttled
+pub async fn tick_unthrottled<R: Runtime>(app: &AppHandle<R>, name: &str, interval: Duration) {
+    set_state(app, name, TaskState::Sleeping, interval);
+    tokio::time::sleep(interval).await;
+    resume_point(app, name, interval).await;
+}
+
+/// Block while tasks are suspended, then mark the task running
+async fn resume_point<R: Runtime>(app: &AppHandle<R>, name: &str, interval: Duration) {
+    let state = app.state::<crate::AppState>();
+    loop {
+        // Created before the check so a concurrent resume can't be missed
+        let resumed = state.background_resume.notified();
+        if !state.background_suspended.load(Ordering::SeqCst) {
+            break;
+        }
+        set_state(app, name, TaskState::Suspended, interval);
+        resumed.await;
+    }
+
+    set_state(app, name, TaskState::Running, interval);
+}
+
+/// Drop a stopped task from the status list
+pub fn forget<R: Runtime>(app: &AppHandle<R>, name: &str) {
+    let state = app.state::<crate::AppState>();
+    state.background_tasks.lock().unwrap().remove(name);
+}
+
+/// Pause all polling background tasks at their next iteration
+pub fn suspend<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    if !state.background_suspended.swap(true, Ordering::SeqCst) {
+        log::info!("Background tasks suspended");
+    }
+}
+
+/// Pause all polling background tasks at their next iteration
+#[tauri::command]
+pub fn suspend_background_tasks<R: Runtime>(app: AppHandle<R>) {
+    suspend(&app);
+}
+
+/// Resume background tasks paused by `suspend_background_tasks`
+#[tauri::command]
+pub fn resume_background_tasks<R: Runtime>(app: AppHandle<R>) {
+    let state = app.state::<crate::AppState>();
+    if state.background_suspended.swap(false, Ordering::SeqCst) {
+        log::info!("Background tasks resumed");
+    }
+    state.background_resume.notify_waiters();
+}
+
+/// List registered background tasks and whether they're suspended
+#[tauri::command]
+pub fn get_background_task_status<R: Runtime>(app: AppHandle<R>) -> BackgroundTaskStatus {
+    let state = app.state::<crate::AppState>();
+    let mut tasks: Vec<TaskStatus> = state
+        .background_tasks
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect();
+    tasks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    BackgroundTaskStatus {
+        suspended: state.background_suspended.load(Ordering::SeqCst),
+        tasks,
+    }
+}
@@ -0,0 +1,78 @@
+//! Background throttling for YULA Desktop
+//!
+//! When enabled, hiding or minimizing the main window puts the app in
+//! throttle mode: the frontend is told via `throttle-mode` to pause
+//! animations and polling, and backend monitors stretch their intervals.
+//! Showing or focusing the window restores the full rate.
+//!
+//! The OS low-power mode throttles the app too, regardless of the window
+//! and of the setting.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, Window};
+
+/// How much slower monitors poll while throttled
+const THROTTLE_FACTOR: u32 = 5;
+
+/// Whether background throttling is currently active
+pub fn is_throttled<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<crate::AppState>()
+        .map(|state| state.throttled.load(Ordering::SeqCst))
+        .unwrap_or(false)
+}
+
+/// Polling interval for a monitor, stretched while throttled
+pub fn interval<R: Runtime>(app: &AppHandle<R>, normal: Duration) -> Duration {
+    if is_throttled(app) {
+        normal * THROTTLE_FACTOR
+    } else {
+        normal
+    }
+}
+
+/// Enter or leave throttle mode, notifying the frontend on change
+///
+/// `throttled` is whether the window is out of sight; low-power mode
+/// throttles either way.
+pub fn set_throttled<R: Runtime>(app: &AppHandle<R>, throttled: bool) {
+    let throttled = (throttled && crate::settings::get(app).background_throttle)
+        || crate::power::is_low_power(app);
+    let state = app.state::<crate::AppState>();
+    if state.throttled.swap(throttled, Ordering::SeqCst) != throttled {
+        log::info!("Background throttle mode: {}", throttled);
+        crate::events::emit(app, crate::events::THROTTLE_MODE, throttled);
+    }
+}
+
+/// Recompute throttle mode from the main window's visibility
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let hidden = crate::window::main_window(app)
+        .map(|window| !window.is_visible().unwrap_or(true))
+        .unwrap_or(false);
+    set_throttled(app, hidden);
+}
+
+/// Update throttle mode after the main window gained or lost focus
+pub fn on_focus_changed<R: Runtime>(window: &Window<R>, focused: bool) {
+    if window.label() != crate::window::MAIN_WINDOW {
+        return;
+    }
+
+    // Losing focus to another app isn't enough; the window must be out of sight
+    let hidden = !focused
+        && (!window.is_visible().unwrap_or(true) || window.is_minimized().unwrap_or(false));
+    set_throttled(window.app_handle(), hidden);
+}
+
+/// Enable or disable throttling while the window is hidden
+#[tauri::command]
+pub async fn set_background_throttle<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.background_throttle = enabled)?;
+
+    refresh(&app);
+    Ok(())
+}
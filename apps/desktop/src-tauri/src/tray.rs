@@ -1,13 +1,132 @@
 //! System Tray functionality for YULA Desktop
 //!
-//! Provides a persistent tray icon with quick actions.
+//! Provides a persistent tray icon with quick actions. The icon can be
+//! replaced from the frontend, or badged with a presence status drawn over
+//! the bundled app icon. For alerts it can blink between its normal image
+//! and a tinted variant until the main window is focused.
+//!
+//! The icon's screen rectangle is kept for anchoring popovers. Where the OS
+//! can't report it on demand, it's known only after the first time the
+//! pointer interacts with the icon.
+//!
+//! The bundled icon comes in 1x, 2x and 3x sizes. The one matching the scale
+//! of the monitor showing the tray, rounded up so the OS only ever shrinks
+//! it, is picked when the tray is created and again when a window moves to
+//! a monitor with a different scale; status badges are redrawn on it.
+//! Icons set from the frontend are shown as given.
+//!
+//! Clicks follow the platform convention unless `set_tray_click_behavior`
+//! picks otherwise: on macOS the menu opens on left click, on Windows a left
+//! click shows the window and a right click opens the menu. Linux tray hosts
+//! (AppIndicator) always open the menu and don't report clicks.
 
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use tauri::{
+    image::Image,
     menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager, Runtime,
+    tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Manager, Runtime, Window,
 };
 
+/// Id of the app's tray icon, used to look it up after setup
+pub const TRAY_ID: &str = "main";
+
+/// Shortest allowed blink interval
+const MIN_BLINK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// RGB color the icon is tinted with in its alert variant
+const ALERT_COLOR: [u8; 3] = [255, 59, 48];
+
+/// Bundled tray icons for 1x, 2x and 3x monitors, 22 logical pixels square
+const BUNDLED_ICONS: [&[u8]; 3] = [
+    include_bytes!("../icons/tray/tray.png"),
+    include_bytes!("../icons/tray/tray@2x.png"),
+    include_bytes!("../icons/tray/tray@3x.png"),
+];
+
+/// Image shown on the tray icon and the blink animation, if running
+#[derive(Default)]
+pub struct TrayState {
+    /// Icon set from the frontend, `None` for the bundled app icon
+    icon: Option<Image<'static>>,
+    /// Status badged on the bundled icon, redrawn when the scale changes
+    status: Option<TrayStatus>,
+    /// Size of the bundled icon in use, 1 to 3
+    icon_scale: u32,
+    blink: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Rectangle reported by the last tray event
+    rect: Option<TrayRect>,
+}
+
+impl std::fmt::Debug for TrayState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrayState")
+            .field("custom_icon", &self.icon.is_some())
+            .field("status", &self.status)
+            .field("icon_scale", &self.icon_scale)
+            .field("blinking", &self.blink.is_some())
+            .field("rect", &self.rect)
+            .finish()
+    }
+}
+
+/// Screen rectangle of the tray icon, in physical pixels
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TrayRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&tauri::Rect> for TrayRect {
+    fn from(rect: &tauri::Rect) -> Self {
+        // Tray events report physical coordinates, so no scaling applies
+        let position = rect.position.to_physical::<i32>(1.0);
+        let size = rect.size.to_physical::<u32>(1.0);
+        Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+/// What a left click on the tray icon does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TrayClickBehavior {
+    /// The menu on macOS; the window elsewhere, with the menu on right click
+    #[default]
+    Auto,
+    /// Open the menu
+    ShowMenu,
+    /// Show the main window, or the popover in menu bar mode
+    ShowWindow,
+}
+
+/// Presence status shown on the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TrayStatus {
+    Online,
+    Away,
+    Busy,
+    Offline,
+}
+
+impl TrayStatus {
+    /// RGB color of the status badge
+    fn color(self) -> [u8; 3] {
+        match self {
+            Self::Online => [52, 199, 89],
+            Self::Away => [255, 159, 10],
+            Self::Busy => [255, 59, 48],
+            Self::Offline => [142, 142, 147],
+        }
+    }
+}
+
 /// Setup the system tray icon and menu
 pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
     // Create menu items
@@ -36,6 +155,10 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::err
         .accelerator("CmdOrCtrl+,")
         .build(app)?;
 
+    let about = MenuItemBuilder::new("About YULA")
+        .id("about")
+        .build(app)?;
+
     let separator2 = PredefinedMenuItem::separator(app)?;
 
     let quit = MenuItemBuilder::new("Quit YULA")
@@ -52,47 +175,52 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::err
         .item(&separator1)
         .item(&check_updates)
         .item(&preferences)
+        .item(&about)
         .item(&separator2)
         .item(&quit)
         .build()?;
 
     // Create tray icon
-    let _tray = TrayIconBuilder::new()
-        .icon(app.default_window_icon().unwrap().clone())
+    let scale = icon_scale(tray_scale_factor(app));
+    app.state::<crate::AppState>().tray.lock().unwrap().icon_scale = scale;
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
+        .icon(bundled_icon(scale)?)
         .menu(&menu)
-        .show_menu_on_left_click(false)
+        .show_menu_on_left_click(menu_on_left_click(app))
         .on_menu_event(move |app, event| {
             match event.id().as_ref() {
                 "open" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                    if let Err(e) = crate::window::bring_to_front(app) {
+                        log::warn!("Failed to raise main window: {}", e);
                     }
                 }
                 "new_chat" => {
                     if let Some(window) = app.get_webview_window("main") {
                         let _ = window.show();
                         let _ = window.set_focus();
-                        let _ = window.emit("tray-action", "new-chat");
+                        crate::events::emit(app, crate::events::TRAY_ACTION, "new-chat");
                     }
                 }
                 "council" => {
                     if let Some(window) = app.get_webview_window("main") {
                         let _ = window.show();
                         let _ = window.set_focus();
-                        let _ = window.emit("tray-action", "council");
+                        crate::events::emit(app, crate::events::TRAY_ACTION, "council");
                     }
                 }
                 "check_updates" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.emit("tray-action", "check-updates");
-                    }
+                    crate::events::emit(app, crate::events::TRAY_ACTION, "check-updates");
                 }
                 "preferences" => {
                     if let Some(window) = app.get_webview_window("main") {
                         let _ = window.show();
                         let _ = window.set_focus();
-                        let _ = window.emit("tray-action", "preferences");
+                        crate::events::emit(app, crate::events::TRAY_ACTION, "preferences");
+                    }
+                }
+                "about" => {
+                    if let Err(e) = crate::about::show(app) {
+                        log::warn!("Failed to show About panel: {}", e);
                     }
                 }
                 "quit" => {
@@ -102,21 +230,341 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::err
             }
         })
         .on_tray_icon_event(|tray, event| {
-            if let TrayIconEvent::Click {
-                button: MouseButton::Left,
+            let app = tray.app_handle();
+            record_rect(app, &event);
+            let TrayIconEvent::Click {
+                button,
                 button_state: MouseButtonState::Up,
                 ..
             } = event
-            {
-                // Show window on left click
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
+            else {
+                return;
+            };
+            match button {
+                // The OS opens the menu itself when it's shown on left click
+                MouseButton::Left if !menu_on_left_click(app) => {
+                    // As a popover in menu bar mode
+                    let result = if crate::menu_bar::is_enabled(app) {
+                        crate::menu_bar::toggle(app)
+                    } else {
+                        crate::window::bring_to_front(app)
+                    };
+                    if let Err(e) = result {
+                        log::warn!("Failed to raise main window: {}", e);
+                    }
                 }
+                // Right click opens the menu on every platform
+                _ => {}
             }
         })
         .build(app)?;
 
     Ok(())
 }
+
+/// Whether a left click opens the menu rather than showing the window
+///
+/// Menu bar mode keeps the left click for its popover unless the menu was
+/// explicitly chosen.
+fn menu_on_left_click<R: Runtime>(app: &AppHandle<R>) -> bool {
+    match crate::settings::get(app).tray_click_behavior {
+        TrayClickBehavior::Auto => cfg!(target_os = "macos") && !crate::menu_bar::is_enabled(app),
+        TrayClickBehavior::ShowMenu => true,
+        TrayClickBehavior::ShowWindow => false,
+    }
+}
+
+/// Update which click opens the menu after the behavior or menu bar mode
+/// changed
+pub fn apply_click_behavior<R: Runtime>(app: &AppHandle<R>) {
+    let result = tray(app).and_then(|tray| {
+        tray.set_show_menu_on_left_click(menu_on_left_click(app))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to apply tray click behavior: {}", e);
+    }
+}
+
+/// Remember the icon's rectangle from any event that carries it
+fn record_rect<R: Runtime>(app: &AppHandle<R>, event: &TrayIconEvent) {
+    let rect = match event {
+        TrayIconEvent::Click { rect, .. }
+        | TrayIconEvent::DoubleClick { rect, .. }
+        | TrayIconEvent::Enter { rect, .. }
+        | TrayIconEvent::Move { rect, .. }
+        | TrayIconEvent::Leave { rect, .. } => TrayRect::from(rect),
+        _ => return,
+    };
+    app.state::<crate::AppState>().tray.lock().unwrap().rect = Some(rect);
+}
+
+/// Current screen rectangle of the tray icon, if known
+///
+/// Asks the OS first and falls back to the last rectangle seen in a tray
+/// event, so on platforms that can't answer directly it's `None` until the
+/// icon has been hovered or clicked.
+pub fn rect<R: Runtime>(app: &AppHandle<R>) -> Option<TrayRect> {
+    let current = tray(app)
+        .ok()
+        .and_then(|tray| tray.rect().ok().flatten())
+        .map(|rect| TrayRect::from(&rect));
+    let state = app.state::<crate::AppState>();
+    let mut tray_state = state.tray.lock().unwrap();
+    if current.is_some() {
+        tray_state.rect = current;
+    }
+    tray_state.rect
+}
+
+/// Look up the tray icon created in `setup_tray`
+pub fn tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, String> {
+    app.tray_by_id(TRAY_ID)
+        .ok_or_else(|| "Tray icon not found".to_string())
+}
+
+/// Bundled icon size (1 to 3) for a monitor scale factor, rounded up
+pub fn icon_scale(scale_factor: f64) -> u32 {
+    (scale_factor.ceil() as u32).clamp(1, BUNDLED_ICONS.len() as u32)
+}
+
+fn bundled_icon(scale: u32) -> Result<Image<'static>, String> {
+    Image::from_bytes(BUNDLED_ICONS[scale as usize - 1]).map_err(|e| e.to_string())
+}
+
+/// Scale factor of the monitor showing the tray
+///
+/// Falls back to the main window's monitor, then the primary one, while the
+/// tray's position isn't known.
+fn tray_scale_factor<R: Runtime>(app: &AppHandle<R>) -> f64 {
+    let monitor = rect(app)
+        .and_then(|rect| {
+            let (x, y) = (rect.x + rect.width as i32 / 2, rect.y + rect.height as i32 / 2);
+            app.monitor_from_point(x as f64, y as f64).ok().flatten()
+        })
+        .or_else(|| {
+            crate::window::main_window(app)
+                .ok()
+                .and_then(|window| window.current_monitor().ok().flatten())
+        })
+        .or_else(|| app.primary_monitor().ok().flatten());
+    monitor.map_or(1.0, |monitor| monitor.scale_factor())
+}
+
+/// The tray's normal image: the last icon set, or the bundled app icon
+fn normal_icon<R: Runtime>(app: &AppHandle<R>) -> Result<Image<'static>, String> {
+    let state = app.state::<crate::AppState>();
+    let tray_state = state.tray.lock().unwrap();
+    match &tray_state.icon {
+        Some(icon) => Ok(icon.clone()),
+        None => bundled_icon(tray_state.icon_scale.max(1)),
+    }
+}
+
+/// Switch to the bundled icon size for the tray's monitor, if it changed
+pub fn refresh_icon_scale<R: Runtime>(app: &AppHandle<R>) {
+    let scale = icon_scale(tray_scale_factor(app));
+    let state = app.state::<crate::AppState>();
+    let mut tray_state = state.tray.lock().unwrap();
+    let custom = tray_state.icon.is_some() && tray_state.status.is_none();
+    if custom || tray_state.icon_scale == scale {
+        return;
+    }
+    tray_state.icon_scale = scale;
+    let status = tray_state.status;
+    drop(tray_state);
+
+    log::debug!("Tray icon switched to the {}x asset", scale);
+    let result = match status {
+        Some(status) => status_icon(app, status).and_then(|icon| apply_icon(app, icon)),
+        None => bundled_icon(scale).and_then(|icon| {
+            tray(app)?.set_icon(Some(icon)).map_err(|e| e.to_string())
+        }),
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to update tray icon: {}", e);
+    }
+}
+
+/// Show an image on the tray and remember it as the normal icon
+fn apply_icon<R: Runtime>(app: &AppHandle<R>, icon: Image<'static>) -> Result<(), String> {
+    let tray = tray(app)?;
+    tray.set_icon(Some(icon.clone())).map_err(|e| e.to_string())?;
+    // Custom icons carry their own colors
+    tray.set_icon_as_template(false).map_err(|e| e.to_string())?;
+    app.state::<crate::AppState>().tray.lock().unwrap().icon = Some(icon);
+    Ok(())
+}
+
+/// Blend every opaque pixel halfway towards the alert color
+fn alert_icon(base: &Image<'_>) -> Image<'static> {
+    let mut rgba = base.rgba().to_vec();
+    for pixel in rgba.chunks_exact_mut(4) {
+        for (channel, alert) in pixel.iter_mut().zip(ALERT_COLOR) {
+            *channel = ((*channel as u16 + alert as u16) / 2) as u8;
+        }
+    }
+    Image::new_owned(rgba, base.width(), base.height())
+}
+
+/// Stop blinking and restore the normal icon, if a blink is running
+fn stop_blink<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let task = app.state::<crate::AppState>().tray.lock().unwrap().blink.take();
+    let Some(task) = task else {
+        return Ok(());
+    };
+    task.abort();
+
+    let icon = normal_icon(app)?;
+    tray(app)?.set_icon(Some(icon)).map_err(|e| e.to_string())
+}
+
+/// Stop an alert blink once the user focuses the main window
+pub fn on_focus_changed<R: Runtime>(window: &Window<R>, focused: bool) {
+    if !focused || window.label() != crate::window::MAIN_WINDOW {
+        return;
+    }
+    if let Err(e) = stop_blink(window.app_handle()) {
+        log::warn!("Failed to stop tray blink: {}", e);
+    }
+}
+
+/// Draw a status badge in the bottom-right corner of the app icon
+fn status_icon<R: Runtime>(
+    app: &AppHandle<R>,
+    status: TrayStatus,
+) -> Result<Image<'static>, String> {
+    let scale = app.state::<crate::AppState>().tray.lock().unwrap().icon_scale;
+    let base = bundled_icon(scale.max(1))?;
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+
+    let radius = width.min(height) as f64 / 4.0;
+    let (cx, cy) = (width as f64 - radius, height as f64 - radius);
+    let [r, g, b] = status.color();
+
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f64 + 0.5 - cx, y as f64 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+    }
+
+    Ok(Image::new_owned(rgba, width, height))
+}
+
+/// Decode a base64-encoded PNG, returning its bytes and the decoded image
+pub fn decode_png(base64_png: &str) -> Result<(Vec<u8>, Image<'static>), String> {
+    use base64::Engine;
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(base64_png.trim())
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+    let image = Image::from_bytes(&bytes).map_err(|e| format!("Invalid PNG: {}", e))?;
+    Ok((bytes, image))
+}
+
+/// Replace the tray icon with a base64-encoded PNG
+#[tauri::command]
+pub async fn set_tray_icon<R: Runtime>(
+    app: AppHandle<R>,
+    base64_png: String,
+) -> Result<(), String> {
+    let (_, icon) = decode_png(&base64_png)?;
+    apply_icon(&app, icon)?;
+    app.state::<crate::AppState>().tray.lock().unwrap().status = None;
+    Ok(())
+}
+
+/// Show a presence status badge on the tray icon
+#[tauri::command]
+pub async fn set_tray_status<R: Runtime>(
+    app: AppHandle<R>,
+    status: TrayStatus,
+) -> Result<(), String> {
+    let icon = status_icon(&app, status)?;
+    apply_icon(&app, icon)?;
+    app.state::<crate::AppState>().tray.lock().unwrap().status = Some(status);
+    Ok(())
+}
+
+/// Blink the tray icon between its normal image and an alert variant
+///
+/// Stops on `stop_tray_blink` or when the main window is focused.
+#[tauri::command]
+pub async fn start_tray_blink<R: Runtime>(
+    app: AppHandle<R>,
+    interval_ms: u64,
+) -> Result<(), String> {
+    let interval = Duration::from_millis(interval_ms);
+    if interval < MIN_BLINK_INTERVAL {
+        return Err(format!(
+            "Blink interval must be at least {} ms",
+            MIN_BLINK_INTERVAL.as_millis()
+        ));
+    }
+
+    stop_blink(&app)?;
+    let tray = tray(&app)?;
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let mut alert = false;
+        loop {
+            tokio::time::sleep(interval).await;
+            alert = !alert;
+
+            // Re-read the normal icon so status changes show mid-blink
+            let icon = match normal_icon(&handle) {
+                Ok(icon) if alert => alert_icon(&icon),
+                Ok(icon) => icon,
+                Err(e) => {
+                    log::warn!("Tray blink stopped: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = tray.set_icon(Some(icon)) {
+                log::warn!("Failed to swap tray icon: {}", e);
+            }
+        }
+    });
+    app.state::<crate::AppState>().tray.lock().unwrap().blink = Some(task);
+    Ok(())
+}
+
+/// Stop blinking the tray icon and restore its normal image
+#[tauri::command]
+pub async fn stop_tray_blink<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    stop_blink(&app)
+}
+
+/// Get the tray icon's screen rectangle in physical pixels, for anchoring popovers
+///
+/// `None` until the icon was interacted with on platforms that don't report
+/// its position on demand.
+#[tauri::command]
+pub fn get_tray_rect<R: Runtime>(app: AppHandle<R>) -> Option<TrayRect> {
+    rect(&app)
+}
+
+/// Choose what a left click on the tray icon does
+///
+/// `Auto` follows the platform: the menu on macOS, the window elsewhere.
+/// Linux tray hosts open the menu on any click regardless.
+#[tauri::command]
+pub async fn set_tray_click_behavior<R: Runtime>(
+    app: AppHandle<R>,
+    behavior: TrayClickBehavior,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.tray_click_behavior = behavior)?;
+    apply_click_behavior(&app);
+    Ok(())
+}
+
+/// Get what a left click on the tray icon does
+#[tauri::command]
+pub fn get_tray_click_behavior<R: Runtime>(app: AppHandle<R>) -> TrayClickBehavior {
+    crate::settings::get(&app).tray_click_behavior
+}
@@ -0,0 +1,963 @@
+//! Update metadata for YULA Desktop
+//!
+//! Builds a structured changelog for the "What's New" screen. The update
+//! manifest may carry a `changelog` array of `{ version, date, highlights }`
+//! objects; otherwise the release notes are parsed as markdown with one
+//! heading per version and bullet highlights. When neither yields anything
+//! the raw notes are returned as-is.
+//!
+//! Found updates are announced with `update-available` and a notification,
+//! and downloads report `update-download-progress`. Debug builds can drive
+//! both with fake data to exercise the update UI without an update server.
+//!
+//! Checks and installs also move an explicit `UpdaterState`, kept in
+//! `AppState` and emitted as `updater-state-changed` on every transition, so
+//! the UI can follow one value instead of piecing it together from events.
+//!
+//! The package is downloaded here rather than by the updater plugin so the
+//! download can be paused, resumed and cancelled. Pausing drops the
+//! connection; resuming asks for the rest with a `Range` request and starts
+//! over when the server ignores it. The finished package is checked against
+//! the updater's public key, as the plugin would, before it is installed.
+//!
+//! Packages are downloaded into the system temp directory, which can be too
+//! small or too slow. A download directory can be configured instead; it is
+//! checked for write access and free space when set and again before each
+//! download, falling back to the temp directory when it no longer fits. The
+//! plugin still unpacks the installer it's handed in the temp directory, as
+//! it offers no way to pick another.
+//!
+//! An installed update takes effect on the next launch. Until then polling
+//! background tasks are suspended and the presence heartbeat is stopped, and
+//! `update-staged` tells the frontend to offer a restart. The version being
+//! replaced is written to `update_staged.json` before installing, since the
+//! Windows installer quits the app as it starts. A launch that finds the
+//! file running a different version emits `updated-from` once the frontend
+//! is ready, so it can say what changed and resume.
+//!
+//! `update_dry_run` runs the pipeline up to the install, with the usual
+//! progress events, so release QA can catch broken manifests, packages and
+//! signatures before users download them.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands::UpdateInfo;
+
+/// File in the app data dir recording the version an update replaces
+const STAGED_FILE: &str = "update_staged.json";
+
+/// Size of the fake download driven by `simulate_update_progress`
+const SIMULATED_DOWNLOAD_BYTES: u64 = 48 * 1024 * 1024;
+
+/// Number of progress events in a simulated download
+const SIMULATED_STEPS: u64 = 40;
+
+/// Delay between simulated progress events
+const SIMULATED_STEP_DELAY: Duration = Duration::from_millis(100);
+
+/// Free space a download directory needs to hold an update
+const MIN_FREE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Minimum time between `update-download-progress` events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Timeout for connecting to the update server
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest wait for the next chunk before the download is given up
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Release notes of a single version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseNote {
+    pub version: String,
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+}
+
+/// Changelog between the running version and an available update
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateChangelog {
+    pub current_version: String,
+    pub target_version: Option<String>,
+    /// Releases newer than the running version, newest first
+    pub releases: Vec<ReleaseNote>,
+    /// Raw notes, set when they couldn't be structured
+    pub notes: Option<String>,
+}
+
+/// Payload of the `update-download-progress` event
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub downloaded_bytes: u64,
+    /// Size of the package, when the server reports it
+    pub total_bytes: Option<u64>,
+}
+
+/// An installed update waiting for the app to restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StagedUpdate {
+    old_version: String,
+    new_version: String,
+}
+
+/// Payload of the `update-staged` event
+#[derive(Debug, Clone, Serialize)]
+struct StagedPayload {
+    version: String,
+}
+
+/// Payload of the `updated-from` event
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdatedFrom {
+    pub old_version: String,
+    /// The running version
+    pub new_version: String,
+}
+
+/// A step of `update_dry_run`, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DryRunStepKind {
+    Check,
+    Download,
+    Size,
+    Signature,
+}
+
+/// Outcome of one step of an update dry run
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunStep {
+    pub step: DryRunStepKind,
+    pub ok: bool,
+    /// What was found, or why the step failed
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Report of `update_dry_run`
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub current_version: String,
+    /// Version the update server offered, if any
+    pub target_version: Option<String>,
+    /// Steps that ran; a failed check or download ends the run
+    pub steps: Vec<DryRunStep>,
+    /// Whether every step ran and succeeded
+    pub passed: bool,
+}
+
+impl DryRunReport {
+    fn record(&mut self, step: DryRunStepKind, started: Instant, result: Result<String, String>) {
+        let ok = result.is_ok();
+        let detail = result.unwrap_or_else(|e| e);
+        log::info!("Update dry run, {:?}: {}", step, detail);
+        self.steps.push(DryRunStep {
+            step,
+            ok,
+            detail,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+/// Where the updater is in checking for and installing an update
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum UpdaterState {
+    #[default]
+    Idle,
+    Checking,
+    Available {
+        version: String,
+    },
+    Downloading {
+        downloaded_bytes: u64,
+        total_bytes: Option<u64>,
+    },
+    Paused {
+        downloaded_bytes: u64,
+        total_bytes: Option<u64>,
+    },
+    Installing,
+    Failed {
+        error: String,
+    },
+}
+
+impl UpdaterState {
+    /// Whether an update is being downloaded or installed
+    pub fn is_installing(&self) -> bool {
+        matches!(self, Self::Downloading { .. } | Self::Paused { .. } | Self::Installing)
+    }
+}
+
+/// Pause and cancel requests for the running update download
+#[derive(Debug, Default)]
+pub struct DownloadControl {
+    active: AtomicBool,
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    /// Wakes a paused download after a resume or cancel
+    wake: tokio::sync::Notify,
+}
+
+impl DownloadControl {
+    /// Whether the last download stopped because it was cancelled
+    pub fn was_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        if self.was_cancelled() {
+            Err("Update download cancelled".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Get the current updater state
+pub fn state<R: Runtime>(app: &AppHandle<R>) -> UpdaterState {
+    app.state::<crate::AppState>().updater_state.lock().unwrap().clone()
+}
+
+/// Move the updater to `next`, emitting `updater-state-changed` if it differs
+pub fn set_state<R: Runtime>(app: &AppHandle<R>, next: UpdaterState) {
+    let state = app.state::<crate::AppState>();
+    let mut current = state.updater_state.lock().unwrap();
+    if *current == next {
+        return;
+    }
+    log::debug!("Updater state: {:?}", next);
+    *current = next.clone();
+    // Emitted under the lock so listeners see transitions in order
+    crate::events::emit(app, crate::events::UPDATER_STATE_CHANGED, next);
+}
+
+/// Emit `update-available` and notify once per version
+pub fn announce<R: Runtime>(app: &AppHandle<R>, info: &UpdateInfo) {
+    let Some(version) = info.version.as_deref() else {
+        return;
+    };
+
+    // A later check mustn't hide a download that is under way
+    if !state(app).is_installing() {
+        set_state(
+            app,
+            UpdaterState::Available {
+                version: version.to_string(),
+            },
+        );
+    }
+    crate::events::emit(app, crate::events::UPDATE_AVAILABLE, info.clone());
+
+    let state = app.state::<crate::AppState>();
+    let mut announced = state.announced_update.lock().unwrap();
+    if announced.as_deref() == Some(version) {
+        return;
+    }
+    *announced = Some(version.to_string());
+    drop(announced);
+
+    let body = format!("YULA {} is ready to install.", version);
+    if let Err(e) = crate::notifications::notify(app, "Update available", &body, None) {
+        log::warn!("Failed to show update notification: {}", e);
+    }
+}
+
+/// Emit `update-download-progress` and move to `Downloading`
+pub fn emit_progress<R: Runtime>(app: &AppHandle<R>, downloaded_bytes: u64, total: Option<u64>) {
+    set_state(
+        app,
+        UpdaterState::Downloading {
+            downloaded_bytes,
+            total_bytes: total,
+        },
+    );
+    crate::events::emit(
+        app,
+        crate::events::UPDATE_DOWNLOAD_PROGRESS,
+        DownloadProgress {
+            downloaded_bytes,
+            total_bytes: total,
+        },
+    );
+}
+
+/// Check a package against the updater's public key and the release signature
+fn verify_signature<R: Runtime>(
+    app: &AppHandle<R>,
+    bytes: &[u8],
+    signature: &str,
+) -> Result<(), String> {
+    use base64::Engine;
+    use minisign_verify::{PublicKey, Signature};
+
+    let decode = |encoded: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+    };
+    let pubkey = app
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|config| config.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .and_then(decode)
+        .ok_or("The updater has no valid public key")?;
+    let signature = decode(signature).ok_or("The update signature is malformed")?;
+
+    let pubkey = PublicKey::decode(&pubkey).map_err(|e| e.to_string())?;
+    let signature = Signature::decode(&signature).map_err(|e| e.to_string())?;
+    pubkey
+        .verify(bytes, &signature, true)
+        .map_err(|e| format!("The update signature doesn't match: {}", e))
+}
+
+/// Download an update package into `file`, following pause and cancel
+/// requests, and return its size
+async fn fetch<R: Runtime>(
+    app: &AppHandle<R>,
+    control: &DownloadControl,
+    url: &tauri::Url,
+    file: &mut tokio::fs::File,
+) -> Result<u64, String> {
+    use reqwest::header::{ACCEPT, RANGE};
+    use reqwest::StatusCode;
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut downloaded = 0;
+    let mut total = None;
+    emit_progress(app, 0, None);
+
+    loop {
+        let mut request = client
+            .get(url.as_str())
+            .header(ACCEPT, "application/octet-stream");
+        if downloaded > 0 {
+            request = request.header(RANGE, format!("bytes={}-", downloaded));
+        }
+        let mut response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?;
+        if downloaded > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+            log::info!("Update server doesn't resume downloads; starting over");
+            downloaded = 0;
+            file.set_len(0).await.map_err(|e| e.to_string())?;
+            file.rewind().await.map_err(|e| e.to_string())?;
+        }
+        if downloaded == 0 {
+            total = response.content_length();
+        }
+
+        let mut last_report = Instant::now();
+        let mut paused = false;
+        loop {
+            let chunk = tokio::select! {
+                chunk = tokio::time::timeout(CHUNK_TIMEOUT, response.chunk()) => chunk
+                    .map_err(|_| "The update server stopped sending data".to_string())?
+                    .map_err(|e| e.to_string())?,
+                // Pause and cancel don't wait for a stalled connection
+                _ = control.wake.notified() => {
+                    control.check_cancelled()?;
+                    if control.paused.load(Ordering::SeqCst) {
+                        paused = true;
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            downloaded += chunk.len() as u64;
+            control.check_cancelled()?;
+            if control.paused.load(Ordering::SeqCst) {
+                paused = true;
+                break;
+            }
+            // Chunks are small; report progress a few times a second
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                last_report = Instant::now();
+                emit_progress(app, downloaded, total);
+            }
+        }
+        if !paused {
+            emit_progress(app, downloaded, total);
+            file.flush().await.map_err(|e| e.to_string())?;
+            log::info!("Downloaded {} bytes", downloaded);
+            return Ok(downloaded);
+        }
+
+        drop(response);
+        log::info!("Update download paused at {} bytes", downloaded);
+        set_state(
+            app,
+            UpdaterState::Paused {
+                downloaded_bytes: downloaded,
+                total_bytes: total,
+            },
+        );
+        while control.paused.load(Ordering::SeqCst) {
+            control.check_cancelled()?;
+            control.wake.notified().await;
+        }
+        control.check_cancelled()?;
+        log::info!("Resuming update download");
+        emit_progress(app, downloaded, total);
+    }
+}
+
+/// Download an update's package, pausable and cancellable from the frontend
+async fn download<R: Runtime>(
+    app: &AppHandle<R>,
+    update: &tauri_plugin_updater::Update,
+) -> Result<Vec<u8>, String> {
+    let state = app.state::<crate::AppState>();
+    let control = &state.update_download;
+    control.paused.store(false, Ordering::SeqCst);
+    control.cancelled.store(false, Ordering::SeqCst);
+    control.active.store(true, Ordering::SeqCst);
+
+    let path = download_dir(app).join(format!("yula-update-{}.part", update.version));
+    let result = match tokio::fs::File::create(&path).await {
+        Ok(mut file) => fetch(app, control, &update.download_url, &mut file).await,
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    };
+    control.active.store(false, Ordering::SeqCst);
+
+    let bytes = match result {
+        Ok(_) => tokio::fs::read(&path).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    let _ = tokio::fs::remove_file(&path).await;
+    bytes
+}
+
+/// Download, verify and install an update
+pub async fn download_and_install<R: Runtime>(
+    app: &AppHandle<R>,
+    update: &tauri_plugin_updater::Update,
+) -> Result<(), String> {
+    let bytes = download(app, update).await?;
+
+    verify_signature(app, &bytes, &update.signature)?;
+    log::info!("Download finished, installing...");
+    set_state(app, UpdaterState::Installing);
+    record_staged(app, &update.version);
+    if let Err(e) = update.install(bytes) {
+        clear_staged(app);
+        return Err(e.to_string());
+    }
+
+    prepare_for_restart(app, &update.version);
+    Ok(())
+}
+
+fn staged_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(STAGED_FILE))
+        .map_err(|e| e.to_string())
+}
+
+/// Remember the running version before an update replaces it
+///
+/// Kept apart from the rollback metadata, which only exists when the
+/// installation could be backed up.
+fn record_staged<R: Runtime>(app: &AppHandle<R>, new_version: &str) {
+    let staged = StagedUpdate {
+        old_version: app.package_info().version.to_string(),
+        new_version: new_version.to_string(),
+    };
+    let result = staged_path(app).and_then(|path| {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string(&staged).map_err(|e| e.to_string())?;
+        crate::settings::write_atomic(&path, contents)
+    });
+    if let Err(e) = result {
+        log::warn!("The next launch won't report the update: {}", e);
+    }
+}
+
+fn clear_staged<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(path) = staged_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Quiet the app while the installed update waits for a restart, so no
+/// poll or connection is cut off halfway when it comes
+fn prepare_for_restart<R: Runtime>(app: &AppHandle<R>, version: &str) {
+    log::info!("Update to {} staged, waiting for restart", version);
+    crate::tasks::suspend(app);
+    crate::presence::stop(app);
+    crate::events::emit(
+        app,
+        crate::events::UPDATE_STAGED,
+        StagedPayload {
+            version: version.to_string(),
+        },
+    );
+}
+
+/// Detect that this launch follows an update, reported as `updated-from`
+/// once the frontend is ready
+pub fn check_launch<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(path) = staged_path(app) else {
+        return;
+    };
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    let _ = std::fs::remove_file(&path);
+    let Ok(staged) = serde_json::from_str::<StagedUpdate>(&contents) else {
+        return;
+    };
+
+    let current = app.package_info().version.to_string();
+    if staged.old_version == current {
+        log::warn!("Update to {} didn't take effect", staged.new_version);
+        return;
+    }
+    log::info!("Updated from {} to {}", staged.old_version, current);
+    *app.state::<crate::AppState>().updated_from.lock().unwrap() = Some(UpdatedFrom {
+        old_version: staged.old_version,
+        new_version: current,
+    });
+}
+
+/// Emit `updated-from` if this launch follows an update
+pub fn announce_updated<R: Runtime>(app: &AppHandle<R>) {
+    let updated_from = app
+        .state::<crate::AppState>()
+        .updated_from
+        .lock()
+        .unwrap()
+        .clone();
+    if let Some(updated_from) = updated_from {
+        crate::events::emit(app, crate::events::UPDATED_FROM, updated_from);
+    }
+}
+
+/// Check that a directory can hold an update
+fn validate_download_dir(path: &Path) -> Result<PathBuf, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+
+    let probe = path.join(format!(".yula-write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"")
+        .map_err(|e| format!("{} is not writable: {}", path.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+
+    let usage = crate::disk::usage(&path)?;
+    if usage.available_bytes < MIN_FREE_BYTES {
+        return Err(format!(
+            "{} has {} MB free; updates need at least {} MB",
+            path.display(),
+            usage.available_bytes / (1024 * 1024),
+            MIN_FREE_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(path)
+}
+
+/// Directory to download the next package into: the configured one while
+/// it still fits, or the system temp directory
+fn download_dir<R: Runtime>(app: &AppHandle<R>) -> PathBuf {
+    let Some(dir) = crate::settings::get(app).update_download_dir else {
+        return std::env::temp_dir();
+    };
+    match validate_download_dir(&dir) {
+        Ok(dir) => {
+            log::info!("Downloading update into {}", dir.display());
+            dir
+        }
+        Err(e) => {
+            log::warn!("Downloading update into the temp directory instead: {}", e);
+            std::env::temp_dir()
+        }
+    }
+}
+
+fn require_debug_build() -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        Ok(())
+    } else {
+        Err("Update simulation is only available in debug builds".to_string())
+    }
+}
+
+/// Parse markdown notes with a heading per version, e.g. `## 1.2.0 (2026-03-01)`
+fn parse_markdown(notes: &str) -> Vec<ReleaseNote> {
+    let mut releases: Vec<ReleaseNote> = Vec::new();
+
+    for line in notes.lines().map(str::trim) {
+        if let Some(heading) = line.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            let mut words = heading.split_whitespace();
+            let version = words.find_map(|word| {
+                let word = word.trim_start_matches(['v', '[']).trim_end_matches(']');
+                semver::Version::parse(word).ok()
+            });
+            if let Some(version) = version {
+                let date = heading
+                    .split_whitespace()
+                    .map(|word| word.trim_matches(['(', ')', '-', ' ']))
+                    .find(|word| chrono::NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok())
+                    .map(str::to_string);
+                releases.push(ReleaseNote {
+                    version: version.to_string(),
+                    date,
+                    highlights: Vec::new(),
+                });
+            }
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            if let Some(release) = releases.last_mut() {
+                release.highlights.push(item.trim().to_string());
+            }
+        }
+    }
+
+    releases
+}
+
+/// Keep releases in `(current, target]`, newest first
+fn between(
+    mut releases: Vec<ReleaseNote>,
+    current: &semver::Version,
+    target: Option<&semver::Version>,
+) -> Vec<ReleaseNote> {
+    releases.retain(|release| {
+        semver::Version::parse(release.version.trim_start_matches('v')).is_ok_and(|version| {
+            version > *current && target.is_none_or(|target| version <= *target)
+        })
+    });
+    releases.sort_by(|a, b| {
+        let parse = |r: &ReleaseNote| semver::Version::parse(r.version.trim_start_matches('v'));
+        parse(b).ok().cmp(&parse(a).ok())
+    });
+    releases
+}
+
+/// Get the changelog for the available update
+#[tauri::command]
+pub async fn get_update_changelog<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<UpdateChangelog, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    crate::plugins::require(&app, crate::plugins::UPDATER)?;
+
+    let current = app.package_info().version.clone();
+    let Some(update) = app
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(UpdateChangelog {
+            current_version: current.to_string(),
+            target_version: None,
+            releases: Vec::new(),
+            notes: None,
+        });
+    };
+
+    let target = semver::Version::parse(&update.version).ok();
+    let structured: Vec<ReleaseNote> = update
+        .raw_json
+        .get("changelog")
+        .and_then(|changelog| serde_json::from_value(changelog.clone()).ok())
+        .unwrap_or_default();
+    let mut releases = between(structured, &current, target.as_ref());
+    if releases.is_empty() {
+        let parsed = update.body.as_deref().map(parse_markdown).unwrap_or_default();
+        releases = between(parsed, &current, target.as_ref());
+    }
+
+    let notes = if releases.is_empty() {
+        update.body.clone()
+    } else {
+        None
+    };
+
+    Ok(UpdateChangelog {
+        current_version: current.to_string(),
+        target_version: Some(update.version.clone()),
+        releases,
+        notes,
+    })
+}
+
+/// Announce a fake update exactly like a real check would (debug builds)
+#[tauri::command]
+pub async fn simulate_update_available<R: Runtime>(
+    app: AppHandle<R>,
+    version: String,
+    notes: String,
+) -> Result<(), String> {
+    require_debug_build()?;
+
+    log::info!("Simulating update {}", version);
+    // Repeated simulations of one version should notify every time
+    *app.state::<crate::AppState>().announced_update.lock().unwrap() = None;
+    announce(
+        &app,
+        &UpdateInfo {
+            available: true,
+            version: Some(version),
+            notes: Some(notes),
+            download_size_bytes: Some(SIMULATED_DOWNLOAD_BYTES),
+        },
+    );
+    Ok(())
+}
+
+/// Emit fake download progress from zero to complete (debug builds)
+#[tauri::command]
+pub async fn simulate_update_progress<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    require_debug_build()?;
+
+    tauri::async_runtime::spawn(async move {
+        for step in 0..=SIMULATED_STEPS {
+            let downloaded = SIMULATED_DOWNLOAD_BYTES * step / SIMULATED_STEPS;
+            emit_progress(&app, downloaded, Some(SIMULATED_DOWNLOAD_BYTES));
+            tokio::time::sleep(SIMULATED_STEP_DELAY).await;
+        }
+        set_state(&app, UpdaterState::Idle);
+    });
+    Ok(())
+}
+
+/// Pause the running update download
+#[tauri::command]
+pub fn pause_update_download<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    let control = &state.update_download;
+    if !control.active.load(Ordering::SeqCst) {
+        return Err("No update is downloading".to_string());
+    }
+    control.paused.store(true, Ordering::SeqCst);
+    control.wake.notify_one();
+    Ok(())
+}
+
+/// Resume a paused update download where it stopped, if the server allows
+#[tauri::command]
+pub fn resume_update_download<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    let control = &state.update_download;
+    if !control.active.load(Ordering::SeqCst) || !control.paused.swap(false, Ordering::SeqCst) {
+        return Err("No update download is paused".to_string());
+    }
+    control.wake.notify_one();
+    Ok(())
+}
+
+/// Stop the running update download and discard what was downloaded
+#[tauri::command]
+pub fn cancel_update_download<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    let control = &state.update_download;
+    if !control.active.load(Ordering::SeqCst) {
+        return Err("No update is downloading".to_string());
+    }
+    control.cancelled.store(true, Ordering::SeqCst);
+    control.wake.notify_one();
+    Ok(())
+}
+
+/// Get the updater's current state
+#[tauri::command]
+pub fn get_updater_state<R: Runtime>(app: AppHandle<R>) -> UpdaterState {
+    state(&app)
+}
+
+/// Run the steps of `update_dry_run` up to the first that can't continue
+async fn dry_run<R: Runtime>(app: &AppHandle<R>, report: &mut DryRunReport) {
+    use tauri_plugin_updater::UpdaterExt;
+
+    let started = Instant::now();
+    let checked = match app.updater() {
+        Ok(updater) => updater.check().await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let checked = checked.and_then(|update| {
+        update.ok_or_else(|| "No update is available, so nothing can be downloaded".to_string())
+    });
+    let update = match checked {
+        Ok(update) => update,
+        Err(e) => {
+            report.record(DryRunStepKind::Check, started, Err(e));
+            return;
+        }
+    };
+    report.target_version = Some(update.version.clone());
+    let found = format!("Found {} at {}", update.version, update.download_url);
+    report.record(DryRunStepKind::Check, started, Ok(found));
+
+    let started = Instant::now();
+    let bytes = match download(app, &update).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            report.record(DryRunStepKind::Download, started, Err(e));
+            return;
+        }
+    };
+    let downloaded = format!("Downloaded {} bytes", bytes.len());
+    report.record(DryRunStepKind::Download, started, Ok(downloaded));
+
+    let started = Instant::now();
+    let size = match crate::network::content_length(&update.download_url).await {
+        _ if bytes.is_empty() => Err("The package is empty".to_string()),
+        Some(expected) if expected != bytes.len() as u64 => Err(format!(
+            "The server advertises {} bytes but sent {}",
+            expected,
+            bytes.len()
+        )),
+        Some(expected) => Ok(format!("{} bytes, as advertised", expected)),
+        None => Ok(format!("{} bytes; the server doesn't advertise a size", bytes.len())),
+    };
+    report.record(DryRunStepKind::Size, started, size);
+
+    let started = Instant::now();
+    let signature = verify_signature(app, &bytes, &update.signature)
+        .map(|()| "Matches the updater's public key".to_string());
+    report.record(DryRunStepKind::Signature, started, signature);
+}
+
+/// Check for and download an update and verify its size and signature,
+/// without installing it
+///
+/// Emits the usual updater state and download progress events. Each step's
+/// outcome is in the report; a run only passes when an update is available.
+#[tauri::command]
+pub async fn update_dry_run<R: Runtime>(app: AppHandle<R>) -> Result<DryRunReport, String> {
+    crate::plugins::require(&app, crate::plugins::UPDATER)?;
+    if state(&app).is_installing() {
+        return Err("An update is already being installed".to_string());
+    }
+
+    log::info!("Starting an update dry run");
+    let mut report = DryRunReport {
+        current_version: app.package_info().version.to_string(),
+        target_version: None,
+        steps: Vec::new(),
+        passed: false,
+    };
+    set_state(&app, UpdaterState::Checking);
+    dry_run(&app, &mut report).await;
+    set_state(&app, UpdaterState::Idle);
+
+    let finished = report
+        .steps
+        .last()
+        .is_some_and(|last| last.step == DryRunStepKind::Signature);
+    report.passed = finished && report.steps.iter().all(|step| step.ok);
+    log::info!("Update dry run {}", if report.passed { "passed" } else { "failed" });
+    Ok(report)
+}
+
+/// Get the version this launch was updated from, if it follows an update
+#[tauri::command]
+pub fn get_updated_from<R: Runtime>(app: AppHandle<R>) -> Option<UpdatedFrom> {
+    app.state::<crate::AppState>()
+        .updated_from
+        .lock()
+        .unwrap()
+        .clone()
+}
+
+/// Choose where updates are downloaded; `None` restores the system temp
+/// directory
+///
+/// The directory must be writable and have room for an update.
+#[tauri::command]
+pub async fn set_update_download_dir<R: Runtime>(
+    app: AppHandle<R>,
+    path: Option<String>,
+) -> Result<(), String> {
+    let dir = path
+        .map(|path| validate_download_dir(Path::new(&path)))
+        .transpose()?;
+    log::info!("Update download directory set to {:?}", dir);
+    crate::settings::update(&app, |settings| settings.update_download_dir = dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTES: &str = "\
+# Changelog
+
+Intro text that isn't a release.
+- Not under a version
+
+## [1.2.0] - 2026-03-01
+- Faster startup
+* Quieter notifications
+
+## v1.1.0 (2026-02-01)
+- Offline cache
+
+### 1.0.0
+";
+
+    #[test]
+    fn parses_versions_dates_and_highlights() {
+        let releases = parse_markdown(NOTES);
+        let versions: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, ["1.2.0", "1.1.0", "1.0.0"]);
+
+        assert_eq!(releases[0].date.as_deref(), Some("2026-03-01"));
+        assert_eq!(releases[0].highlights, ["Faster startup", "Quieter notifications"]);
+        assert_eq!(releases[1].date.as_deref(), Some("2026-02-01"));
+        assert_eq!(releases[1].highlights, ["Offline cache"]);
+        assert_eq!(releases[2].date, None);
+        assert!(releases[2].highlights.is_empty());
+    }
+
+    #[test]
+    fn ignores_notes_without_versions() {
+        assert!(parse_markdown("Bug fixes\n- Something").is_empty());
+    }
+
+    #[test]
+    fn keeps_releases_after_current_up_to_target() {
+        let current = semver::Version::new(1, 0, 0);
+        let target = semver::Version::new(1, 1, 0);
+        let releases = between(parse_markdown(NOTES), &current, Some(&target));
+        let versions: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, ["1.1.0"]);
+
+        let releases = between(parse_markdown(NOTES), &current, None);
+        let versions: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, ["1.2.0", "1.1.0"]);
+    }
+}
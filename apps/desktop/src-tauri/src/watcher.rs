@@ -0,0 +1,136 @@
+//! Directory watching for YULA Desktop
+//!
+//! Backs the "watched folder" feature: changes under a watched directory are
+//! debounced (editors often write a file several times in a row) and emitted
+//! as `fs-change`. Watchers live in `AppState` and are stopped on exit.
+
+use notify::{EventKind, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Quiet period before a burst of changes is reported
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Identifier of a directory watch
+pub type WatchId = u32;
+
+/// Kind of change reported in `fs-change`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ChangeKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+/// Payload of the `fs-change` event
+#[derive(Debug, Clone, Serialize)]
+struct FsChangePayload {
+    watch_id: WatchId,
+    kind: ChangeKind,
+    paths: Vec<String>,
+}
+
+/// Active directory watches
+#[derive(Default)]
+pub struct DirectoryWatchers {
+    next_id: WatchId,
+    active: HashMap<WatchId, Debouncer<notify::RecommendedWatcher, RecommendedCache>>,
+}
+
+impl std::fmt::Debug for DirectoryWatchers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirectoryWatchers")
+            .field("active", &self.active.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Stop every watch, called on exit
+pub fn stop_all<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        state.watchers.lock().unwrap().active.clear();
+    }
+}
+
+/// Start watching a directory, returning an id for `unwatch_directory`
+#[tauri::command]
+pub async fn watch_directory<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    recursive: bool,
+) -> Result<WatchId, String> {
+    let path = crate::files::check_scope(&app, &path).map_err(|e| e.to_string())?;
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+
+    let state = app.state::<crate::AppState>();
+    let mut watchers = state.watchers.lock().unwrap();
+    watchers.next_id = watchers.next_id.wrapping_add(1).max(1);
+    let watch_id = watchers.next_id;
+
+    let handle = app.clone();
+    let mut debouncer = new_debouncer(DEBOUNCE, None, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(errors) => {
+                for e in errors {
+                    log::warn!("Directory watch {} error: {}", watch_id, e);
+                }
+                return;
+            }
+        };
+
+        for event in events {
+            let kind = match event.kind {
+                EventKind::Create(_) => ChangeKind::Create,
+                EventKind::Modify(_) => ChangeKind::Modify,
+                EventKind::Remove(_) => ChangeKind::Delete,
+                _ => continue,
+            };
+            let paths = event
+                .paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            crate::events::emit(
+                &handle,
+                crate::events::FS_CHANGE,
+                FsChangePayload {
+                    watch_id,
+                    kind,
+                    paths,
+                },
+            );
+        }
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    debouncer.watch(&path, mode).map_err(|e| e.to_string())?;
+
+    log::info!("Watching {} (id {})", path.display(), watch_id);
+    watchers.active.insert(watch_id, debouncer);
+    Ok(watch_id)
+}
+
+/// Stop watching a directory
+#[tauri::command]
+pub fn unwatch_directory<R: Runtime>(app: AppHandle<R>, id: WatchId) -> Result<(), String> {
+    app.state::<crate::AppState>()
+        .watchers
+        .lock()
+        .unwrap()
+        .active
+        .remove(&id)
+        .map(|_| ())
+        .ok_or_else(|| format!("Directory watch not found: {}", id))
+}
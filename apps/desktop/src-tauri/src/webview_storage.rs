@@ -0,0 +1,242 @@
+//! Webview cache and storage management for YULA Desktop
+//!
+//! Sizes and clears what the webview keeps on disk for the active profile,
+//! so users can recover from corrupt cached assets without reinstalling.
+//! Each engine has its own layout: WebView2 keeps everything under
+//! `EBWebView/Default`, WKWebView splits caches (`~/Library/Caches`) from
+//! website data (`~/Library/WebKit`), and WebKitGTK uses the XDG data and
+//! cache directories. Only directories inside those roots are touched, and
+//! paths that resolve anywhere else are refused.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::CommandError;
+
+/// A kind of webview storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageKind {
+    /// HTTP, code and GPU caches
+    Cache,
+    LocalStorage,
+    IndexedDb,
+}
+
+/// Bytes the webview stores on disk, by kind
+#[derive(Debug, Clone, Serialize)]
+pub struct WebviewStorageUsage {
+    pub cache_bytes: u64,
+    pub local_storage_bytes: u64,
+    pub indexeddb_bytes: u64,
+}
+
+/// Where the webview keeps its data for the active profile
+struct Layout {
+    /// Directories everything else must stay within
+    roots: Vec<PathBuf>,
+    cache: Vec<PathBuf>,
+    local_storage: Vec<PathBuf>,
+    indexeddb: Vec<PathBuf>,
+}
+
+impl Layout {
+    fn dirs(&self, kind: StorageKind) -> &[PathBuf] {
+        match kind {
+            StorageKind::Cache => &self.cache,
+            StorageKind::LocalStorage => &self.local_storage,
+            StorageKind::IndexedDb => &self.indexeddb,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn layout<R: Runtime>(app: &AppHandle<R>) -> Result<Layout, String> {
+    // Profiles get their own data directory; see `profiles::isolate_webview`
+    let base = match crate::profiles::active(app) {
+        Some(_) => crate::profiles::data_dir(app)?.join("webview"),
+        None => app.path().app_local_data_dir().map_err(|e| e.to_string())?,
+    };
+    let root = base.join("EBWebView").join("Default");
+    Ok(Layout {
+        cache: vec![root.join("Cache"), root.join("Code Cache"), root.join("GPUCache")],
+        local_storage: vec![root.join("Local Storage")],
+        indexeddb: vec![root.join("IndexedDB")],
+        roots: vec![root],
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn layout<R: Runtime>(app: &AppHandle<R>) -> Result<Layout, String> {
+    let identifier = &app.config().identifier;
+    let website_data = app
+        .path()
+        .home_dir()
+        .map_err(|e| e.to_string())?
+        .join("Library/WebKit")
+        .join(identifier);
+
+    match crate::profiles::active(app) {
+        // Stores created with an identifier live in one directory named by it
+        Some(profile) => {
+            let hex: String = crate::profiles::webview_store_id(&profile)
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+            let uuid = format!(
+                "{}-{}-{}-{}-{}",
+                &hex[..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..]
+            );
+            let root = website_data.join("WebsiteDataStore").join(uuid);
+            Ok(Layout {
+                cache: vec![root.join("NetworkCache"), root.join("CacheStorage")],
+                local_storage: vec![root.join("LocalStorage")],
+                indexeddb: vec![root.join("IndexedDB")],
+                roots: vec![root],
+            })
+        }
+        None => {
+            let cache = app
+                .path()
+                .app_cache_dir()
+                .map_err(|e| e.to_string())?
+                .join("WebKit");
+            let root = website_data.join("WebsiteData");
+            Ok(Layout {
+                cache: vec![cache.clone()],
+                local_storage: vec![root.join("LocalStorage")],
+                indexeddb: vec![root.join("IndexedDB")],
+                roots: vec![cache, root],
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn layout<R: Runtime>(app: &AppHandle<R>) -> Result<Layout, String> {
+    let (data, cache) = match crate::profiles::active(app) {
+        // A custom data directory holds the cache too
+        Some(_) => {
+            let data = crate::profiles::data_dir(app)?.join("webview");
+            let cache = data.join("cache");
+            (data, cache)
+        }
+        None => (
+            app.path().app_local_data_dir().map_err(|e| e.to_string())?,
+            app.path().app_cache_dir().map_err(|e| e.to_string())?,
+        ),
+    };
+    Ok(Layout {
+        cache: vec![cache.join("WebKitCache"), cache.join("CacheStorage")],
+        local_storage: vec![data.join("localstorage")],
+        indexeddb: vec![data.join("databases").join("indexeddb")],
+        roots: vec![data, cache],
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn layout<R: Runtime>(_app: &AppHandle<R>) -> Result<Layout, String> {
+    Err("Webview storage is not supported on this platform".to_string())
+}
+
+/// Total size of a directory's files, not following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Refuse a path that resolves outside the webview's own directories
+fn check_within(path: &Path, roots: &[PathBuf]) -> Result<(), CommandError> {
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| CommandError::Failed(format!("{}: {}", path.display(), e)))?;
+    let inside = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+    if inside {
+        Ok(())
+    } else {
+        Err(CommandError::PolicyViolation(format!(
+            "{} is outside the webview data directory",
+            path.display()
+        )))
+    }
+}
+
+/// Measure the webview's cache, local storage and IndexedDB on disk
+#[tauri::command]
+pub async fn get_webview_storage_usage<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<WebviewStorageUsage, CommandError> {
+    let layout = layout(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let size = |kind| -> u64 { layout.dirs(kind).iter().map(|dir| dir_size(dir)).sum() };
+        WebviewStorageUsage {
+            cache_bytes: size(StorageKind::Cache),
+            local_storage_bytes: size(StorageKind::LocalStorage),
+            indexeddb_bytes: size(StorageKind::IndexedDb),
+        }
+    })
+    .await
+    .map_err(|e| CommandError::Failed(e.to_string()))
+}
+
+/// Delete the selected kinds of webview storage, then reload every window
+///
+/// Data the webview holds open may survive until the next launch; paths
+/// that couldn't be removed are listed in the error.
+#[tauri::command]
+pub async fn clear_webview_cache<R: Runtime>(
+    app: AppHandle<R>,
+    kinds: Vec<StorageKind>,
+) -> Result<(), CommandError> {
+    let layout = layout(&app)?;
+    let failed = tauri::async_runtime::spawn_blocking(move || {
+        let mut failed = Vec::new();
+        for kind in kinds {
+            for dir in layout.dirs(kind).iter().filter(|dir| dir.exists()) {
+                let result = check_within(dir, &layout.roots).and_then(|()| {
+                    std::fs::remove_dir_all(dir).map_err(|e| CommandError::Failed(e.to_string()))
+                });
+                if let Err(e) = result {
+                    log::warn!("Failed to clear {}: {}", dir.display(), e);
+                    failed.push(dir.display().to_string());
+                }
+            }
+        }
+        failed
+    })
+    .await
+    .map_err(|e| CommandError::Failed(e.to_string()))?;
+
+    // Pages must reload to drop what they already loaded from storage
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.reload() {
+            log::warn!("Failed to reload {}: {}", window.label(), e);
+        }
+    }
+
+    if failed.is_empty() {
+        log::info!("Cleared webview storage");
+        Ok(())
+    } else {
+        Err(CommandError::Failed(format!(
+            "Some storage could not be cleared: {}",
+            failed.join(", ")
+        )))
+    }
+}
@@ -0,0 +1,879 @@
+//! Window management for YULA Desktop
+//!
+//! The main window is declared in `tauri.conf.json` with `create: false` and
+//! built here in `setup`, so preferences that can only be applied when the
+//! webview is created are honored.
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, LogicalSize, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+    Window,
+};
+
+use crate::error::CommandError;
+
+/// Label of the main application window
+pub const MAIN_WINDOW: &str = "main";
+
+/// Minimum main window size used when the config doesn't specify one
+const DEFAULT_MIN_SIZE: (u32, u32) = (900, 600);
+
+/// Physical pixels of a window that must overlap a work area to be reachable
+const MIN_VISIBLE: i32 = 64;
+
+/// Logical size limits applied to a window
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SizeConstraints {
+    pub min: Option<(u32, u32)>,
+    pub max: Option<(u32, u32)>,
+}
+
+/// Whether secondary windows may be opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WindowPolicy {
+    /// Secondary windows open alongside the main window
+    #[default]
+    MultiWindow,
+    /// Only the main window exists; requests to open others focus it
+    SingleWindow,
+}
+
+/// Payload of the `scale-factor-changed` event
+#[derive(Debug, Clone, Serialize)]
+struct ScaleFactorPayload {
+    label: String,
+    scale_factor: f64,
+}
+
+/// Default WebView2 arguments, kept when we pass our own
+#[cfg(target_os = "windows")]
+const WEBVIEW2_DEFAULT_ARGS: &str =
+    "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+/// User agent identifying the desktop app to our API
+pub fn default_user_agent<R: Runtime>(app: &AppHandle<R>) -> String {
+    format!(
+        "Mozilla/5.0 ({}; {}) YULA-Desktop/{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        app.package_info().version
+    )
+}
+
+/// URL of a frontend route for a new window
+///
+/// Always on the bundled origin (the dev server in development), which the
+/// webview keys local storage, IndexedDB and cookies on.
+pub fn frontend_url(path: &str) -> WebviewUrl {
+    WebviewUrl::App(path.into())
+}
+
+/// Look up the main window
+pub fn main_window<R: Runtime>(app: &AppHandle<R>) -> Result<WebviewWindow<R>, String> {
+    app.get_webview_window(MAIN_WINDOW)
+        .ok_or_else(|| "Main window not found".to_string())
+}
+
+/// Look up a window by label
+pub fn get_window<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+) -> Result<WebviewWindow<R>, String> {
+    app.get_webview_window(label)
+        .ok_or_else(|| format!("Window not found: {}", label))
+}
+
+/// Create the main window from its config entry, applying persisted settings
+pub fn create_main_window<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<WebviewWindow<R>, Box<dyn std::error::Error>> {
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW)
+        .cloned()
+        .ok_or("Main window config not found")?;
+
+    let settings = crate::settings::get(app);
+    let mut builder = WebviewWindowBuilder::from_config(app, &config)?;
+    builder = crate::csp::attach(app, builder);
+    builder = crate::profiles::isolate_webview(app, builder);
+
+    // The user agent can only be set before the webview loads
+    let user_agent = settings
+        .user_agent
+        .clone()
+        .unwrap_or_else(|| default_user_agent(app));
+    builder = builder.user_agent(&user_agent);
+    *app.state::<crate::AppState>().user_agent.lock().unwrap() = user_agent;
+
+    // Launched at login: initialize everything but stay in the tray
+    let start_minimized = crate::launch::should_start_minimized(app);
+    if start_minimized {
+        log::info!("Starting minimized to tray");
+        builder = builder.visible(false).focused(false);
+    }
+
+    if !settings.hardware_acceleration {
+        log::info!("Hardware acceleration disabled by user preference");
+
+        #[cfg(target_os = "windows")]
+        {
+            builder = builder
+                .additional_browser_args(&format!("{} --disable-gpu", WEBVIEW2_DEFAULT_ARGS));
+        }
+
+        // WebKitGTK's switches were set at launch by `disable_webkit_compositing`
+
+        // WKWebView offers no switch to disable GPU compositing
+        #[cfg(target_os = "macos")]
+        log::warn!("Disabling hardware acceleration is not supported on macOS");
+    }
+
+    // Effects only show through a transparent window, which costs
+    // compositing work, so it's transparent only while they're on; Linux
+    // has no effects
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    if settings.window_effects.is_some() {
+        builder = builder.transparent(true);
+        app.state::<crate::AppState>()
+            .main_transparent
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    let window = builder.build()?;
+    crate::effects::restore(app, &window);
+
+    #[cfg(not(target_os = "windows"))]
+    if settings.visible_on_all_workspaces {
+        window.set_visible_on_all_workspaces(true)?;
+    }
+
+    // Keep the window from being collapsed to an unusable sliver
+    let min = match (config.min_width, config.min_height) {
+        (Some(width), Some(height)) => (width as u32, height as u32),
+        _ => DEFAULT_MIN_SIZE,
+    };
+    let max = match (config.max_width, config.max_height) {
+        (Some(width), Some(height)) => Some((width as u32, height as u32)),
+        _ => None,
+    };
+    apply_size_constraints(app, &window, Some(min), max)?;
+
+    if start_minimized {
+        crate::throttle::set_throttled(app, true);
+    }
+
+    Ok(window)
+}
+
+/// Turn off WebKitGTK's GPU compositing if hardware acceleration is disabled
+///
+/// WebKitGTK only reads the switches from the environment, and changing it
+/// races with every thread that reads it, so this runs from `main` before
+/// Tauri starts any, reading the settings file itself.
+#[cfg(target_os = "linux")]
+pub fn disable_webkit_compositing() {
+    let config: serde_json::Value =
+        serde_json::from_str(include_str!("../tauri.conf.json")).unwrap_or_default();
+    let Some(dir) = config["identifier"]
+        .as_str()
+        .and_then(crate::profiles::launch_config_dir)
+    else {
+        return;
+    };
+    if !crate::settings::read_at_launch(&dir).hardware_acceleration {
+        std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+        std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    }
+}
+
+/// Enable or disable webview hardware acceleration
+///
+/// The webview can't switch rendering modes live, so the preference takes
+/// effect on the next launch. Returns whether a restart is required.
+#[tauri::command]
+pub async fn set_hardware_acceleration<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<bool, String> {
+    let previous = crate::settings::get(&app).hardware_acceleration;
+    crate::settings::update(&app, |settings| settings.hardware_acceleration = enabled)?;
+
+    let restart_required = previous != enabled;
+    if restart_required {
+        log::warn!("Hardware acceleration set to {}, restart required", enabled);
+    }
+
+    Ok(restart_required)
+}
+
+/// Check whether webview hardware acceleration is enabled
+#[tauri::command]
+pub fn get_hardware_acceleration<R: Runtime>(app: AppHandle<R>) -> bool {
+    crate::settings::get(&app).hardware_acceleration
+}
+
+/// Apply and record the logical size limits of a window
+pub fn apply_size_constraints<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &WebviewWindow<R>,
+    min: Option<(u32, u32)>,
+    max: Option<(u32, u32)>,
+) -> Result<(), String> {
+    if let (Some(min), Some(max)) = (min, max) {
+        if min.0 > max.0 || min.1 > max.1 {
+            return Err("Minimum size must not exceed maximum size".to_string());
+        }
+    }
+
+    window
+        .set_min_size(min.map(|(w, h)| LogicalSize::new(w, h)))
+        .map_err(|e| e.to_string())?;
+    window
+        .set_max_size(max.map(|(w, h)| LogicalSize::new(w, h)))
+        .map_err(|e| e.to_string())?;
+
+    app.state::<crate::AppState>()
+        .size_constraints
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), SizeConstraints { min, max });
+
+    Ok(())
+}
+
+/// Set the minimum and maximum logical size of a window
+#[tauri::command]
+pub async fn set_size_constraints<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    min: Option<(u32, u32)>,
+    max: Option<(u32, u32)>,
+) -> Result<(), String> {
+    let window = get_window(&app, &label)?;
+    apply_size_constraints(&app, &window, min, max)
+}
+
+/// Get the size limits applied to a window
+#[tauri::command]
+pub fn get_size_constraints<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<SizeConstraints, String> {
+    get_window(&app, &label)?;
+
+    Ok(app
+        .state::<crate::AppState>()
+        .size_constraints
+        .lock()
+        .unwrap()
+        .get(&label)
+        .copied()
+        .unwrap_or_default())
+}
+
+/// Show the main window on every workspace / virtual desktop and persist it
+///
+/// Uses the window's collection behavior on macOS and the sticky hint
+/// (`_NET_WM_DESKTOP`) on Linux. Windows has no public API for pinning a
+/// window to all virtual desktops, so it returns `NotSupported`.
+#[tauri::command]
+pub async fn set_visible_on_all_workspaces<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (app, enabled);
+        Err(CommandError::NotSupported(
+            "Pinning to all virtual desktops is not available on Windows".to_string(),
+        ))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        crate::kiosk::ensure_inactive(&app, "Showing on all workspaces")?;
+        main_window(&app)?
+            .set_visible_on_all_workspaces(enabled)
+            .map_err(|e| CommandError::Failed(e.to_string()))?;
+        crate::settings::update(&app, |settings| settings.visible_on_all_workspaces = enabled)?;
+        Ok(())
+    }
+}
+
+/// Raise the main window above other apps, bypassing focus-stealing prevention
+///
+/// Only for when the user explicitly invoked the app (tray click, global
+/// hotkey); raising the window unprompted is hostile and discouraged.
+pub fn bring_to_front<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let window = main_window(app)?;
+    window.unminimize().map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        app.run_on_main_thread(|| unsafe {
+            let ns_app: *mut AnyObject =
+                objc2::msg_send![objc2::class!(NSApplication), sharedApplication];
+            if let Some(ns_app) = ns_app.as_ref() {
+                let _: () = objc2::msg_send![ns_app, activateIgnoringOtherApps: true];
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            AllowSetForegroundWindow, BringWindowToTop, GetForegroundWindow,
+            GetWindowThreadProcessId, SetForegroundWindow, ASFW_ANY,
+        };
+
+        // Windows only lets the thread owning the foreground window hand
+        // focus away, so borrow its input state while raising ours
+        let hwnd = window.hwnd().map_err(|e| e.to_string())?.0 as isize;
+        app.run_on_main_thread(move || unsafe {
+            let hwnd = windows::Win32::Foundation::HWND(hwnd as *mut _);
+            let current_thread = GetCurrentThreadId();
+            let foreground_thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+            let attached = foreground_thread != 0
+                && foreground_thread != current_thread
+                && AttachThreadInput(current_thread, foreground_thread, true).as_bool();
+
+            let _ = AllowSetForegroundWindow(ASFW_ANY);
+            let _ = BringWindowToTop(hwnd);
+            let _ = SetForegroundWindow(hwnd);
+
+            if attached {
+                let _ = AttachThreadInput(current_thread, foreground_thread, false);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    }
+
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Hide a window, handing focus back to the app that was active before
+///
+/// On macOS, hiding the key window leaves focus on an empty desktop. When
+/// `return_focus_on_hide` is set and no other window of ours is visible, the
+/// whole app is hidden as well, which activates the previous app the way
+/// dismissing a native menu bar app does. Showing a window with
+/// `set_focus` activates, and so unhides, the app again.
+pub fn hide<R: Runtime>(window: &Window<R>) -> Result<(), String> {
+    window.hide().map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        let app = window.app_handle();
+        let others_visible = app
+            .webview_windows()
+            .values()
+            .any(|other| other.label() != window.label() && other.is_visible().unwrap_or(false));
+        if crate::settings::get(app).return_focus_on_hide && !others_visible {
+            app.run_on_main_thread(|| unsafe {
+                let ns_app: *mut AnyObject =
+                    objc2::msg_send![objc2::class!(NSApplication), sharedApplication];
+                if let Some(ns_app) = ns_app.as_ref() {
+                    let nil: *mut AnyObject = std::ptr::null_mut();
+                    let _: () = objc2::msg_send![ns_app, hide: nil];
+                }
+            })
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Move a window that is off every monitor onto the primary one
+///
+/// A window counts as reachable while at least a `MIN_VISIBLE` pixel square
+/// of it overlaps some monitor's work area. Otherwise it
+/// is shrunk to fit and centered on the primary monitor, keeping it
+/// maximized if it was. Returns whether the window moved.
+pub fn ensure_on_screen<R: Runtime>(window: &WebviewWindow<R>) -> Result<bool, String> {
+    if window.is_minimized().unwrap_or(false) || window.is_fullscreen().unwrap_or(false) {
+        return Ok(false);
+    }
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+
+    let reachable = monitors.iter().any(|monitor| {
+        let area = monitor.work_area();
+        let overlap = |start: i32, length: u32, area_start: i32, area_length: u32| {
+            (start + length as i32).min(area_start + area_length as i32) - start.max(area_start)
+        };
+        overlap(position.x, size.width, area.position.x, area.size.width) >= MIN_VISIBLE
+            && overlap(position.y, size.height, area.position.y, area.size.height) >= MIN_VISIBLE
+    });
+    if reachable {
+        return Ok(false);
+    }
+
+    let primary = window.primary_monitor().map_err(|e| e.to_string())?;
+    let Some(target) = primary.or_else(|| monitors.into_iter().next()) else {
+        return Ok(false);
+    };
+    let area = target.work_area();
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        window.unmaximize().map_err(|e| e.to_string())?;
+    }
+    // `set_size` takes the inner size, so shrink by the frame difference
+    let width = size.width.min(area.size.width);
+    let height = size.height.min(area.size.height);
+    if (width, height) != (size.width, size.height) {
+        let inner = window.inner_size().map_err(|e| e.to_string())?;
+        window
+            .set_size(tauri::PhysicalSize::new(
+                inner.width.saturating_sub(size.width - width),
+                inner.height.saturating_sub(size.height - height),
+            ))
+            .map_err(|e| e.to_string())?;
+    }
+    window
+        .set_position(tauri::PhysicalPosition::new(
+            area.position.x + (area.size.width - width) as i32 / 2,
+            area.position.y + (area.size.height - height) as i32 / 2,
+        ))
+        .map_err(|e| e.to_string())?;
+    if maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+
+    log::info!("Moved off-screen window {} onto {:?}", window.label(), target.name());
+    Ok(true)
+}
+
+/// Raise the main window above other apps
+///
+/// Use only in response to an explicit user action, e.g. a global hotkey.
+#[tauri::command]
+pub async fn force_foreground<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    bring_to_front(&app)
+}
+
+/// Tell the frontend a window moved to a monitor with a different DPI, and
+/// resize the tray icon to match
+pub fn on_scale_factor_changed<R: Runtime>(window: &tauri::Window<R>, scale_factor: f64) {
+    log::debug!("Window {} scale factor changed: {}", window.label(), scale_factor);
+    crate::tray::refresh_icon_scale(window.app_handle());
+    crate::events::emit(
+        window.app_handle(),
+        crate::events::SCALE_FACTOR_CHANGED,
+        ScaleFactorPayload {
+            label: window.label().to_string(),
+            scale_factor,
+        },
+    );
+}
+
+/// Get the ratio of physical to logical pixels of a window's monitor
+#[tauri::command]
+pub fn get_window_scale_factor<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<f64, String> {
+    get_window(&app, &label)?
+        .scale_factor()
+        .map_err(|e| e.to_string())
+}
+
+/// Create a secondary window at a frontend route, hidden unless `visible`
+pub fn build_secondary<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+    url: &str,
+    visible: bool,
+) -> Result<WebviewWindow<R>, String> {
+    if label == MAIN_WINDOW {
+        return Err("The main window can't be opened as a secondary window".to_string());
+    }
+    // Only frontend routes; an absolute URL would load remote content
+    if url.contains("://") || url.starts_with("//") {
+        return Err(format!("Window URL must be an app route: {}", url));
+    }
+
+    let builder = WebviewWindowBuilder::new(app, label, frontend_url(url.trim_start_matches('/')));
+    let builder = crate::csp::attach(app, builder);
+    crate::profiles::isolate_webview(app, builder)
+        .title("YULA")
+        .inner_size(1280.0, 800.0)
+        .visible(visible)
+        .focused(visible)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Show a window and give it focus
+fn reveal<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    window.unminimize().map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// The window policy in effect; kiosk mode always allows a single window
+pub fn window_policy<R: Runtime>(app: &AppHandle<R>) -> WindowPolicy {
+    if crate::kiosk::is_active(app) {
+        WindowPolicy::SingleWindow
+    } else {
+        crate::settings::get(app).window_policy
+    }
+}
+
+/// Reject opening a secondary window under the single-window policy,
+/// focusing the main window instead
+pub fn check_policy<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<(), CommandError> {
+    if window_policy(app) == WindowPolicy::MultiWindow {
+        return Ok(());
+    }
+
+    log::info!("Window {} blocked by the single-window policy", label);
+    reveal(&main_window(app)?)?;
+    Err(CommandError::PolicyViolation(format!(
+        "Opening window {} is not allowed under the single-window policy",
+        label
+    )))
+}
+
+/// Forget a closed window's preload and taskbar state
+pub fn on_window_destroyed<R: Runtime>(window: &tauri::Window<R>) {
+    if let Some(state) = window.try_state::<crate::AppState>() {
+        state.preloaded.lock().unwrap().remove(window.label());
+        state.skip_taskbar.lock().unwrap().remove(window.label());
+        state.content_protected.lock().unwrap().remove(window.label());
+    }
+}
+
+/// Choose whether hiding the last window returns focus to the previous app
+///
+/// Only has an effect on macOS, where focus otherwise stays on the desktop.
+#[tauri::command]
+pub async fn set_return_focus_on_hide<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    crate::settings::update(&app, |settings| settings.return_focus_on_hide = enabled)
+}
+
+/// Hide a window from the taskbar and alt-tab, or show it there again
+///
+/// macOS has no taskbar entry per window, so the window is left out of the
+/// Cmd+` window cycle instead.
+pub fn apply_skip_taskbar<R: Runtime>(window: &WebviewWindow<R>, skip: bool) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::runtime::AnyObject;
+
+        /// `NSWindowCollectionBehaviorParticipatesInCycle`
+        const PARTICIPATES_IN_CYCLE: usize = 1 << 5;
+        /// `NSWindowCollectionBehaviorIgnoresCycle`
+        const IGNORES_CYCLE: usize = 1 << 6;
+
+        // Raw pointers aren't Send, so hand the address to the main thread
+        let ns_window = window.ns_window().map_err(|e| e.to_string())? as usize;
+        window
+            .run_on_main_thread(move || unsafe {
+                let ns_window = &*(ns_window as *const AnyObject);
+                let behavior: usize = objc2::msg_send![ns_window, collectionBehavior];
+                let behavior = if skip {
+                    (behavior & !PARTICIPATES_IN_CYCLE) | IGNORES_CYCLE
+                } else {
+                    behavior & !IGNORES_CYCLE
+                };
+                let _: () = objc2::msg_send![ns_window, setCollectionBehavior: behavior];
+            })
+            .map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    window.set_skip_taskbar(skip).map_err(|e| e.to_string())?;
+
+    window
+        .state::<crate::AppState>()
+        .skip_taskbar
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), skip);
+    Ok(())
+}
+
+/// Open a secondary window at a frontend route, revealing it if it exists
+///
+/// A window created by `preload_window` appears instantly. Under the
+/// single-window policy this focuses the main window and fails with
+/// `PolicyViolation`.
+#[tauri::command]
+pub async fn open_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    url: String,
+) -> Result<(), CommandError> {
+    check_policy(&app, &label)?;
+
+    if let Some(window) = app.get_webview_window(&label) {
+        app.state::<crate::AppState>()
+            .preloaded
+            .lock()
+            .unwrap()
+            .remove(&label);
+        return Ok(reveal(&window)?);
+    }
+
+    build_secondary(&app, &label, &url, true)?;
+    Ok(())
+}
+
+/// Create a window hidden in the background so opening it later is instant
+///
+/// Does nothing if a window with this label already exists. Fails with
+/// `PolicyViolation` under the single-window policy.
+#[tauri::command]
+pub async fn preload_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    url: String,
+) -> Result<(), CommandError> {
+    if window_policy(&app) == WindowPolicy::SingleWindow {
+        return Err(CommandError::PolicyViolation(format!(
+            "Preloading window {} is not allowed under the single-window policy",
+            label
+        )));
+    }
+    if app.get_webview_window(&label).is_some() {
+        return Ok(());
+    }
+
+    build_secondary(&app, &label, &url, false)?;
+    log::info!("Preloaded window {}", label);
+    app.state::<crate::AppState>()
+        .preloaded
+        .lock()
+        .unwrap()
+        .insert(label);
+    Ok(())
+}
+
+/// Reveal a window created by `preload_window`
+#[tauri::command]
+pub async fn show_preloaded_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<(), String> {
+    let preloaded = app
+        .state::<crate::AppState>()
+        .preloaded
+        .lock()
+        .unwrap()
+        .remove(&label);
+    if !preloaded {
+        return Err(format!("Window was not preloaded: {}", label));
+    }
+
+    reveal(&get_window(&app, &label)?)
+}
+
+/// Allow or forbid secondary windows and persist the choice
+///
+/// Switching to `SingleWindow` affects later requests; windows that are
+/// already open stay open.
+#[tauri::command]
+pub async fn set_window_policy<R: Runtime>(
+    app: AppHandle<R>,
+    policy: WindowPolicy,
+) -> Result<(), String> {
+    log::info!("Window policy: {:?}", policy);
+    crate::settings::update(&app, |settings| settings.window_policy = policy)
+}
+
+/// Get the persisted window policy
+#[tauri::command]
+pub fn get_window_policy<R: Runtime>(app: AppHandle<R>) -> WindowPolicy {
+    window_policy(&app)
+}
+
+/// Hide a window from the taskbar and alt-tab (the window cycle on macOS)
+#[tauri::command]
+pub async fn set_skip_taskbar<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    skip: bool,
+) -> Result<(), String> {
+    apply_skip_taskbar(&get_window(&app, &label)?, skip)
+}
+
+/// Check whether a window was hidden from the taskbar with `set_skip_taskbar`
+#[tauri::command]
+pub fn get_skip_taskbar<R: Runtime>(app: AppHandle<R>, label: String) -> Result<bool, String> {
+    get_window(&app, &label)?;
+    Ok(app
+        .state::<crate::AppState>()
+        .skip_taskbar
+        .lock()
+        .unwrap()
+        .get(&label)
+        .copied()
+        .unwrap_or(false))
+}
+
+/// Keep a window out of screenshots, recordings and screen shares, where it
+/// shows as black or blank
+///
+/// Fails with `NotSupported` on Linux, where compositors offer no way to.
+#[tauri::command]
+pub async fn set_content_protection<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    #[cfg(not(target_os = "linux"))]
+    {
+        app.get_webview_window(&label)
+            .ok_or_else(|| CommandError::WindowNotFound(label.clone()))?
+            .set_content_protected(enabled)
+            .map_err(|e| CommandError::Failed(e.to_string()))?;
+
+        log::info!("Content protection for {}: {}", label, enabled);
+        app.state::<crate::AppState>()
+            .content_protected
+            .lock()
+            .unwrap()
+            .insert(label, enabled);
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = (app, label, enabled);
+        Err(CommandError::NotSupported(
+            "Content protection isn't available on Linux".to_string(),
+        ))
+    }
+}
+
+/// Check whether a window was kept out of captures with `set_content_protection`
+#[tauri::command]
+pub fn get_content_protection<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> Result<bool, CommandError> {
+    if app.get_webview_window(&label).is_none() {
+        return Err(CommandError::WindowNotFound(label));
+    }
+    Ok(app
+        .state::<crate::AppState>()
+        .content_protected
+        .lock()
+        .unwrap()
+        .get(&label)
+        .copied()
+        .unwrap_or(false))
+}
+
+/// Reload a window's page, e.g. to recover from webview memory bloat
+#[tauri::command]
+pub async fn reload_window<R: Runtime>(app: AppHandle<R>, label: String) -> Result<(), String> {
+    get_window(&app, &label)?
+        .reload()
+        .map_err(|e| e.to_string())
+}
+
+/// Run a script in a window's page, for integration tests and support repairs
+///
+/// Only available in debug builds and builds with the `webview-eval`
+/// feature; elsewhere it fails with `NotSupported`.
+#[tauri::command]
+pub fn eval_in_webview<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+    script: String,
+) -> Result<(), CommandError> {
+    #[cfg(any(debug_assertions, feature = "webview-eval"))]
+    {
+        let window = app
+            .get_webview_window(&label)
+            .ok_or(CommandError::WindowNotFound(label))?;
+        log::warn!("Evaluating {} bytes of script in {}", script.len(), window.label());
+        window
+            .eval(&script)
+            .map_err(|e| CommandError::Failed(e.to_string()))
+    }
+
+    #[cfg(not(any(debug_assertions, feature = "webview-eval")))]
+    {
+        let _ = (app, label, script);
+        Err(CommandError::NotSupported(
+            "This build doesn't allow evaluating scripts".to_string(),
+        ))
+    }
+}
+
+/// Get the user agent the main webview was created with
+#[tauri::command]
+pub fn get_user_agent<R: Runtime>(app: AppHandle<R>) -> String {
+    app.state::<crate::AppState>()
+        .user_agent
+        .lock()
+        .unwrap()
+        .clone()
+}
+
+/// Trim a custom user agent, rejecting empty ones
+pub fn normalize_user_agent(user_agent: Option<String>) -> Result<Option<String>, String> {
+    let user_agent = user_agent.map(|ua| ua.trim().to_string());
+    if user_agent.as_deref() == Some("") {
+        return Err("User agent must not be empty".to_string());
+    }
+    Ok(user_agent)
+}
+
+/// Set a custom webview user agent, or `None` to restore the default
+///
+/// Takes effect on the next launch. Returns whether a restart is required.
+#[tauri::command]
+pub async fn set_user_agent<R: Runtime>(
+    app: AppHandle<R>,
+    user_agent: Option<String>,
+) -> Result<bool, String> {
+    let user_agent = normalize_user_agent(user_agent)?;
+    let next = user_agent.clone().unwrap_or_else(|| default_user_agent(&app));
+    crate::settings::update(&app, |settings| settings.user_agent = user_agent)?;
+
+    Ok(next != get_user_agent(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The webview keys local storage, IndexedDB and cookies on the origin,
+    // so loading pages from another one would sign every user out
+    #[test]
+    fn windows_keep_the_bundled_origin() {
+        assert_eq!(frontend_url(""), WebviewUrl::App("".into()));
+        assert_eq!(frontend_url("chat"), WebviewUrl::App("chat".into()));
+
+        let config: serde_json::Value =
+            serde_json::from_str(include_str!("../tauri.conf.json")).unwrap();
+        let main = config["app"]["windows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|window| window["label"] == MAIN_WINDOW)
+            .unwrap();
+        assert!(main["url"].as_str().is_none_or(|url| !url.contains(':')));
+        assert!(main["useHttpsScheme"].is_null());
+    }
+}
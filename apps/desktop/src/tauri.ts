@@ -5,147 +5,3006 @@
  * These functions communicate with the Rust backend.
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke as invokeCommand, type InvokeArgs } from '@tauri-apps/api/core';
 import { listen, emit } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+
+// ==================== Command Timing ====================
+
+/** Samples reported at once, or after a short delay if fewer arrive */
+const COMMAND_SAMPLE_BATCH = 100;
+const COMMAND_SAMPLE_DELAY_MS = 5000;
+
+const commandSamples: { command: string; duration_ms: number; ok: boolean }[] = [];
+let commandSampleTimer: ReturnType<typeof setTimeout> | null = null;
+
+/**
+ * Invoke a command, timing it from request to reply for the local command
+ * metrics. The samples never leave the device.
+ */
+async function invoke<T>(command: string, args?: InvokeArgs): Promise<T> {
+    const started = performance.now();
+    let ok = false;
+    try {
+        const result = await invokeCommand<T>(command, args);
+        ok = true;
+        return result;
+    } finally {
+        commandSamples.push({ command, duration_ms: performance.now() - started, ok });
+        if (commandSamples.length >= COMMAND_SAMPLE_BATCH) {
+            void flushCommandSamples();
+        } else if (commandSampleTimer === null) {
+            commandSampleTimer = setTimeout(
+                () => void flushCommandSamples(),
+                COMMAND_SAMPLE_DELAY_MS
+            );
+        }
+    }
+}
+
+/**
+ * Report the pending samples, untimed so reporting doesn't measure itself
+ */
+async function flushCommandSamples(): Promise<void> {
+    if (commandSampleTimer !== null) {
+        clearTimeout(commandSampleTimer);
+        commandSampleTimer = null;
+    }
+    if (commandSamples.length === 0) {
+        return;
+    }
+    const samples = commandSamples.splice(0);
+    await invokeCommand('record_command_samples', { samples }).catch(() => {});
+}
 
 // ==================== Types ====================
 
-export interface SystemInfo {
-    platform: string;
-    arch: string;
-    os_version: string;
-    hostname: string;
+export interface SystemInfo {
+    platform: string;
+    arch: string;
+    os_version: string;
+    hostname: string;
+}
+
+export interface UpdateInfo {
+    available: boolean;
+    version: string | null;
+    notes: string | null;
+    download_size_bytes: number | null;
+}
+
+export interface ReleaseNote {
+    version: string;
+    date: string | null;
+    highlights: string[];
+}
+
+export interface UpdateChangelog {
+    current_version: string;
+    target_version: string | null;
+    /** Releases newer than the running version, newest first */
+    releases: ReleaseNote[];
+    /** Raw notes, set when they couldn't be structured */
+    notes: string | null;
+}
+
+export interface RollbackInfo {
+    previous_version: string;
+    updated_version: string;
+    /** Installation replaced by the update */
+    installed_path: string;
+    /** Copy of the installation before the update */
+    backup_path: string;
+    /** When the backup was taken, Unix millis */
+    recorded_at: number;
+    /** Consecutive launches of the updated version that crashed */
+    crash_count: number;
+}
+
+export interface ScaleFactorChange {
+    label: string;
+    scale_factor: number;
+}
+
+export interface WebviewMemoryHigh {
+    rss_bytes: number;
+    limit_bytes: number;
+}
+
+export interface ScheduledNotification {
+    id: number;
+    title: string;
+    body: string;
+    /** Wall-clock time the notification is due, e.g. `2026-03-29T09:00:00` */
+    local_time: string;
+    /** IANA zone of `local_time`; null follows the system zone */
+    timezone: string | null;
+    /** Resolved fire time, Unix millis */
+    fire_at: number;
+}
+
+export type TaskState = 'running' | 'sleeping' | 'suspended';
+
+export interface TaskStatus {
+    name: string;
+    state: TaskState;
+    /** Polling interval before throttling */
+    interval_ms: number;
+    /** When the task last started an iteration, Unix millis */
+    last_run_at: number | null;
+}
+
+export interface BackgroundTaskStatus {
+    suspended: boolean;
+    tasks: TaskStatus[];
+}
+
+export type CheckStatus = 'passed' | 'failed' | 'skipped';
+
+export interface SelfTestCheck {
+    name: string;
+    status: CheckStatus;
+    message: string | null;
+}
+
+export interface SelfTestReport {
+    /** Whether no check failed */
+    passed: boolean;
+    checks: SelfTestCheck[];
+    /** When the test ran, Unix millis */
+    ran_at: number;
+}
+
+export type MediaKeyAction = 'play' | 'pause' | 'play-pause' | 'next' | 'previous' | 'stop';
+
+/** Microphone input level, both values between 0 and 1 */
+export interface AudioLevel {
+    rms: number;
+    peak: number;
+}
+
+export interface CpuArchitectureDetails {
+    /** Architecture of the hardware, e.g. `aarch64` */
+    native_arch: string;
+    /** Architecture this binary was built for */
+    process_arch: string;
+    is_emulated: boolean;
+}
+
+export interface WindowLayout {
+    label: string;
+    /** Frontend route the window was showing, e.g. `chat/42` */
+    url: string;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    maximized: boolean;
+    fullscreen: boolean;
+    always_on_top: boolean;
+    visible: boolean;
+}
+
+export interface Layout {
+    name: string;
+    windows: WindowLayout[];
+    /** When the layout was saved, Unix millis */
+    saved_at: number;
+}
+
+export interface SystemLogLine {
+    /** Tail the line belongs to, as returned by `tailSystemLog` */
+    id: number;
+    line: string;
+    /** Lines dropped by the rate limit since the previous one */
+    dropped: number;
+}
+
+/** Whether secondary windows may be opened */
+export type WindowPolicy = 'MultiWindow' | 'SingleWindow';
+
+export interface EventInfo {
+    name: string;
+    /** TypeScript type of the payload, as declared in this file */
+    payload: string;
+    description: string;
+}
+
+/** Native light/dark appearance; `System` follows the OS */
+export type AppearanceMode = 'System' | 'Light' | 'Dark';
+
+export interface AppearanceChange {
+    mode: AppearanceMode;
+    /** Theme in effect after the change */
+    theme: 'light' | 'dark';
+}
+
+export interface DownloadProgress {
+    downloaded_bytes: number;
+    /** Size of the package, when the server reports it */
+    total_bytes: number | null;
+}
+
+export type UpdaterState =
+    | { state: 'idle' }
+    | { state: 'checking' }
+    | { state: 'available'; version: string }
+    | { state: 'downloading'; downloaded_bytes: number; total_bytes: number | null }
+    | { state: 'paused'; downloaded_bytes: number; total_bytes: number | null }
+    | { state: 'installing' }
+    | { state: 'failed'; error: string };
+
+export type DryRunStepKind = 'check' | 'download' | 'size' | 'signature';
+
+export interface DryRunStep {
+    step: DryRunStepKind;
+    ok: boolean;
+    /** What was found, or why the step failed */
+    detail: string;
+    duration_ms: number;
+}
+
+export interface DryRunReport {
+    current_version: string;
+    /** Version the update server offered, if any */
+    target_version: string | null;
+    /** Steps that ran; a failed check or download ends the run */
+    steps: DryRunStep[];
+    /** Whether every step ran and succeeded */
+    passed: boolean;
+}
+
+export interface UpdatedFrom {
+    old_version: string;
+    /** The running version */
+    new_version: string;
+}
+
+export interface ClockSkew {
+    /** Reference time minus local time; positive when the local clock is behind */
+    offset_secs: number;
+    /** Whether the skew (over a minute) is likely to break sign-in and TLS */
+    is_significant: boolean;
+}
+
+export interface ProfileInfo {
+    name: string;
+    active: boolean;
+}
+
+export interface CommandStats {
+    command: string;
+    calls: number;
+    failures: number;
+    /** Over the most recent 512 calls */
+    p50_ms: number;
+    p95_ms: number;
+}
+
+export interface SessionStats {
+    app_uptime_secs: number;
+    /** Uptime for display, e.g. `2h 5m` */
+    app_uptime_formatted: string;
+    /** Unix millis */
+    session_start: number;
+    total_sessions: number;
+    /** Includes the running session */
+    total_runtime_secs: number;
+}
+
+export interface DiskUsage {
+    /** Where the volume is mounted */
+    mount_point: string;
+    total_bytes: number;
+    available_bytes: number;
+}
+
+export interface AutoLockSettings {
+    enabled: boolean;
+    /** Minutes without input before locking */
+    idle_minutes: number;
+    /** Hide every window when locking */
+    hide_windows: boolean;
+}
+
+export interface AutoLockEvent {
+    reason: 'Idle' | 'Sleep';
+}
+
+/** Tray icon bounds in physical pixels */
+export interface TrayRect {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface SignatureStatus {
+    signed: boolean;
+    /** Signed and unmodified since signing */
+    valid: boolean;
+    /** Apple developer team that signed the bundle (macOS) */
+    team_id: string | null;
+    /** Whether Gatekeeper accepts the bundle as notarized, `null` off macOS */
+    notarized: boolean | null;
+}
+
+/** `Auto` adapts to focus and power, using native notifications where available */
+export type ClipboardPollStrategy = 'Auto' | 'Fast' | 'Slow' | 'Off';
+
+export type StorageKind = 'Cache' | 'LocalStorage' | 'IndexedDb';
+
+export interface WebviewStorageUsage {
+    cache_bytes: number;
+    local_storage_bytes: number;
+    indexeddb_bytes: number;
+}
+
+/** A rectangle in physical pixels */
+export interface DisplayRect {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface DisplayInfo {
+    name: string | null;
+    bounds: DisplayRect;
+    /** Bounds minus the menu bar, dock or taskbar */
+    work_area: DisplayRect;
+    scale_factor: number;
+    is_primary: boolean;
+}
+
+export interface MonitorScaling {
+    name: string | null;
+    scale_factor: number;
+    /** Not a whole multiple, like 150% */
+    fractional: boolean;
+    /** Size of the bundled tray icon used on this monitor, 1 to 3 */
+    icon_scale: number;
+}
+
+export interface EffectiveScaling {
+    /** Primary monitor first */
+    monitors: MonitorScaling[];
+    /** Monitors with different scale factors are connected */
+    mixed: boolean;
+}
+
+export interface DisplayColorInfo {
+    /** Index in `getDisplayArrangement` */
+    display_index: number;
+    name: string | null;
+    /** Color space or ICC profile name, e.g. "Display P3"; null where the OS doesn't say */
+    color_space: string | null;
+    /** Null where the OS doesn't say; on Windows, whether HDR is turned on */
+    hdr_capable: boolean | null;
+}
+
+export type ExportFormat = 'Json' | 'Toml';
+
+export interface PluginStatus {
+    available: boolean;
+    error: string | null;
+}
+
+export interface SettingsValidation {
+    valid: boolean;
+    error: string | null;
+}
+
+export interface SettingsResetEvent {
+    reason: string;
+    backup_path: string | null;
+}
+
+export type BlurMaterial =
+    | 'Sidebar'
+    | 'Popover'
+    | 'HudWindow'
+    | 'UnderWindowBackground'
+    | 'Mica'
+    | 'Acrylic'
+    | 'Blur';
+
+export interface WindowEffects {
+    /** Materials to try in order; each platform uses the ones it supports */
+    materials: BlurMaterial[];
+    /** Corner radius of the effect view (macOS only) */
+    radius?: number;
+}
+
+export interface DecorationsStyle {
+    /** Logical corner radius, 0-64; Windows 11 rounds to one of two fixed sizes */
+    corner_radius: number;
+    shadow: boolean;
+}
+
+export interface SizeConstraints {
+    /** Minimum logical size as [width, height] */
+    min: [number, number] | null;
+    /** Maximum logical size as [width, height] */
+    max: [number, number] | null;
+}
+
+export interface RecordedEvent {
+    event: string;
+    payload: unknown;
+    /** Window label the event was sent to, or null for broadcasts */
+    target: string | null;
+    /** Unix timestamp in milliseconds */
+    timestamp: number;
+}
+
+/**
+ * Region selected in the quick capture overlay, in physical screen pixels
+ */
+export interface CaptureRegion {
+    x: number;
+    y: number;
+    w: number;
+    h: number;
+}
+
+export type AppDir = 'Config' | 'Data' | 'Cache' | 'Log';
+
+export type ActivationPolicy = 'Regular' | 'Accessory' | 'Prohibited';
+
+export type IntegrationAction =
+    | { action: 'new-chat'; text: string | null }
+    | { action: 'open-url'; url: string };
+
+export type TrayStatus = 'Online' | 'Away' | 'Busy' | 'Offline';
+
+export type TrayAction = 'new-chat' | 'council' | 'check-updates' | 'preferences';
+
+/**
+ * What a left click on the tray icon does; `Auto` opens the menu on macOS
+ * and shows the window elsewhere
+ */
+export type TrayClickBehavior = 'Auto' | 'ShowMenu' | 'ShowWindow';
+
+/**
+ * Error returned by commands with machine-readable failure kinds
+ */
+export type CommandError =
+    | {
+          kind:
+              | 'InvalidAccelerator'
+              | 'ShortcutAlreadyRegistered'
+              | 'NotSupported'
+              | 'PermissionDenied'
+              | 'NotFound'
+              | 'WindowNotFound'
+              | 'PolicyViolation'
+              | 'Failed';
+          message: string;
+      }
+    | { kind: 'InvalidSettings'; message: string[] };
+
+export type NotificationResult = 'Shown' | 'RateLimited' | 'Digested';
+
+/** Payload of `notification-digest-click` */
+export interface NotificationDigest {
+    /** Notifications the digest stood for */
+    count: number;
+    /** Their groups, e.g. chat ids, in the order they first arrived */
+    groups: string[];
+}
+
+export interface DeliveredNotification {
+    id: number;
+    title: string;
+    body: string;
+    delivered_at: number;
+}
+
+export interface ShortcutBinding {
+    accelerator: string;
+    action_id: string;
+}
+
+export interface SerialPortInfo {
+    port_name: string;
+    type: 'Usb' | 'Pci' | 'Bluetooth' | 'Unknown';
+    usb_vid: number | null;
+    usb_pid: number | null;
+    manufacturer: string | null;
+}
+
+export interface AboutInfo {
+    name: string;
+    version: string;
+    credits: string;
+    copyright: string | null;
+}
+
+export type PermissionState = 'granted' | 'denied' | 'undetermined' | 'not_applicable';
+
+export type Permission =
+    | 'notifications'
+    | 'microphone'
+    | 'screen_recording'
+    | 'accessibility'
+    | 'camera';
+
+export type PermissionsStatus = Record<Permission, PermissionState>;
+
+export type HashAlgorithm = 'Sha256' | 'Sha512' | 'Blake3';
+
+export interface HashProgress {
+    path: string;
+    bytes_read: number;
+    total_bytes: number;
+}
+
+/** Payload of `operation-progress` */
+export interface OperationProgress {
+    id: number;
+    name: string;
+    /** The step being worked on, e.g. "Rendering" */
+    step: string | null;
+    /** Between 0 and 1 */
+    fraction: number;
+    completed: boolean;
+}
+
+export interface FsChangeEvent {
+    watch_id: number;
+    kind: 'create' | 'modify' | 'delete';
+    paths: string[];
+}
+
+export interface DeepLinkEntry {
+    url: string;
+    received_at: number;
+}
+
+export interface DeepLink {
+    /** The link as received */
+    url: string;
+    /** `host/path` without outer slashes, e.g. `council/abc` */
+    route: string;
+    /** Query parameters; the last value wins for repeated keys */
+    params: Record<string, string>;
+}
+
+export interface DeepLinkError {
+    url: string;
+    reason: string;
+}
+
+// ==================== Notifications ====================
+
+/**
+ * Show a native system notification.
+ * Resolves to 'RateLimited' when it was dropped by the rate limiter, or 'Digested'
+ * when it was held for the digest, counted under `group` (e.g. a chat id).
+ */
+export async function showNotification(
+    title: string,
+    body: string,
+    icon?: string,
+    group?: string
+): Promise<NotificationResult> {
+    return invoke('show_notification', { title, body, icon, group });
+}
+
+/**
+ * Set how many notifications may be shown per minute (null for no limit),
+ * and whether dropped ones are summarized
+ */
+export async function setNotificationRateLimit(
+    perMinute: number | null,
+    summarize: boolean
+): Promise<void> {
+    return invoke('set_notification_rate_limit', { perMinute, summarize });
+}
+
+/**
+ * Hold rate-limited notifications for `windowSecs` and show them as one
+ * "N new messages from X chats" digest instead of dropping them
+ */
+export async function setNotificationDigest(enabled: boolean, windowSecs: number): Promise<void> {
+    return invoke('set_notification_digest', { enabled, windowSecs });
+}
+
+/**
+ * Schedule a notification for a wall-clock time (e.g. `2026-03-29T09:00`) in an
+ * IANA time zone, defaulting to the system zone. DST transitions are handled.
+ */
+export async function scheduleNotification(
+    title: string,
+    body: string,
+    at: string,
+    timezone?: string
+): Promise<ScheduledNotification> {
+    return invoke('schedule_notification', { title, body, at, timezone });
+}
+
+/**
+ * Cancel a scheduled notification. Resolves to false if it wasn't pending.
+ */
+export async function cancelScheduledNotification(id: number): Promise<boolean> {
+    return invoke('cancel_scheduled_notification', { id });
+}
+
+/**
+ * Get pending scheduled notifications, soonest first
+ */
+export async function getScheduledNotifications(): Promise<ScheduledNotification[]> {
+    return invoke('get_scheduled_notifications');
+}
+
+/**
+ * Get the IANA name of the system time zone, e.g. `Europe/Istanbul`
+ */
+export async function getSystemTimezone(): Promise<string> {
+    return invoke('get_system_timezone');
+}
+
+/**
+ * Set the notification badge count (dock/taskbar)
+ */
+export async function setBadgeCount(count: number): Promise<void> {
+    return invoke('set_badge_count', { count });
+}
+
+/**
+ * Clear the notification badge
+ */
+export async function clearBadge(): Promise<void> {
+    return invoke('clear_badge');
+}
+
+/**
+ * Replace the dock (macOS) or taskbar icon with a base64-encoded PNG
+ */
+export async function setDockIcon(base64Png: string): Promise<void> {
+    return invoke('set_dock_icon', { base64Png });
+}
+
+/**
+ * Restore the bundled dock / taskbar icon
+ */
+export async function resetDockIcon(): Promise<void> {
+    return invoke('reset_dock_icon');
+}
+
+/**
+ * Get notifications still showing in the notification center
+ */
+export async function getDeliveredNotifications(): Promise<DeliveredNotification[]> {
+    return invoke('get_delivered_notifications');
+}
+
+/**
+ * Remove a delivered notification
+ */
+export async function removeNotification(id: number): Promise<void> {
+    return invoke('remove_notification', { id });
+}
+
+/**
+ * Remove all delivered notifications
+ */
+export async function removeAllNotifications(): Promise<void> {
+    return invoke('remove_all_notifications');
+}
+
+// ==================== System Info ====================
+
+/**
+ * Get system information
+ */
+export async function getSystemInfo(): Promise<SystemInfo> {
+    return invoke('get_system_info');
+}
+
+/**
+ * Force light or dark native chrome (menus, dialogs, tray), or follow the OS
+ */
+export async function setAppearance(mode: AppearanceMode): Promise<void> {
+    return invoke('set_appearance', { mode });
+}
+
+/**
+ * Get the persisted appearance preference
+ */
+export async function getAppearance(): Promise<AppearanceMode> {
+    return invoke('get_appearance');
+}
+
+/**
+ * Compare the system clock with a time server
+ */
+export async function checkClockSkew(): Promise<ClockSkew> {
+    return invoke('check_clock_skew');
+}
+
+/**
+ * Get the process and native CPU architectures, e.g. to detect Rosetta
+ */
+export async function getCpuArchitectureDetails(): Promise<CpuArchitectureDetails> {
+    return invoke('get_cpu_architecture_details');
+}
+
+/**
+ * Get application version
+ */
+export async function getAppVersion(): Promise<string> {
+    return invoke('get_app_version');
+}
+
+/**
+ * Show the About panel (native on macOS; elsewhere emits `show-about`)
+ */
+export async function showAboutPanel(): Promise<void> {
+    return invoke('show_about_panel');
+}
+
+// ==================== Updates ====================
+
+/**
+ * Check for application updates
+ */
+export async function checkForUpdates(): Promise<UpdateInfo> {
+    return invoke('check_for_updates');
+}
+
+/**
+ * Get the changelog between the running version and the available update
+ */
+export async function getUpdateChangelog(): Promise<UpdateChangelog> {
+    return invoke('get_update_changelog');
+}
+
+/**
+ * Check whether the active connection is metered (false if unknown)
+ */
+export async function isMeteredConnection(): Promise<boolean> {
+    return invoke('is_metered_connection');
+}
+
+/**
+ * Check whether the OS is in low-power or battery-saver mode (false if unknown)
+ */
+export async function isLowPowerMode(): Promise<boolean> {
+    return invoke('is_low_power_mode');
+}
+
+/**
+ * Install available update
+ */
+export async function installUpdate(): Promise<void> {
+    return invoke('install_update');
+}
+
+/**
+ * Pause the running update download
+ */
+export async function pauseUpdateDownload(): Promise<void> {
+    return invoke('pause_update_download');
+}
+
+/**
+ * Resume a paused update download where it stopped, if the server allows;
+ * otherwise it starts over
+ */
+export async function resumeUpdateDownload(): Promise<void> {
+    return invoke('resume_update_download');
+}
+
+/**
+ * Cancel the running update download; `installUpdate` rejects and the
+ * updater returns to idle
+ */
+export async function cancelUpdateDownload(): Promise<void> {
+    return invoke('cancel_update_download');
+}
+
+/**
+ * Announce a fake update like a real check would (debug builds only)
+ */
+export async function simulateUpdateAvailable(version: string, notes: string): Promise<void> {
+    return invoke('simulate_update_available', { version, notes });
+}
+
+/**
+ * Emit fake `update-download-progress` events from zero to complete (debug builds only)
+ */
+export async function simulateUpdateProgress(): Promise<void> {
+    return invoke('simulate_update_progress');
+}
+
+/**
+ * Choose where updates are downloaded; `null` restores the system temp directory.
+ * The directory must be writable and have room for an update.
+ */
+export async function setUpdateDownloadDir(path: string | null): Promise<void> {
+    return invoke('set_update_download_dir', { path });
+}
+
+/**
+ * Get the updater's current state
+ */
+export async function getUpdaterState(): Promise<UpdaterState> {
+    return invoke('get_updater_state');
+}
+
+/**
+ * Get the version this launch was updated from, if it follows an update
+ */
+export async function getUpdatedFrom(): Promise<UpdatedFrom | null> {
+    return invoke('get_updated_from');
+}
+
+/**
+ * Check for and download an update and verify it without installing, for
+ * release QA. Emits the usual updater events.
+ */
+export async function updateDryRun(): Promise<DryRunReport> {
+    return invoke('update_dry_run');
+}
+
+/**
+ * Get the previous installation kept for rolling back the last update
+ */
+export async function getRollbackInfo(): Promise<RollbackInfo | null> {
+    return invoke('get_rollback_info');
+}
+
+/**
+ * Reinstall the version replaced by the last update and restart
+ */
+export async function rollbackUpdate(): Promise<void> {
+    return invoke('rollback_update');
+}
+
+// ==================== Autostart ====================
+
+/**
+ * Enable or disable autostart on system boot
+ */
+export async function setAutostart(enabled: boolean): Promise<void> {
+    return invoke('set_autostart', { enabled });
+}
+
+/**
+ * Check if autostart is enabled
+ */
+export async function getAutostartEnabled(): Promise<boolean> {
+    return invoke('get_autostart_enabled');
+}
+
+/**
+ * Check whether autostart is enabled and its entry launches this install.
+ * Resolves to false when autostart is disabled.
+ */
+export async function verifyAutostart(): Promise<boolean> {
+    return invoke('verify_autostart');
+}
+
+/**
+ * Re-register autostart at this install's path if it's enabled.
+ * Resolves to whether the entry had to be rewritten.
+ */
+export async function repairAutostart(): Promise<boolean> {
+    return invoke('repair_autostart');
+}
+
+/**
+ * Check whether the OS launched the app at login (autostart)
+ */
+export async function wasAutoLaunched(): Promise<boolean> {
+    return invoke('was_auto_launched');
+}
+
+/**
+ * Check whether the main window was started hidden in the tray
+ */
+export async function getShouldStartMinimized(): Promise<boolean> {
+    return invoke('get_should_start_minimized');
+}
+
+/**
+ * Signal that the page has loaded and is listening for events.
+ * Files opened at launch are emitted as `file-opened` after this call.
+ */
+export async function frontendReady(): Promise<void> {
+    return invoke('frontend_ready');
+}
+
+// ==================== Settings ====================
+
+/**
+ * Check whether the settings file on disk is valid
+ */
+export async function validateSettings(): Promise<SettingsValidation> {
+    return invoke('validate_settings');
+}
+
+/**
+ * Back up the settings file and reset it to defaults
+ */
+export async function repairSettings(): Promise<Record<string, unknown>> {
+    return invoke('repair_settings');
+}
+
+/**
+ * Export settings to a file (machine-specific fields are left out)
+ */
+export async function exportSettings(path: string): Promise<void> {
+    return invoke('export_settings', { path });
+}
+
+/**
+ * Export settings as JSON or as TOML with each field's default in a comment
+ */
+export async function exportSettingsFormat(path: string, format: ExportFormat): Promise<void> {
+    return invoke('export_settings_format', { path, format });
+}
+
+/**
+ * Import settings from a JSON or TOML file, detected by extension or content.
+ * Rejects with an `InvalidSettings` CommandError listing bad fields.
+ */
+export async function importSettings(path: string): Promise<Record<string, unknown>> {
+    return invoke('import_settings', { path });
+}
+
+// ==================== Accessibility ====================
+
+/**
+ * Check whether animations should be reduced.
+ * The app-level override wins; otherwise the OS setting applies (false if unknown).
+ */
+export async function getReduceMotion(): Promise<boolean> {
+    return invoke('get_reduce_motion');
+}
+
+/**
+ * Check whether the OS high-contrast / forced-colors mode is on
+ */
+export async function getHighContrast(): Promise<boolean> {
+    return invoke('get_high_contrast');
+}
+
+/**
+ * Get the active keyboard layout / input source id (null if undetectable).
+ * The format is platform-specific, e.g. `com.apple.keylayout.US` or `xkb:us`.
+ */
+export async function getKeyboardLayout(): Promise<string | null> {
+    return invoke('get_keyboard_layout');
+}
+
+/**
+ * Override the OS reduce-motion setting, or pass null to follow the OS
+ */
+export async function setReduceMotionOverride(reduceMotion: boolean | null): Promise<void> {
+    return invoke('set_reduce_motion_override', { reduceMotion });
+}
+
+// ==================== Permissions ====================
+
+/**
+ * Get the state of every OS permission the app may need
+ */
+export async function getPermissionsStatus(): Promise<PermissionsStatus> {
+    return invoke('get_permissions_status');
+}
+
+/**
+ * Ask for permission to show notifications, prompting if the OS hasn't asked yet.
+ * Otherwise the prompt comes with the first notification shown.
+ */
+export async function requestNotificationPermission(): Promise<PermissionState> {
+    return invoke('request_notification_permission');
+}
+
+/**
+ * Open the OS settings page where a permission can be granted
+ */
+export async function openSystemSettings(permission: Permission): Promise<void> {
+    return invoke('open_system_settings', { permission });
+}
+
+// ==================== Diagnostics ====================
+
+/**
+ * Check notification permission, updater reachability, clipboard, settings and keychain
+ */
+export async function runSelfTest(): Promise<SelfTestReport> {
+    return invoke('run_self_test');
+}
+
+/**
+ * Enable or disable running the self-test in the background
+ */
+export async function setPeriodicSelfTest(enabled: boolean): Promise<void> {
+    return invoke('set_periodic_self_test', { enabled });
+}
+
+/**
+ * List every event the backend can emit, with its payload type
+ */
+export async function getEventManifest(): Promise<EventInfo[]> {
+    return invoke('get_event_manifest');
+}
+
+/**
+ * Follow the OS log (unified log, Event Log or journal), emitting `system-log-line`.
+ * Without a subsystem the log is filtered to this app. Requires system log access.
+ */
+export async function tailSystemLog(subsystem?: string): Promise<number> {
+    return invoke('tail_system_log', { subsystem });
+}
+
+/**
+ * Stop a log tail started by `tailSystemLog`
+ */
+export async function stopTailSystemLog(id: number): Promise<void> {
+    return invoke('stop_tail_system_log', { id });
+}
+
+/**
+ * Allow or forbid tailing the OS logs (advanced)
+ */
+export async function setSystemLogAccess(enabled: boolean): Promise<void> {
+    return invoke('set_system_log_access', { enabled });
+}
+
+/**
+ * Measure the webview's cache, local storage and IndexedDB on disk
+ */
+export async function getWebviewStorageUsage(): Promise<WebviewStorageUsage> {
+    return invoke('get_webview_storage_usage');
+}
+
+/**
+ * Delete the selected kinds of webview storage and reload every window.
+ * Rejects listing what the webview held open and couldn't be removed.
+ */
+export async function clearWebviewCache(kinds: StorageKind[]): Promise<void> {
+    return invoke('clear_webview_cache', { kinds });
+}
+
+/**
+ * Store a value in the encrypted offline cache, evicting the least recently
+ * used entries past 64 MB
+ */
+export async function cachePut(key: string, value: Uint8Array): Promise<void> {
+    return invoke('cache_put', { key, value: Array.from(value) });
+}
+
+/**
+ * Read a value from the encrypted offline cache
+ */
+export async function cacheGet(key: string): Promise<Uint8Array | null> {
+    const value = await invoke<number[] | null>('cache_get', { key });
+    return value ? new Uint8Array(value) : null;
+}
+
+/**
+ * Remove a value from the encrypted offline cache
+ */
+export async function cacheDelete(key: string): Promise<void> {
+    return invoke('cache_delete', { key });
+}
+
+/**
+ * Remove every value from the encrypted offline cache
+ */
+export async function cacheClear(): Promise<void> {
+    return invoke('cache_clear');
+}
+
+// ==================== Usage ====================
+
+/**
+ * Get this session's uptime and the launch and runtime counters, kept only on this device
+ */
+export async function getSessionStats(): Promise<SessionStats> {
+    return invoke('get_session_stats');
+}
+
+/**
+ * Clear the usage counters, counting the running session as the first
+ */
+export async function resetSessionStats(): Promise<void> {
+    return invoke('reset_session_stats');
+}
+
+/**
+ * Get call counts and p50/p95 latencies per native command, slowest first.
+ * Measured by this module's `invoke` wrapper from request to reply, so
+ * calls made without it aren't counted. Kept only on this device.
+ */
+export async function getCommandMetrics(): Promise<CommandStats[]> {
+    await flushCommandSamples();
+    return invokeCommand('get_command_metrics');
+}
+
+/**
+ * Forget the recorded command timings
+ */
+export async function resetCommandMetrics(): Promise<void> {
+    commandSamples.length = 0;
+    return invokeCommand('reset_command_metrics');
+}
+
+// ==================== Privacy ====================
+
+/**
+ * Check whether the screen appears to be shared or recorded. Best effort: only a few known
+ * capture tools are recognized by process name, so other captures read as `false`.
+ * Use `setContentProtection` to keep a window out of captures.
+ */
+export async function isScreenBeingCaptured(): Promise<boolean> {
+    return invoke('is_screen_being_captured');
+}
+
+/**
+ * Capture one of our own windows as a base64-encoded PNG, e.g. for bug reports.
+ * The window must be visible; macOS requires screen recording access.
+ */
+export async function captureWindow(label: string): Promise<string> {
+    return invoke('capture_window', { label });
+}
+
+// ==================== Security ====================
+
+/**
+ * Get the Content-Security-Policy applied to loaded pages
+ */
+export async function getEffectiveCsp(): Promise<string> {
+    return invoke('get_effective_csp');
+}
+
+/**
+ * Set extra CSP `connect-src` sources (e.g. API hosts).
+ * Applies to pages loaded after the change.
+ */
+export async function setCspConnectSources(sources: string[]): Promise<void> {
+    return invoke('set_csp_connect_sources', { sources });
+}
+
+/**
+ * Lock the app after `idleMinutes` without input or after sleep, emitting `auto-lock`.
+ * `hideWindows` keeps its previous value when omitted.
+ */
+export async function setAutoLock(
+    enabled: boolean,
+    idleMinutes: number,
+    hideWindows?: boolean
+): Promise<void> {
+    return invoke('set_auto_lock', { enabled, idleMinutes, hideWindows });
+}
+
+/**
+ * Get the auto-lock configuration
+ */
+export async function getAutoLockSettings(): Promise<AutoLockSettings> {
+    return invoke('get_auto_lock_settings');
+}
+
+/**
+ * Check the app's code signature (and notarization on macOS).
+ * Emits `signature-invalid` when it fails in a release build; rejects on Linux.
+ */
+export async function verifyAppSignature(): Promise<SignatureStatus> {
+    return invoke('verify_app_signature');
+}
+
+/**
+ * Refuse to install updates while the app's code signature is invalid
+ */
+export async function setRequireValidSignature(enabled: boolean): Promise<void> {
+    return invoke('set_require_valid_signature', { enabled });
+}
+
+/**
+ * Check whether the app carries the macOS quarantine mark behind Gatekeeper's
+ * "can't be opened" warnings. Rejects with `NotSupported` off macOS.
+ */
+export async function getQuarantineStatus(): Promise<boolean> {
+    return invoke('get_quarantine_status');
+}
+
+/**
+ * Remove the quarantine mark after the user confirms in a native dialog.
+ * Resolves to whether it was removed; rejects with `NotSupported` off macOS.
+ */
+export async function clearQuarantine(): Promise<boolean> {
+    return invoke('clear_quarantine');
+}
+
+// ==================== App Directories ====================
+
+/**
+ * Get the path of an app-managed directory
+ */
+export async function getAppDirectory(kind: AppDir): Promise<string> {
+    return invoke('get_app_directory', { kind });
+}
+
+/**
+ * Reveal an app-managed directory in the file manager
+ */
+export async function openAppDirectory(kind: AppDir): Promise<void> {
+    return invoke('open_app_directory', { kind });
+}
+
+/**
+ * Get the size and free space of the volume holding a path (default: app data)
+ */
+export async function getDiskUsage(path?: string): Promise<DiskUsage> {
+    return invoke('get_disk_usage', { path });
+}
+
+// ==================== Plugins ====================
+
+/**
+ * Get the initialization status of each native plugin, keyed by plugin name.
+ * Use it to hide features whose plugin failed to load.
+ */
+export async function getPluginStatus(): Promise<Record<string, PluginStatus>> {
+    return invoke('get_plugin_status');
+}
+
+// ==================== Profiles ====================
+
+/**
+ * List the profiles, with the default profile first
+ */
+export async function listProfiles(): Promise<ProfileInfo[]> {
+    return invoke('list_profiles');
+}
+
+/**
+ * Create an empty profile with its own settings, data and keychain namespace
+ */
+export async function createProfile(name: string): Promise<void> {
+    return invoke('create_profile', { name });
+}
+
+/**
+ * Relaunch the app in another profile; resolves only if it's already active
+ */
+export async function switchProfile(name: string): Promise<void> {
+    return invoke('switch_profile', { name });
+}
+
+/**
+ * Delete a profile and its local data; the active and default profiles can't be deleted
+ */
+export async function deleteProfile(name: string): Promise<void> {
+    return invoke('delete_profile', { name });
+}
+
+// ==================== Dialogs ====================
+
+/**
+ * Ask the user for a line of text with a native prompt (null if cancelled)
+ */
+export async function promptDialog(
+    title: string,
+    message: string,
+    defaultValue?: string
+): Promise<string | null> {
+    return invoke('prompt_dialog', { title, message, defaultValue });
+}
+
+/**
+ * Ask the user to confirm with a native dialog (false for cancel/no)
+ */
+export async function confirmDialog(title: string, message: string): Promise<boolean> {
+    return invoke('confirm_dialog', { title, message });
+}
+
+// ==================== External Links ====================
+
+/**
+ * Open a URL in the default browser
+ */
+export async function openExternalLink(url: string): Promise<void> {
+    return invoke('open_external_link', { url });
+}
+
+// ==================== Deep Links ====================
+
+/**
+ * Get recently received deep links, oldest first
+ */
+export async function getDeepLinkHistory(): Promise<DeepLinkEntry[]> {
+    return invoke('get_deep_link_history');
+}
+
+/**
+ * Clear the deep link history
+ */
+export async function clearDeepLinkHistory(): Promise<void> {
+    return invoke('clear_deep_link_history');
+}
+
+/**
+ * Route deep links whose path starts with `pathPrefix` (e.g. `council`) to a window
+ */
+export async function setDeepLinkRoute(pathPrefix: string, windowLabel: string): Promise<void> {
+    return invoke('set_deep_link_route', { pathPrefix, windowLabel });
+}
+
+/**
+ * Remove a deep-link route, sending its links back to the main window
+ */
+export async function removeDeepLinkRoute(pathPrefix: string): Promise<void> {
+    return invoke('remove_deep_link_route', { pathPrefix });
+}
+
+/**
+ * Make this install the one that opens `yula://` links, e.g. to pick
+ * between stable and beta installs
+ */
+export async function setAsDefaultProtocolHandler(): Promise<void> {
+    return invoke('set_as_default_protocol_handler');
+}
+
+/**
+ * Check whether this install opens `yula://` links.
+ * Emits `protocol-handler-conflict` when another install does.
+ */
+export async function isDefaultProtocolHandler(): Promise<boolean> {
+    return invoke('is_default_protocol_handler');
+}
+
+/**
+ * Mint a single-use token for signing in on the web; the web app returns it
+ * as `yula://auth?token=...` within five minutes
+ */
+export async function generateHandoffToken(): Promise<string> {
+    return invoke('generate_handoff_token');
+}
+
+// ==================== File Associations ====================
+
+/**
+ * Register the app as the handler for a file extension (e.g. "yula").
+ * On macOS only extensions declared in the bundle can be handled.
+ */
+export async function registerFileAssociation(extension: string): Promise<void> {
+    return invoke('register_file_association', { extension });
+}
+
+/**
+ * Check whether the app is the registered handler for a file extension
+ */
+export async function isFileAssociationRegistered(extension: string): Promise<boolean> {
+    return invoke('is_file_association_registered', { extension });
+}
+
+// ==================== Integrations ====================
+
+/**
+ * Register "New Chat" / "Ask YULA" with OS automation (jump-list tasks on Windows).
+ * Elsewhere this rejects with 'NotSupported'; automation can open
+ * `yula://action/new-chat` or `yula://action/ask?text=...` directly.
+ */
+export async function registerAppIntents(): Promise<void> {
+    return invoke('register_app_intents');
+}
+
+/**
+ * Start the localhost integration server, returning its port.
+ * Binds to a random free port when none is given.
+ */
+export async function startLocalServer(port?: number): Promise<number> {
+    return invoke('start_local_server', { port });
+}
+
+/**
+ * Stop the localhost integration server
+ */
+export async function stopLocalServer(): Promise<void> {
+    return invoke('stop_local_server');
+}
+
+/**
+ * Get the bearer token integrations must present (null when stopped)
+ */
+export async function getLocalServerToken(): Promise<string | null> {
+    return invoke('get_local_server_token');
+}
+
+// ==================== Presence ====================
+
+/**
+ * Ping `endpoint` (HTTPS) every `intervalSecs` with `token` as bearer token, even while
+ * the window is hidden. Skipped offline and while background tasks are suspended.
+ */
+export async function startPresenceHeartbeat(
+    intervalSecs: number,
+    endpoint: string,
+    token: string
+): Promise<void> {
+    return invoke('start_presence_heartbeat', { intervalSecs, endpoint, token });
+}
+
+/**
+ * Stop the presence heartbeat
+ */
+export async function stopPresenceHeartbeat(): Promise<void> {
+    return invoke('stop_presence_heartbeat');
+}
+
+// ==================== Devices ====================
+
+/**
+ * List connected serial ports
+ */
+export async function listSerialPorts(): Promise<SerialPortInfo[]> {
+    return invoke('list_serial_ports');
+}
+
+// ==================== Files ====================
+
+/**
+ * Open a file for chunked reading, returning a stream id.
+ * Idle streams are closed automatically after a few minutes.
+ */
+export async function openFileStream(path: string): Promise<number> {
+    return invoke('open_file_stream', { path });
+}
+
+/**
+ * Read up to `size` bytes from a stream, base64-encoded (null at end of file)
+ */
+export async function readFileChunk(id: number, size: number): Promise<string | null> {
+    return invoke('read_file_chunk', { id, size });
+}
+
+/**
+ * Close a file stream
+ */
+export async function closeFileStream(id: number): Promise<void> {
+    return invoke('close_file_stream', { id });
+}
+
+/**
+ * Watch a directory for changes, returning an id for `unwatchDirectory`
+ */
+export async function watchDirectory(path: string, recursive: boolean): Promise<number> {
+    return invoke('watch_directory', { path, recursive });
+}
+
+/**
+ * Stop watching a directory
+ */
+export async function unwatchDirectory(id: number): Promise<void> {
+    return invoke('unwatch_directory', { id });
+}
+
+/**
+ * Compute the hex digest of a file.
+ * Rejects with a `NotFound` CommandError for missing paths.
+ */
+export async function hashFile(path: string, algorithm: HashAlgorithm): Promise<string> {
+    return invoke('hash_file', { path, algorithm });
+}
+
+// ==================== Operations ====================
+
+/**
+ * Start a multi-step operation, returning its id. The most recent running
+ * operation's progress is shown on the dock or taskbar icon.
+ */
+export async function beginOperation(name: string): Promise<number> {
+    return invoke('begin_operation', { name });
+}
+
+/**
+ * Report the step an operation is on and how far along it is, from 0 to 1.
+ * Omitting `step` keeps the previous one.
+ */
+export async function updateOperation(id: number, fraction: number, step?: string): Promise<void> {
+    return invoke('update_operation', { id, step, fraction });
+}
+
+/**
+ * Mark an operation finished
+ */
+export async function completeOperation(id: number): Promise<void> {
+    return invoke('complete_operation', { id });
+}
+
+/**
+ * Get the operations that are still running, oldest first
+ */
+export async function getActiveOperations(): Promise<OperationProgress[]> {
+    return invoke('get_active_operations');
+}
+
+// ==================== Media ====================
+
+/**
+ * Start receiving hardware media keys as `media-key` events
+ */
+export async function enableMediaKeys(): Promise<void> {
+    return invoke('enable_media_keys');
+}
+
+/**
+ * Stop receiving hardware media keys and clear the OS media overlay
+ */
+export async function disableMediaKeys(): Promise<void> {
+    return invoke('disable_media_keys');
+}
+
+/**
+ * Show what's playing in the OS media overlay. `artwork` is an image URL.
+ */
+export async function setNowPlaying(
+    title: string,
+    artist?: string,
+    artwork?: string
+): Promise<void> {
+    return invoke('set_now_playing', { title, artist, artwork });
+}
+
+/**
+ * Start emitting `audio-level` events from the default microphone.
+ * Rejects with a `PermissionDenied` CommandError if microphone access was refused.
+ */
+export async function startAudioLevelMonitoring(): Promise<void> {
+    return invoke('start_audio_level_monitoring');
+}
+
+/**
+ * Stop emitting `audio-level` events and close the microphone
+ */
+export async function stopAudioLevelMonitoring(): Promise<void> {
+    return invoke('stop_audio_level_monitoring');
+}
+
+// ==================== Clipboard ====================
+
+/**
+ * Copy text to system clipboard
+ */
+export async function copyToClipboard(text: string): Promise<void> {
+    return invoke('copy_to_clipboard', { text });
+}
+
+/**
+ * Get the text selected in the frontmost app (null if nothing is selected).
+ * Rejects with a `PermissionDenied` CommandError on macOS until
+ * accessibility access is granted. Where the selection has to be copied,
+ * clipboard contents other than text are lost.
+ */
+export async function getSelectedText(): Promise<string | null> {
+    return invoke('get_selected_text');
+}
+
+/**
+ * Read text from system clipboard
+ */
+export async function readFromClipboard(): Promise<string> {
+    return invoke('read_from_clipboard');
+}
+
+/**
+ * Start emitting `clipboard-changed` events; call once per listener.
+ * Nothing is watched until `setClipboardPollStrategy` picks a strategy other than `Off`.
+ */
+export async function startClipboardMonitoring(): Promise<void> {
+    return invoke('start_clipboard_monitoring');
+}
+
+/**
+ * Stop emitting `clipboard-changed` events for one listener
+ */
+export async function stopClipboardMonitoring(): Promise<void> {
+    return invoke('stop_clipboard_monitoring');
+}
+
+/**
+ * Choose how the clipboard is watched for `clipboard-changed`
+ */
+export async function setClipboardPollStrategy(strategy: ClipboardPollStrategy): Promise<void> {
+    return invoke('set_clipboard_poll_strategy', { strategy });
+}
+
+/**
+ * Get how the clipboard is watched
+ */
+export async function getClipboardPollStrategy(): Promise<ClipboardPollStrategy> {
+    return invoke('get_clipboard_poll_strategy');
+}
+
+// ==================== Window Management ====================
+
+/**
+ * Minimize window to system tray
+ */
+export async function minimizeToTray(): Promise<void> {
+    return invoke('minimize_to_tray');
+}
+
+/**
+ * Quit the application
+ */
+export async function quitApp(): Promise<void> {
+    return invoke('quit_app');
+}
+
+/**
+ * Restart the application
+ */
+export async function restartApp(): Promise<void> {
+    return invoke('restart_app');
+}
+
+/**
+ * Reduce background activity while the window is hidden.
+ * Listen for `throttle-mode` to pause animations and polling.
+ */
+export async function setBackgroundThrottle(enabled: boolean): Promise<void> {
+    return invoke('set_background_throttle', { enabled });
+}
+
+/**
+ * Pause all polling background tasks (monitors) at their next iteration
+ */
+export async function suspendBackgroundTasks(): Promise<void> {
+    return invoke('suspend_background_tasks');
+}
+
+/**
+ * Resume background tasks paused by `suspendBackgroundTasks`
+ */
+export async function resumeBackgroundTasks(): Promise<void> {
+    return invoke('resume_background_tasks');
+}
+
+/**
+ * List background tasks and whether they're suspended
+ */
+export async function getBackgroundTaskStatus(): Promise<BackgroundTaskStatus> {
+    return invoke('get_background_task_status');
+}
+
+/**
+ * Enable or disable webview hardware acceleration.
+ * Resolves to `true` when a restart is required to apply the change.
+ */
+export async function setHardwareAcceleration(enabled: boolean): Promise<boolean> {
+    return invoke('set_hardware_acceleration', { enabled });
+}
+
+/**
+ * Check whether webview hardware acceleration is enabled
+ */
+export async function getHardwareAcceleration(): Promise<boolean> {
+    return invoke('get_hardware_acceleration');
+}
+
+/**
+ * Set the macOS activation policy (`Accessory` hides the dock icon)
+ */
+export async function setActivationPolicy(policy: ActivationPolicy): Promise<void> {
+    return invoke('set_activation_policy', { policy });
+}
+
+/**
+ * Get the persisted macOS activation policy
+ */
+export async function getActivationPolicy(): Promise<ActivationPolicy> {
+    return invoke('get_activation_policy');
+}
+
+/**
+ * Run as a menu bar app: no dock icon, a tray popover and a summon hotkey
+ * (default `CmdOrCtrl+Shift+Space`). Disabling restores the regular dock presence.
+ */
+export async function setMenuBarAppMode(enabled: boolean, hotkey?: string): Promise<void> {
+    return invoke('set_menu_bar_app_mode', { enabled, hotkey });
+}
+
+/**
+ * Check whether the app runs as a menu bar app
+ */
+export async function getMenuBarAppMode(): Promise<boolean> {
+    return invoke('get_menu_bar_app_mode');
+}
+
+/**
+ * Show the main window on every workspace / virtual desktop.
+ * Rejects with a `NotSupported` CommandError on Windows.
+ */
+export async function setVisibleOnAllWorkspaces(enabled: boolean): Promise<void> {
+    return invoke('set_visible_on_all_workspaces', { enabled });
+}
+
+/**
+ * Check whether a window is fully covered, minimized or hidden
+ * (covered windows are only detected on macOS)
+ */
+export async function isWindowOccluded(label: string): Promise<boolean> {
+    return invoke('is_window_occluded', { label });
+}
+
+/**
+ * Switch the main window into or out of the compact, always-on-top mini mode
+ */
+export async function setMiniMode(enabled: boolean): Promise<void> {
+    return invoke('set_mini_mode', { enabled });
+}
+
+/**
+ * Check whether the main window is in mini mode
+ */
+export async function isMiniMode(): Promise<boolean> {
+    return invoke('is_mini_mode');
+}
+
+/**
+ * Raise the main window above other apps, bypassing focus-stealing prevention.
+ * Only call in response to an explicit user action (e.g. a global hotkey).
+ */
+export async function forceForeground(): Promise<void> {
+    return invoke('force_foreground');
+}
+
+/**
+ * Open a secondary window at a frontend route (e.g. `council`), revealing it if it exists.
+ * Under the single-window policy this focuses the main window and rejects with a
+ * `PolicyViolation` CommandError.
+ */
+export async function openWindow(label: string, url: string): Promise<void> {
+    return invoke('open_window', { label, url });
+}
+
+/**
+ * Create a window hidden in the background so opening it later is instant
+ */
+export async function preloadWindow(label: string, url: string): Promise<void> {
+    return invoke('preload_window', { label, url });
+}
+
+/**
+ * Reveal a window created by `preloadWindow`
+ */
+export async function showPreloadedWindow(label: string): Promise<void> {
+    return invoke('show_preloaded_window', { label });
+}
+
+/**
+ * Choose whether hiding the last window returns focus to the previous app (macOS)
+ */
+export async function setReturnFocusOnHide(enabled: boolean): Promise<void> {
+    return invoke('set_return_focus_on_hide', { enabled });
+}
+
+/**
+ * Hide a window from the taskbar and alt-tab (the window cycle on macOS)
+ */
+export async function setSkipTaskbar(label: string, skip: boolean): Promise<void> {
+    return invoke('set_skip_taskbar', { label, skip });
+}
+
+/**
+ * Check whether a window was hidden from the taskbar with `setSkipTaskbar`
+ */
+export async function getSkipTaskbar(label: string): Promise<boolean> {
+    return invoke('get_skip_taskbar', { label });
+}
+
+/**
+ * Keep a window out of screenshots and screen shares, where it shows blank
+ * (macOS and Windows)
+ */
+export async function setContentProtection(label: string, enabled: boolean): Promise<void> {
+    return invoke('set_content_protection', { label, enabled });
+}
+
+/**
+ * Check whether a window was kept out of captures with `setContentProtection`
+ */
+export async function getContentProtection(label: string): Promise<boolean> {
+    return invoke('get_content_protection', { label });
+}
+
+/**
+ * Allow or forbid secondary windows (kiosk / enterprise deployments)
+ */
+export async function setWindowPolicy(policy: WindowPolicy): Promise<void> {
+    return invoke('set_window_policy', { policy });
+}
+
+/**
+ * Get the persisted window policy
+ */
+export async function getWindowPolicy(): Promise<WindowPolicy> {
+    return invoke('get_window_policy');
+}
+
+/**
+ * Save every open window's route, geometry and flags as a named layout
+ */
+export async function saveLayout(name: string): Promise<Layout> {
+    return invoke('save_layout', { name });
+}
+
+/**
+ * Restore a saved layout, creating windows that aren't open
+ */
+export async function restoreLayout(name: string): Promise<void> {
+    return invoke('restore_layout', { name });
+}
+
+/**
+ * List the saved window layouts
+ */
+export async function listLayouts(): Promise<Layout[]> {
+    return invoke('list_layouts');
+}
+
+/**
+ * Delete a saved window layout
+ */
+export async function deleteLayout(name: string): Promise<void> {
+    return invoke('delete_layout', { name });
+}
+
+/**
+ * Get the ratio of physical to logical pixels of a window's monitor
+ */
+export async function getWindowScaleFactor(label: string): Promise<number> {
+    return invoke('get_window_scale_factor', { label });
+}
+
+/**
+ * Get every connected monitor's bounds, work area and scale, primary first
+ */
+export async function getDisplayArrangement(): Promise<DisplayInfo[]> {
+    return invoke('get_display_arrangement');
+}
+
+/**
+ * Summarize every monitor's scale factor and the tray icon size it gets.
+ * On Linux, fractional compositor scaling shows as the next whole factor.
+ */
+export async function getEffectiveScaling(): Promise<EffectiveScaling> {
+    return invoke('get_effective_scaling');
+}
+
+/**
+ * Get a display's color space and HDR support, by its index in `getDisplayArrangement`.
+ * Rejects with a `NotFound` CommandError for an unknown index.
+ */
+export async function getDisplayColorInfo(displayIndex: number): Promise<DisplayColorInfo> {
+    return invoke('get_display_color_info', { displayIndex });
+}
+
+/**
+ * Reload a window's page, e.g. to recover from webview memory bloat
+ */
+export async function reloadWindow(label: string): Promise<void> {
+    return invoke('reload_window', { label });
+}
+
+/**
+ * Run a script in a window's page, for integration tests and support repairs.
+ * Rejects with `WindowNotFound` for unknown labels, and with `NotSupported` in
+ * release builds without the `webview-eval` feature.
+ */
+export async function evalInWebview(label: string, script: string): Promise<void> {
+    return invoke('eval_in_webview', { label, script });
+}
+
+/**
+ * Get the resident memory of the webview processes in bytes (null if unknown)
+ */
+export async function getWebviewMemory(): Promise<number | null> {
+    return invoke('get_webview_memory');
+}
+
+/**
+ * Set the memory limit in MiB above which `webview-memory-high` is emitted (null disables)
+ */
+export async function setWebviewMemoryLimit(limitMb: number | null): Promise<void> {
+    return invoke('set_webview_memory_limit', { limitMb });
+}
+
+/**
+ * Get the user agent the main webview was created with
+ */
+export async function getUserAgent(): Promise<string> {
+    return invoke('get_user_agent');
+}
+
+/**
+ * Set a custom webview user agent, or null to restore the default.
+ * Resolves to `true` when a restart is required to apply the change.
+ */
+export async function setUserAgent(userAgent: string | null): Promise<boolean> {
+    return invoke('set_user_agent', { userAgent });
+}
+
+/**
+ * Set the minimum and maximum logical size of a window
+ */
+export async function setSizeConstraints(
+    label: string,
+    min: [number, number] | null,
+    max: [number, number] | null
+): Promise<void> {
+    return invoke('set_size_constraints', { label, min, max });
+}
+
+/**
+ * Get the size limits applied to a window
+ */
+export async function getSizeConstraints(label: string): Promise<SizeConstraints> {
+    return invoke('get_size_constraints', { label });
+}
+
+/**
+ * Apply background blur effects to the main window.
+ * Resolves to `true` when a restart is required for them to show.
+ */
+export async function setWindowEffects(effects: WindowEffects): Promise<boolean> {
+    return invoke('set_window_effects', { effects });
+}
+
+/**
+ * Remove background effects from the main window
+ */
+export async function clearWindowEffects(): Promise<void> {
+    return invoke('clear_window_effects');
+}
+
+/**
+ * Set the main window opacity (0 - 1)
+ */
+export async function setWindowOpacity(opacity: number): Promise<void> {
+    return invoke('set_window_opacity', { opacity });
+}
+
+/**
+ * Round the corners of and add a shadow to a window without decorations.
+ * Does nothing on Linux, where the compositor draws both.
+ */
+export async function setWindowDecorationsStyle(
+    label: string,
+    style: DecorationsStyle,
+): Promise<void> {
+    return invoke('set_window_decorations_style', { label, style });
+}
+
+// ==================== Kiosk ====================
+
+/**
+ * Set the PIN that leaves kiosk mode (at least 4 characters).
+ * Fails with `PolicyViolation` while kiosk mode is on.
+ */
+export async function setKioskPin(pin: string): Promise<void> {
+    return invoke('set_kiosk_pin', { pin });
+}
+
+/**
+ * Lock the app fullscreen onto a display, by its index in `getDisplayArrangement`.
+ * The tray is hidden, secondary windows, mini mode and layouts are refused,
+ * and kiosk mode is re-entered on launch until it is left. Requires a PIN.
+ */
+export async function enterKioskMode(displayIndex: number): Promise<void> {
+    return invoke('enter_kiosk_mode', { displayIndex });
+}
+
+/**
+ * Leave kiosk mode; fails with `PermissionDenied` for a wrong PIN
+ */
+export async function exitKioskMode(pin: string): Promise<void> {
+    return invoke('exit_kiosk_mode', { pin });
+}
+
+/**
+ * Check whether kiosk mode is on
+ */
+export async function isKioskMode(): Promise<boolean> {
+    return invoke('is_kiosk_mode');
+}
+
+// ==================== Tray ====================
+
+/**
+ * Replace the tray icon with a base64-encoded PNG
+ */
+export async function setTrayIcon(base64Png: string): Promise<void> {
+    return invoke('set_tray_icon', { base64Png });
+}
+
+/**
+ * Show a presence status badge on the tray icon
+ */
+export async function setTrayStatus(status: TrayStatus): Promise<void> {
+    return invoke('set_tray_status', { status });
+}
+
+/**
+ * Blink the tray icon for an alert until the main window is focused
+ */
+export async function startTrayBlink(intervalMs: number): Promise<void> {
+    return invoke('start_tray_blink', { intervalMs });
+}
+
+/**
+ * Stop blinking the tray icon
+ */
+export async function stopTrayBlink(): Promise<void> {
+    return invoke('stop_tray_blink');
+}
+
+/**
+ * Get the tray icon's screen rectangle for anchoring popovers.
+ * `null` until the icon was hovered or clicked where the OS can't report it on demand.
+ */
+export async function getTrayRect(): Promise<TrayRect | null> {
+    return invoke('get_tray_rect');
+}
+
+/**
+ * Choose what a left click on the tray icon does (ignored by Linux tray hosts)
+ */
+export async function setTrayClickBehavior(behavior: TrayClickBehavior): Promise<void> {
+    return invoke('set_tray_click_behavior', { behavior });
+}
+
+/**
+ * Get what a left click on the tray icon does
+ */
+export async function getTrayClickBehavior(): Promise<TrayClickBehavior> {
+    return invoke('get_tray_click_behavior');
+}
+
+// ==================== Quick Capture ====================
+
+/**
+ * Show the quick capture overlay on the monitor under the cursor
+ */
+export async function showQuickCapture(): Promise<void> {
+    return invoke('show_quick_capture');
+}
+
+/**
+ * Close the quick capture overlay without capturing
+ */
+export async function hideQuickCapture(): Promise<void> {
+    return invoke('hide_quick_capture');
+}
+
+/**
+ * Report the region dragged in the overlay, in logical overlay coordinates.
+ * Emits `quick-capture-region` and closes the overlay.
+ */
+export async function submitQuickCaptureRegion(
+    x: number,
+    y: number,
+    w: number,
+    h: number
+): Promise<CaptureRegion> {
+    return invoke('submit_quick_capture_region', { x, y, w, h });
+}
+
+// ==================== Shortcuts ====================
+
+/**
+ * Register a global shortcut that emits `global-shortcut` with the action id
+ */
+export async function registerShortcut(accelerator: string, actionId: string): Promise<void> {
+    return invoke('register_shortcut', { accelerator, actionId });
+}
+
+/**
+ * Unregister a global shortcut
+ */
+export async function unregisterShortcut(accelerator: string): Promise<void> {
+    return invoke('unregister_shortcut', { accelerator });
+}
+
+/**
+ * List registered global shortcut bindings
+ */
+export async function listShortcuts(): Promise<ShortcutBinding[]> {
+    return invoke('list_shortcuts');
+}
+
+/**
+ * Register a shortcut that emits `local-shortcut` only while the main window has focus
+ */
+export async function registerLocalShortcut(accelerator: string, actionId: string): Promise<void> {
+    return invoke('register_local_shortcut', { accelerator, actionId });
+}
+
+/**
+ * Unregister a focus-only shortcut
+ */
+export async function unregisterLocalShortcut(accelerator: string): Promise<void> {
+    return invoke('unregister_local_shortcut', { accelerator });
+}
+
+/**
+ * List registered focus-only shortcut bindings
+ */
+export async function listLocalShortcuts(): Promise<ShortcutBinding[]> {
+    return invoke('list_local_shortcuts');
+}
+
+// ==================== Events ====================
+
+/**
+ * Enable or disable recording of backend events (advanced)
+ */
+export async function setEventRecording(enabled: boolean): Promise<void> {
+    return invoke('set_event_recording', { enabled });
+}
+
+/**
+ * Get recently emitted backend events, oldest first
+ */
+export async function getRecentEvents(): Promise<RecordedEvent[]> {
+    return invoke('get_recent_events');
+}
+
+/**
+ * Re-emit recorded events newer than `since` (Unix millis).
+ * Resolves to the number of events replayed.
+ */
+export async function replayEvents(since: number): Promise<number> {
+    return invoke('replay_events', { since });
+}
+
+/**
+ * Listen for deep link events (yula:// protocol) routed to this window
+ */
+export function onDeepLink(callback: (link: DeepLink) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    getCurrentWebviewWindow()
+        .listen<DeepLink>('deep-link', (event) => {
+            callback(event.payload);
+        })
+        .then((fn) => {
+            unlisten = fn;
+        });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for malformed deep links or links to pages this version doesn't have
+ */
+export function onDeepLinkError(callback: (error: DeepLinkError) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<DeepLinkError>('deep-link-error', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for tray menu actions
+ */
+export function onTrayAction(callback: (action: TrayAction) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<TrayAction>('tray-action', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for custom global shortcut presses
+ */
+export function onGlobalShortcut(callback: (actionId: string) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ action_id: string }>('global-shortcut', (event) => {
+        callback(event.payload.action_id);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for focus-only shortcut presses
+ */
+export function onLocalShortcut(callback: (actionId: string) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ action_id: string }>('local-shortcut', (event) => {
+        callback(event.payload.action_id);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for screen sharing/recording starting or stopping, as detected by
+ * `isScreenBeingCaptured`'s best-effort check
+ */
+export function onScreenCaptureChanged(callback: (captured: boolean) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<boolean>('screen-capture-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for regions captured with the quick capture overlay
+ */
+export function onQuickCaptureRegion(callback: (region: CaptureRegion) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<CaptureRegion>('quick-capture-region', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for the OS low-power or battery-saver mode turning on or off
+ */
+export function onLowPowerModeChanged(callback: (enabled: boolean) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<boolean>('low-power-mode-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for background throttle mode being entered or left
+ */
+export function onThrottleMode(callback: (throttled: boolean) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<boolean>('throttle-mode', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for settings being reset after a corrupt file was found
+ */
+export function onSettingsReset(callback: (event: SettingsResetEvent) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<SettingsResetEvent>('settings-reset', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for settings imported from a file (payload lists the imported fields)
+ */
+export function onSettingsImported(callback: (fields: string[]) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<string[]>('settings-imported', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for changes to the effective reduce-motion preference
+ */
+export function onReduceMotionChanged(callback: (reduceMotion: boolean) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<boolean>('reduce-motion-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for files opened through a file association
+ */
+export function onFileOpened(callback: (path: string) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ path: string }>('file-opened', (event) => {
+        callback(event.payload.path);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for windows becoming covered or uncovered
+ */
+export function onWindowOcclusionChanged(
+    callback: (event: { label: string; occluded: boolean }) => void
+): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ label: string; occluded: boolean }>('window-occlusion-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for actions requested through the localhost integration server
+ */
+export function onIntegrationAction(callback: (action: IntegrationAction) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<IntegrationAction>('integration-action', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for serial devices being connected or removed
+ */
+export function onSerialDevicesChanged(callback: (ports: SerialPortInfo[]) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<SerialPortInfo[]>('serial-devices-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for requests to render the About panel (non-macOS)
+ */
+export function onShowAbout(callback: (info: AboutInfo) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<AboutInfo>('show-about', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for the main window entering or leaving mini mode
+ */
+export function onMiniModeChanged(callback: (enabled: boolean) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<boolean>('mini-mode-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for operations starting, progressing and finishing, native or not
+ */
+export function onOperationProgress(callback: (progress: OperationProgress) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<OperationProgress>('operation-progress', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for progress while hashing large files
+export function onHashProgress(callback: (progress: HashProgress) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<HashProgress>('hash-progress', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for changes in watched directories
+ */
+export function onFsChange(callback: (event: FsChangeEvent) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<FsChangeEvent>('fs-change', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for the OS high-contrast mode being turned on or off
+ */
+export function onHighContrastChanged(callback: (highContrast: boolean) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<boolean>('high-contrast-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for the updated version crashing repeatedly at launch
+ */
+export function onOfferRollback(callback: (info: RollbackInfo) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<RollbackInfo>('offer-rollback', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for the webview exceeding its memory limit, e.g. to prompt a reload
+ */
+export function onWebviewMemoryHigh(callback: (usage: WebviewMemoryHigh) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<WebviewMemoryHigh>('webview-memory-high', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for a window moving to a monitor with a different DPI
+ */
+export function onScaleFactorChanged(callback: (change: ScaleFactorChange) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<ScaleFactorChange>('scale-factor-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for the active keyboard layout changing
+ */
+export function onKeyboardLayoutChanged(callback: (layout: string) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<string>('keyboard-layout-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for self-test checks that passed before and fail now
+ */
+export function onSelfTestFailed(callback: (regressed: SelfTestCheck[]) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<SelfTestCheck[]>('self-test-failed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for microphone levels while `startAudioLevelMonitoring` is running
+ */
+export function onAudioLevel(callback: (level: AudioLevel) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<AudioLevel>('audio-level', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for notifications being clicked; the main window is raised first.
+ * `id` matches `getDeliveredNotifications`.
+ */
+export function onNotificationActivated(callback: (id: number) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ id: number }>('notification-activated', (event) => {
+        callback(event.payload.id);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
+
+/**
+ * Listen for notifications being cleared without acting on them.
+ * Not reported by unbundled macOS builds.
+ */
+export function onNotificationDismissed(callback: (id: number) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ id: number }>('notification-dismissed', (event) => {
+        callback(event.payload.id);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
-export interface UpdateInfo {
-    available: boolean;
-    version: string | null;
-    notes: string | null;
-}
+/**
+ * Listen for clicks on the notification digest, to open the chats it covered
+ */
+export function onNotificationDigestClick(
+    callback: (digest: NotificationDigest) => void
+): () => void {
+    let unlisten: (() => void) | null = null;
 
-export type TrayAction = 'new-chat' | 'council' | 'check-updates' | 'preferences';
+    listen<NotificationDigest>('notification-digest-click', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
 
-// ==================== Notifications ====================
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Show a native system notification
+ * Listen for hardware media key presses
  */
-export async function showNotification(title: string, body: string, icon?: string): Promise<void> {
-    return invoke('show_notification', { title, body, icon });
+export function onMediaKey(callback: (action: MediaKeyAction) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ action: MediaKeyAction }>('media-key', (event) => {
+        callback(event.payload.action);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Set the notification badge count (dock/taskbar)
+ * Listen for lines from OS log tails
  */
-export async function setBadgeCount(count: number): Promise<void> {
-    return invoke('set_badge_count', { count });
+export function onSystemLogLine(callback: (line: SystemLogLine) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<SystemLogLine>('system-log-line', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Clear the notification badge
+ * Listen for appearance override changes and, in `System` mode, OS theme changes
  */
-export async function clearBadge(): Promise<void> {
-    return invoke('clear_badge');
-}
+export function onAppearanceChanged(callback: (change: AppearanceChange) => void): () => void {
+    let unlisten: (() => void) | null = null;
 
-// ==================== System Info ====================
+    listen<AppearanceChange>('appearance-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Get system information
+ * Listen for a newer version being found by an update check
  */
-export async function getSystemInfo(): Promise<SystemInfo> {
-    return invoke('get_system_info');
+export function onUpdateAvailable(callback: (info: UpdateInfo) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<UpdateInfo>('update-available', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Get application version
+ * Listen for update download progress
  */
-export async function getAppVersion(): Promise<string> {
-    return invoke('get_app_version');
-}
+export function onUpdateDownloadProgress(
+    callback: (progress: DownloadProgress) => void
+): () => void {
+    let unlisten: (() => void) | null = null;
 
-// ==================== Updates ====================
+    listen<DownloadProgress>('update-download-progress', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Check for application updates
+ * Listen for updater state transitions
  */
-export async function checkForUpdates(): Promise<UpdateInfo> {
-    return invoke('check_for_updates');
+export function onUpdaterStateChanged(callback: (state: UpdaterState) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<UpdaterState>('updater-state-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Install available update
+ * Listen for an installed update waiting for a restart; background work is
+ * paused until then
  */
-export async function installUpdate(): Promise<void> {
-    return invoke('install_update');
-}
+export function onUpdateStaged(callback: (version: string) => void): () => void {
+    let unlisten: (() => void) | null = null;
 
-// ==================== Autostart ====================
+    listen<{ version: string }>('update-staged', (event) => {
+        callback(event.payload.version);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Enable or disable autostart on system boot
+ * Listen for the first launch after an update, sent after `frontendReady`
  */
-export async function setAutostart(enabled: boolean): Promise<void> {
-    return invoke('set_autostart', { enabled });
+export function onUpdatedFrom(callback: (update: UpdatedFrom) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<UpdatedFrom>('updated-from', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Check if autostart is enabled
+ * Listen for the system clock being off by more than a minute
  */
-export async function getAutostartEnabled(): Promise<boolean> {
-    return invoke('get_autostart_enabled');
-}
+export function onClockSkewDetected(callback: (skew: ClockSkew) => void): () => void {
+    let unlisten: (() => void) | null = null;
 
-// ==================== External Links ====================
+    listen<ClockSkew>('clock-skew-detected', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Open a URL in the default browser
+ * Listen for the launch-at-login entry pointing at a moved or replaced app;
+ * offer `repairAutostart`
  */
-export async function openExternalLink(url: string): Promise<void> {
-    return invoke('open_external_link', { url });
+export function onAutostartBroken(
+    callback: (registeredPath: string | null) => void
+): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<{ registered_path: string | null }>('autostart-broken', (event) => {
+        callback(event.payload.registered_path);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
-// ==================== Clipboard ====================
+/**
+ * Listen for the app locking itself after idling or sleep; ask the user to sign in again
+export function onAutoLock(callback: (event: AutoLockEvent) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<AutoLockEvent>('auto-lock', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Copy text to system clipboard
+ * Listen for the app's code signature failing to verify
  */
-export async function copyToClipboard(text: string): Promise<void> {
-    return invoke('copy_to_clipboard', { text });
+export function onSignatureInvalid(callback: (status: SignatureStatus) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<SignatureStatus>('signature-invalid', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Read text from system clipboard
+ * Listen for clipboard changes; `text` is null when the clipboard holds other content.
+ * Keeps the clipboard monitor running until unsubscribed.
  */
-export async function readFromClipboard(): Promise<string> {
-    return invoke('read_from_clipboard');
+export function onClipboardChanged(callback: (text: string | null) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<string | null>('clipboard-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+    startClipboardMonitoring();
+
+    return () => {
+        if (unlisten) unlisten();
+        stopClipboardMonitoring();
+    };
 }
 
-// ==================== Window Management ====================
+/**
+ * Listen for successful presence pings; receives the Unix time in ms
+ */
+export function onPresenceSent(callback: (sentAt: number) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<number>('presence-sent', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Minimize window to system tray
+ * Listen for failed presence pings
  */
-export async function minimizeToTray(): Promise<void> {
-    return invoke('minimize_to_tray');
+export function onPresenceFailed(callback: (error: string) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<string>('presence-failed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
 /**
- * Quit the application
+ * Listen for a display's color space or HDR support changing
  */
-export async function quitApp(): Promise<void> {
-    return invoke('quit_app');
+export function onColorProfileChanged(callback: (info: DisplayColorInfo) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<DisplayColorInfo>('color-profile-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
 }
 
-// ==================== Events ====================
+/**
+ * Listen for monitors being connected, removed or rearranged.
+ * Windows left off-screen are moved back onto a visible monitor.
+ */
+export function onDisplaysChanged(callback: (displays: DisplayInfo[]) => void): () => void {
+    let unlisten: (() => void) | null = null;
+
+    listen<DisplayInfo[]>('displays-changed', (event) => {
+        callback(event.payload);
+    }).then((fn) => {
+        unlisten = fn;
+    });
+
+    return () => {
+        if (unlisten) unlisten();
+    };
+}
 
 /**
- * Listen for deep link events (yula:// protocol)
+ * Listen for accepted `yula://auth` handoff links; exchange the token for a session
  */
-export function onDeepLink(callback: (url: string) => void): () => void {
+export function onAuthHandoff(callback: (event: { token: string }) => void): () => void {
     let unlisten: (() => void) | null = null;
 
-    listen<string>('deep-link', (event) => {
+    listen<{ token: string }>('auth-handoff', (event) => {
         callback(event.payload);
     }).then((fn) => {
         unlisten = fn;
@@ -157,12 +3016,14 @@ export function onDeepLink(callback: (url: string) => void): () => void {
 }
 
 /**
- * Listen for tray menu actions
+ * Listen for `yula://` links opening another install of the app
  */
-export function onTrayAction(callback: (action: TrayAction) => void): () => void {
+export function onProtocolHandlerConflict(
+    callback: (event: { owner: string }) => void
+): () => void {
     let unlisten: (() => void) | null = null;
 
-    listen<TrayAction>('tray-action', (event) => {
+    listen<{ owner: string }>('protocol-handler-conflict', (event) => {
         callback(event.payload);
     }).then((fn) => {
         unlisten = fn;
@@ -210,10 +3071,15 @@ export async function initializeDesktop(): Promise<void> {
     });
 
     // Setup deep link handler
-    onDeepLink((url) => {
-        console.log('Deep link:', url);
+    onDeepLink((link) => {
+        console.log('Deep link:', link.url, '->', link.route);
         // Dispatch custom event for app to handle
-        window.dispatchEvent(new CustomEvent('yula-deep-link', { detail: url }));
+        window.dispatchEvent(new CustomEvent('yula-deep-link', { detail: link }));
+    });
+
+    onDeepLinkError((error) => {
+        console.warn('Unsupported deep link:', error.url, error.reason);
+        window.dispatchEvent(new CustomEvent('yula-deep-link-error', { detail: error }));
     });
 
     console.log('YULA Desktop initialized');